@@ -0,0 +1,160 @@
+use chrono::Local;
+use rusqlite::Result;
+
+use crate::analytics::{self, GoalProgress};
+use crate::db::Database;
+
+/// Lifetime figures that achievements are judged on.
+#[derive(Debug, Default, Clone, Copy)]
+pub struct Totals {
+    pub pomodoros: i64,
+    pub focus_minutes: i64,
+    /// Most consecutive days with a pomodoro, ever
+    pub longest_streak: u32,
+}
+
+impl Totals {
+    pub fn load(db: &Database) -> Result<Self> {
+        Ok(Self {
+            pomodoros: db.lifetime_pomodoros()?,
+            focus_minutes: db.total_focus_minutes()?,
+            longest_streak: analytics::longest_streak(&db.completed_days()?),
+        })
+    }
+}
+
+/// A milestone that unlocks once `progress` reaches `target`.
+pub struct Achievement {
+    /// Key stored in the achievements table, so it must never change
+    pub id: &'static str,
+    pub name: &'static str,
+    /// Body of the notification sent on unlocking
+    pub description: &'static str,
+    target: i64,
+    unit: &'static str,
+    progress: fn(&Totals) -> i64,
+}
+
+impl Achievement {
+    pub fn is_reached(&self, totals: &Totals) -> bool {
+        (self.progress)(totals) >= self.target
+    }
+    
+    /// Progress in the shape the goal bars are drawn from, capped at the
+    /// target so a reached achievement reads 10/10 rather than 230/10.
+    pub fn progress(&self, totals: &Totals) -> GoalProgress {
+        GoalProgress {
+            label: self.name,
+            current: (self.progress)(totals).min(self.target),
+            target: self.target,
+            unit: self.unit,
+        }
+    }
+}
+
+/// Every achievement, in the order they're listed.
+pub const ACHIEVEMENTS: [Achievement; 7] = [
+    Achievement {
+        id: "first_pomodoro",
+        name: "First pomodoro",
+        description: "You finished your first pomodoro.",
+        target: 1,
+        unit: "",
+        progress: |totals| totals.pomodoros,
+    },
+    Achievement {
+        id: "pomodoros_10",
+        name: "10 pomodoros",
+        description: "Ten pomodoros done. It's becoming a habit!",
+        target: 10,
+        unit: "",
+        progress: |totals| totals.pomodoros,
+    },
+    Achievement {
+        id: "pomodoros_100",
+        name: "100 pomodoros",
+        description: "A hundred pomodoros finished.",
+        target: 100,
+        unit: "",
+        progress: |totals| totals.pomodoros,
+    },
+    Achievement {
+        id: "streak_7",
+        name: "7-day streak",
+        description: "A pomodoro every day for a week.",
+        target: 7,
+        unit: "days",
+        progress: |totals| totals.longest_streak as i64,
+    },
+    Achievement {
+        id: "streak_30",
+        name: "30-day streak",
+        description: "A pomodoro every day for 30 days in a row.",
+        target: 30,
+        unit: "days",
+        progress: |totals| totals.longest_streak as i64,
+    },
+    Achievement {
+        id: "focus_hours_10",
+        name: "10 focus hours",
+        description: "Ten hours of focused work logged.",
+        target: 10,
+        unit: "h",
+        progress: |totals| totals.focus_minutes / 60,
+    },
+    Achievement {
+        id: "focus_hours_100",
+        name: "100 focus hours",
+        description: "A hundred hours of focused work logged.",
+        target: 100,
+        unit: "h",
+        progress: |totals| totals.focus_minutes / 60,
+    },
+];
+
+/// Unlocks every achievement reached since the last check and returns the
+/// newly unlocked ones, so the caller can celebrate them. Run it after
+/// saving a session.
+pub fn check(db: &Database) -> Result<Vec<&'static Achievement>> {
+    let totals = Totals::load(db)?;
+    let now = Local::now();
+    
+    let mut unlocked = Vec::new();
+    for achievement in ACHIEVEMENTS.iter().filter(|a| a.is_reached(&totals)) {
+        if db.unlock_achievement(achievement.id, now)? {
+            unlocked.push(achievement);
+        }
+    }
+    
+    Ok(unlocked)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    
+    #[test]
+    fn test_achievement_ids_are_unique() {
+        for (i, achievement) in ACHIEVEMENTS.iter().enumerate() {
+            assert!(ACHIEVEMENTS[..i].iter().all(|other| other.id != achievement.id));
+        }
+    }
+    
+    #[test]
+    fn test_achievements_reached_by_totals() {
+        let totals = Totals { pomodoros: 12, focus_minutes: 599, longest_streak: 7 };
+        let reached: Vec<&str> = ACHIEVEMENTS
+            .iter()
+            .filter(|a| a.is_reached(&totals))
+            .map(|a| a.id)
+            .collect();
+        
+        // 599 minutes is still short of ten hours
+        assert_eq!(reached, vec!["first_pomodoro", "pomodoros_10", "streak_7"]);
+        assert!(!ACHIEVEMENTS.iter().any(|a| a.is_reached(&Totals::default())));
+        
+        let progress = ACHIEVEMENTS[5].progress(&totals);
+        assert_eq!((progress.current, progress.target), (9, 10));
+        assert_eq!(ACHIEVEMENTS[1].progress(&totals).current, 10);
+    }
+}
\ No newline at end of file
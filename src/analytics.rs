@@ -0,0 +1,919 @@
+use chrono::{DateTime, Datelike, Duration, Local, NaiveDate, NaiveDateTime, NaiveTime, Weekday};
+use clap::ValueEnum;
+use crossterm::style::{Color, Stylize};
+use std::{collections::BTreeMap, fmt};
+
+use crate::day;
+
+/// Width of the text progress bars, in characters
+const BAR_WIDTH: usize = 20;
+
+/// Most symbols in a tally row before the rest are summarized as "+N"
+const MAX_TALLY: u64 = 12;
+
+/// Background of each calendar shade, from no focus to the busiest days
+const CALENDAR_COLORS: [Color; 4] = [
+    Color::AnsiValue(236),
+    Color::AnsiValue(22),
+    Color::AnsiValue(28),
+    Color::AnsiValue(40),
+];
+
+/// Weeks shown by `stats --calendar`
+pub const CALENDAR_WEEKS: usize = 12;
+
+/// Calendar shades for terminals without color
+const CALENDAR_SYMBOLS: [char; 4] = [' ', '.', ':', '#'];
+
+/// Consecutive work sessions on one day with only short pauses between them.
+#[derive(Debug, Clone, PartialEq)]
+pub struct FocusBlock {
+    pub start: DateTime<Local>,
+    pub end: DateTime<Local>,
+    pub sessions: usize,
+    pub focus_minutes: i64,
+}
+
+/// Groups work sessions into focus blocks.
+///
+/// A session joins the current block when it starts on the same day and
+/// less than `max_gap` after the block ends. `sessions` must be ordered by
+/// start time. Focus time only counts the sessions themselves, not the gaps.
+pub fn group_into_blocks(
+    sessions: &[(DateTime<Local>, DateTime<Local>)],
+    max_gap: Duration,
+) -> Vec<FocusBlock> {
+    let mut blocks: Vec<FocusBlock> = Vec::new();
+    
+    for &(start, end) in sessions {
+        let minutes = end.signed_duration_since(start).num_minutes();
+        
+        match blocks.last_mut() {
+            Some(block)
+                if day::of(&block.start) == day::of(&start)
+                    && start.signed_duration_since(block.end) < max_gap =>
+            {
+                block.end = block.end.max(end);
+                block.sessions += 1;
+                block.focus_minutes += minutes;
+            }
+            _ => blocks.push(FocusBlock {
+                start,
+                end,
+                sessions: 1,
+                focus_minutes: minutes,
+            }),
+        }
+    }
+    
+    blocks
+}
+
+/// Where the weekly goal stands if the recent pace keeps up.
+#[derive(Debug, PartialEq)]
+pub struct Forecast {
+    /// Pomodoros still needed for the weekly goal
+    pub remaining: u64,
+    /// Average pomodoros per day over the history given
+    pub pace: f64,
+    /// Days needed at that pace, or `None` when the pace is zero
+    pub days_needed: Option<u64>,
+    /// Pomodoros a day needed to reach the goal in the days left
+    pub per_day_needed: u64,
+    pub on_track: bool,
+}
+
+/// Projects the weekly goal from `history`, the pomodoros completed on each
+/// recent day. `days_left` counts today, so it's at least 1.
+pub fn forecast(history: &[u64], done: u64, weekly_goal: u64, days_left: u64) -> Forecast {
+    let remaining = weekly_goal.saturating_sub(done);
+    let pace = if history.is_empty() {
+        0.0
+    } else {
+        history.iter().sum::<u64>() as f64 / history.len() as f64
+    };
+    let days_needed = (pace > 0.0).then(|| (remaining as f64 / pace).ceil() as u64);
+    
+    Forecast {
+        remaining,
+        pace,
+        days_needed,
+        per_day_needed: remaining.div_ceil(days_left.max(1)),
+        on_track: remaining == 0 || days_needed.is_some_and(|days| days <= days_left),
+    }
+}
+
+/// Counts consecutive active days ending today.
+///
+/// A streak isn't broken until a whole day passes without a pomodoro, so
+/// when today has none yet the count starts from yesterday. `days` may be in
+/// any order and contain duplicates.
+pub fn current_streak(days: &[NaiveDate], today: NaiveDate) -> u32 {
+    let mut day = if days.contains(&today) {
+        today
+    } else {
+        match today.pred_opt() {
+            Some(yesterday) => yesterday,
+            None => return 0,
+        }
+    };
+    
+    let mut streak = 0;
+    while days.contains(&day) {
+        streak += 1;
+        day = match day.pred_opt() {
+            Some(previous) => previous,
+            None => break,
+        };
+    }
+    
+    streak
+}
+
+/// Length of the longest run of consecutive active days. `days` may be in
+/// any order and contain duplicates.
+pub fn longest_streak(days: &[NaiveDate]) -> u32 {
+    let mut days = days.to_vec();
+    days.sort();
+    days.dedup();
+    
+    let mut longest = 0;
+    let mut run = 0;
+    let mut previous: Option<NaiveDate> = None;
+    for day in days {
+        run = if previous.and_then(|p| p.succ_opt()) == Some(day) { run + 1 } else { 1 };
+        longest = longest.max(run);
+        previous = Some(day);
+    }
+    
+    longest
+}
+
+/// Whether `check` should nudge you to start: on a weekday, once `remind_at`
+/// has passed, when no session has been recorded yet that day.
+pub fn needs_first_session_nudge(now: NaiveDateTime, remind_at: NaiveTime, sessions_today: i64) -> bool {
+    let weekday = !matches!(now.weekday(), Weekday::Sat | Weekday::Sun);
+    weekday && now.time() >= remind_at && sessions_today == 0
+}
+
+/// Where today's pomodoros stand against a steady pace towards the daily goal.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Pace {
+    OnPace,
+    /// Pomodoros short of where the pace would be
+    Behind(u64),
+}
+
+impl fmt::Display for Pace {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Pace::OnPace => f.write_str("On pace ✓"),
+            Pace::Behind(by) => write!(f, "Behind by {}", by),
+        }
+    }
+}
+
+/// Pomodoros a steady pace would have finished by `now`, spreading `goal`
+/// evenly over the working hours from `start` to `end`. Only whole
+/// pomodoros are expected, so you're never behind by a fraction.
+pub fn expected_by(goal: u64, now: NaiveTime, start: NaiveTime, end: NaiveTime) -> u64 {
+    if now <= start {
+        return 0;
+    }
+    if now >= end || end <= start {
+        return goal;
+    }
+    
+    let share = (now - start).num_seconds() as f64 / (end - start).num_seconds() as f64;
+    (goal as f64 * share).floor() as u64
+}
+
+/// Compares `done` with `expected_by` for the pacing shown in the timer.
+pub fn daily_pace(done: u64, goal: u64, now: NaiveTime, start: NaiveTime, end: NaiveTime) -> Pace {
+    match expected_by(goal, now, start, end).saturating_sub(done) {
+        0 => Pace::OnPace,
+        by => Pace::Behind(by),
+    }
+}
+
+/// Progress towards one goal on the `today` dashboard.
+pub struct GoalProgress {
+    pub label: &'static str,
+    pub current: i64,
+    pub target: i64,
+    pub unit: &'static str,
+}
+
+impl GoalProgress {
+    pub fn is_met(&self) -> bool {
+        self.current >= self.target
+    }
+    
+    pub fn percent(&self) -> i64 {
+        if self.target <= 0 {
+            100
+        } else {
+            self.current * 100 / self.target
+        }
+    }
+}
+
+/// Renders goals as aligned text progress bars, one per line.
+///
+/// `emoji` selects the check marks; without it plain ASCII is used.
+pub fn render_goals(goals: &[GoalProgress], emoji: bool) -> String {
+    let amounts: Vec<String> = goals
+        .iter()
+        .map(|goal| format!("{}/{} {}", goal.current, goal.target, goal.unit).trim_end().to_string())
+        .collect();
+    
+    let label_width = goals.iter().map(|g| g.label.len()).max().unwrap_or(0);
+    let amount_width = amounts.iter().map(|a| a.chars().count()).max().unwrap_or(0);
+    
+    goals
+        .iter()
+        .zip(&amounts)
+        .map(|(goal, amount)| {
+            let filled = (goal.percent().clamp(0, 100) as usize * BAR_WIDTH) / 100;
+            let (full, empty) = if emoji { ("█", "░") } else { ("#", "-") };
+            let bar = format!("{}{}", full.repeat(filled), empty.repeat(BAR_WIDTH - filled));
+            
+            let mark = match (goal.is_met(), emoji) {
+                (true, true) => "✅",
+                (false, true) => "⬜",
+                (true, false) => "[x]",
+                (false, false) => "[ ]",
+            };
+            
+            format!(
+                "{:<label_width$}  [{}] {:>4}%  {:<amount_width$}  {}",
+                goal.label,
+                bar,
+                goal.percent(),
+                amount,
+                mark,
+            )
+        })
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+/// Row of symbols showing `count` out of `goal`, e.g. "🍅🍅🍅⚪⚪".
+///
+/// Pomodoros past the goal (or all of them, without a goal) are shown
+/// filled, up to `MAX_TALLY` symbols, with any beyond that noted as "+N".
+pub fn render_tally(count: u64, goal: u64, filled: &str, empty: &str) -> String {
+    let width = if goal > 0 { goal.max(count) } else { count }.min(MAX_TALLY);
+    let shown = count.min(width);
+    let empties = goal.min(width).saturating_sub(shown);
+    
+    let mut row = format!("{}{}", filled.repeat(shown as usize), empty.repeat(empties as usize));
+    if count > shown {
+        row.push_str(&format!(" +{}", count - shown));
+    }
+    
+    row
+}
+
+/// Horizontal alignment of a table column.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Align {
+    Left,
+    Right,
+}
+
+/// One table cell, optionally colored.
+pub struct Cell {
+    text: String,
+    color: Option<Color>,
+}
+
+impl Cell {
+    pub fn colored(text: impl Into<String>, color: Color) -> Self {
+        Cell { text: text.into(), color: Some(color) }
+    }
+}
+
+impl From<String> for Cell {
+    fn from(text: String) -> Self {
+        Cell { text, color: None }
+    }
+}
+
+impl From<&str> for Cell {
+    fn from(text: &str) -> Self {
+        Cell::from(text.to_string())
+    }
+}
+
+/// Plain-text table with padded columns for command output.
+///
+/// Colors are only written when enabled with `color`, which callers should
+/// tie to whether stdout is a terminal.
+#[derive(Default)]
+pub struct Table {
+    header: Option<Vec<String>>,
+    align: Vec<Align>,
+    rows: Vec<Vec<Cell>>,
+    color: bool,
+}
+
+impl Table {
+    pub fn new() -> Self {
+        Self::default()
+    }
+    
+    /// Adds a header row, underlined with dashes.
+    pub fn header(mut self, titles: &[&str]) -> Self {
+        self.header = Some(titles.iter().map(|t| t.to_string()).collect());
+        self
+    }
+    
+    /// Sets a column's alignment; columns are left-aligned by default.
+    pub fn align(mut self, column: usize, align: Align) -> Self {
+        if self.align.len() <= column {
+            self.align.resize(column + 1, Align::Left);
+        }
+        self.align[column] = align;
+        self
+    }
+    
+    pub fn color(mut self, enabled: bool) -> Self {
+        self.color = enabled;
+        self
+    }
+    
+    pub fn row(&mut self, cells: Vec<Cell>) {
+        self.rows.push(cells);
+    }
+    
+    pub fn render(&self) -> String {
+        let columns = self
+            .rows
+            .iter()
+            .map(Vec::len)
+            .chain(self.header.as_ref().map(Vec::len))
+            .max()
+            .unwrap_or(0);
+        
+        let mut widths = vec![0; columns];
+        let all_text = self
+            .header
+            .iter()
+            .flat_map(|header| header.iter().enumerate().map(|(i, t)| (i, t.as_str())))
+            .chain(self.rows.iter().flat_map(|row| row.iter().enumerate().map(|(i, c)| (i, c.text.as_str()))));
+        for (column, text) in all_text {
+            widths[column] = widths[column].max(text.chars().count());
+        }
+        
+        let mut lines = Vec::new();
+        if let Some(header) = &self.header {
+            let cells: Vec<Cell> = header.iter().map(|t| Cell::from(t.as_str())).collect();
+            lines.push(self.render_row(&cells, &widths));
+            lines.push(widths.iter().map(|w| "-".repeat(*w)).collect::<Vec<_>>().join("  "));
+        }
+        for row in &self.rows {
+            lines.push(self.render_row(row, &widths));
+        }
+        
+        lines.join("\n")
+    }
+    
+    fn render_row(&self, cells: &[Cell], widths: &[usize]) -> String {
+        let padded: Vec<String> = cells
+            .iter()
+            .enumerate()
+            .map(|(column, cell)| {
+                let width = widths[column];
+                // Pad before coloring so escape codes don't count towards the width
+                let text = match self.align.get(column).copied().unwrap_or(Align::Left) {
+                    Align::Left => format!("{:<width$}", cell.text),
+                    Align::Right => format!("{:>width$}", cell.text),
+                };
+                
+                match cell.color {
+                    Some(color) if self.color => text.with(color).to_string(),
+                    _ => text,
+                }
+            })
+            .collect();
+        
+        padded.join("  ").trim_end().to_string()
+    }
+}
+
+/// Time window for `stats --top-tags` and `leaderboard`.
+#[derive(Debug, Clone, Copy, PartialEq, ValueEnum)]
+pub enum Period {
+    /// The last 7 days, including today
+    Week,
+    /// The last 30 days
+    Month,
+    /// The last 365 days
+    Year,
+    /// Every recorded session
+    All,
+}
+
+impl Period {
+    /// First day included in the period, or `None` for no limit.
+    pub fn first_day(self, today: NaiveDate) -> Option<NaiveDate> {
+        let days = match self {
+            Period::Week => 7,
+            Period::Month => 30,
+            Period::Year => 365,
+            Period::All => return None,
+        };
+        
+        today.checked_sub_signed(Duration::days(days - 1))
+    }
+    
+    /// The start of the period's first day, or `None` for no limit.
+    pub fn start(self, today: NaiveDate) -> Option<DateTime<Local>> {
+        self.first_day(today).map(|day| day::bounds(day).0)
+    }
+    
+    pub fn label(self) -> &'static str {
+        match self {
+            Period::Week => "last 7 days",
+            Period::Month => "last 30 days",
+            Period::Year => "last 365 days",
+            Period::All => "all time",
+        }
+    }
+}
+
+/// Focus-to-break ratios above this leave too little rest
+const MAX_HEALTHY_RATIO: f64 = 8.0;
+
+/// Focus-to-break ratios below this are mostly breaks
+const MIN_HEALTHY_RATIO: f64 = 2.0;
+
+/// How focus time compares with break time.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum RestBalance {
+    TooLittleRest,
+    Balanced,
+    MostlyBreaks,
+}
+
+/// Minutes of focus per minute of break, or `None` without any break time.
+pub fn focus_ratio(work_minutes: i64, break_minutes: i64) -> Option<f64> {
+    (break_minutes > 0).then(|| work_minutes as f64 / break_minutes as f64)
+}
+
+/// Classic 25/5 pomodoros give a ratio of 5; far from that in either
+/// direction is worth pointing out.
+pub fn rest_balance(ratio: f64) -> RestBalance {
+    if ratio > MAX_HEALTHY_RATIO {
+        RestBalance::TooLittleRest
+    } else if ratio < MIN_HEALTHY_RATIO {
+        RestBalance::MostlyBreaks
+    } else {
+        RestBalance::Balanced
+    }
+}
+
+/// Highest focus rating `start --rate` accepts
+const MAX_ENERGY: u8 = 5;
+
+/// A 0-100 score for how well work sessions went: the share that were
+/// completed, or `None` without sessions.
+///
+/// `sessions` holds each session's completion and focus rating. With
+/// `use_energy`, a rated session only counts as far as its rating, so a
+/// completed session rated 3 of 5 adds 0.6 rather than 1. Unrated sessions
+/// count as they would without ratings.
+pub fn focus_score(sessions: &[(bool, Option<u8>)], use_energy: bool) -> Option<u32> {
+    if sessions.is_empty() {
+        return None;
+    }
+    
+    let total: f64 = sessions
+        .iter()
+        .filter(|(completed, _)| *completed)
+        .map(|(_, energy)| match energy {
+            Some(rating) if use_energy => f64::from((*rating).clamp(1, MAX_ENERGY)) / f64::from(MAX_ENERGY),
+            _ => 1.0,
+        })
+        .sum();
+    
+    Some((total * 100.0 / sessions.len() as f64).round() as u32)
+}
+
+/// Completion rates closer than this many percentage points count as steady
+const STEADY_TREND_POINTS: f64 = 1.0;
+
+/// Which way the completion rate is heading.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Trend {
+    Improving,
+    Steady,
+    Declining,
+}
+
+/// Share of `total` sessions that were completed, in percent, or `None`
+/// without any sessions.
+pub fn completion_rate(completed: i64, total: i64) -> Option<f64> {
+    (total > 0).then(|| completed as f64 * 100.0 / total as f64)
+}
+
+/// Compares a recent completion rate with an earlier one, both in percent.
+pub fn completion_trend(recent: f64, previous: f64) -> Trend {
+    let delta = recent - previous;
+    if delta >= STEADY_TREND_POINTS {
+        Trend::Improving
+    } else if delta <= -STEADY_TREND_POINTS {
+        Trend::Declining
+    } else {
+        Trend::Steady
+    }
+}
+
+/// Formats the change between two completion rates, e.g. "↑ +12 pts (80% vs 68%)".
+pub fn format_trend(recent: f64, previous: f64) -> String {
+    let arrow = match completion_trend(recent, previous) {
+        Trend::Improving => "↑",
+        Trend::Steady => "→",
+        Trend::Declining => "↓",
+    };
+    format!("{} {:+.0} pts ({:.0}% vs {:.0}%)", arrow, recent - previous, recent, previous)
+}
+
+/// Renders a ranked list of `(label, minutes)` rows with bars scaled to the first row.
+///
+/// Rows with equal minutes share a rank. `rows` must already be sorted.
+pub fn render_leaderboard(rows: &[(String, i64)], emoji: bool) -> String {
+    let top = rows.first().map(|(_, minutes)| *minutes).unwrap_or(0).max(1);
+    let label_width = rows.iter().map(|(label, _)| label.chars().count()).max().unwrap_or(0);
+    let (full, empty) = if emoji { ("█", "░") } else { ("#", "-") };
+    
+    let mut rank = 0;
+    rows.iter()
+        .enumerate()
+        .map(|(i, (label, minutes))| {
+            if i == 0 || rows[i - 1].1 != *minutes {
+                rank = i + 1;
+            }
+            
+            let filled = (minutes.clamp(&0, &top) * BAR_WIDTH as i64 / top) as usize;
+            format!(
+                "{:>2}. {:<label_width$}  [{}{}] {:>4}m",
+                rank,
+                label,
+                full.repeat(filled),
+                empty.repeat(BAR_WIDTH - filled),
+                minutes,
+            )
+        })
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+/// Shade of a day in the calendar, from 0 for no focus to 3 for days
+/// within the top third of the busiest day's `max` minutes.
+pub fn calendar_shade(minutes: i64, max: i64) -> usize {
+    if minutes <= 0 || max <= 0 {
+        return 0;
+    }
+    ((minutes * 3 + max - 1) / max).clamp(1, 3) as usize
+}
+
+/// Renders focus `minutes` per day as a contribution grid for the `weeks`
+/// weeks up to the one holding `today`: a column per week from Monday and
+/// a row per weekday, with month names over the week each month starts in.
+pub fn render_calendar(minutes: &BTreeMap<NaiveDate, i64>, today: NaiveDate, weeks: usize, color: bool) -> String {
+    let this_monday = today - Duration::days(today.weekday().num_days_from_monday() as i64);
+    let first = this_monday - Duration::weeks(weeks.saturating_sub(1) as i64);
+    let max = minutes.range(first..=today).map(|(_, &m)| m).max().unwrap_or(0);
+    
+    let cell = |shade: usize| {
+        if color {
+            "  ".on(CALENDAR_COLORS[shade]).to_string()
+        } else {
+            format!("{} ", CALENDAR_SYMBOLS[shade])
+        }
+    };
+    
+    // Each cell is two characters wide, so a name may cover the next week
+    let mut months = String::from("    ");
+    for week in 0..weeks {
+        let monday = first + Duration::weeks(week as i64);
+        let column = 4 + week * 2;
+        let new_month = week == 0 || (monday - Duration::weeks(1)).month() != monday.month();
+        if new_month && months.chars().count() <= column {
+            months = format!("{:<column$}{}", months, month_name(monday.month()));
+        }
+    }
+    
+    let mut lines = vec![months];
+    for weekday in 0..7 {
+        let label = match weekday {
+            0 => "Mon",
+            2 => "Wed",
+            4 => "Fri",
+            _ => "",
+        };
+        let mut line = format!("{:<4}", label);
+        for week in 0..weeks {
+            let day = first + Duration::days(week as i64 * 7 + weekday);
+            if day > today {
+                break;
+            }
+            line.push_str(&cell(calendar_shade(minutes.get(&day).copied().unwrap_or(0), max)));
+        }
+        lines.push(line.trim_end().to_string());
+    }
+    lines.push(format!("    Less {}More", (0..4).map(cell).collect::<String>()));
+    
+    lines.join("\n")
+}
+
+fn month_name(month: u32) -> &'static str {
+    const NAMES: [&str; 12] = ["Jan", "Feb", "Mar", "Apr", "May", "Jun", "Jul", "Aug", "Sep", "Oct", "Nov", "Dec"];
+    NAMES[(month as usize + 11) % 12]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+    
+    fn at(hour: u32, min: u32, sec: u32) -> DateTime<Local> {
+        Local.with_ymd_and_hms(2024, 3, 14, hour, min, sec).unwrap()
+    }
+    
+    #[test]
+    fn test_blocks_split_on_gap_threshold() {
+        let sessions = vec![
+            (at(9, 0, 0), at(9, 25, 0)),
+            // 9:59 after the previous session: joins
+            (at(9, 34, 59), at(9, 59, 59)),
+            // Exactly 10:00 after the previous session: new block
+            (at(10, 9, 59), at(10, 34, 59)),
+        ];
+        
+        let blocks = group_into_blocks(&sessions, Duration::minutes(10));
+        
+        assert_eq!(blocks.len(), 2);
+        assert_eq!(blocks[0].sessions, 2);
+        assert_eq!(blocks[0].start, at(9, 0, 0));
+        assert_eq!(blocks[0].end, at(9, 59, 59));
+        assert_eq!(blocks[0].focus_minutes, 50);
+        assert_eq!(blocks[1].sessions, 1);
+    }
+    
+    #[test]
+    fn test_blocks_boundary_cases() {
+        assert!(group_into_blocks(&[], Duration::minutes(10)).is_empty());
+        
+        // Overlapping sessions join and keep the later end
+        let overlapping = vec![
+            (at(9, 0, 0), at(9, 25, 0)),
+            (at(9, 20, 0), at(9, 45, 0)),
+        ];
+        let blocks = group_into_blocks(&overlapping, Duration::minutes(10));
+        assert_eq!(blocks.len(), 1);
+        assert_eq!(blocks[0].end, at(9, 45, 0));
+        
+        // Sessions on different days never share a block
+        let next_day = Local.with_ymd_and_hms(2024, 3, 15, 0, 1, 0).unwrap();
+        let across_midnight = vec![
+            (at(23, 30, 0), at(23, 55, 0)),
+            (next_day, next_day + Duration::minutes(25)),
+        ];
+        assert_eq!(group_into_blocks(&across_midnight, Duration::minutes(10)).len(), 2);
+    }
+    
+    #[test]
+    fn test_current_streak() {
+        let today = NaiveDate::from_ymd_opt(2024, 3, 14).unwrap();
+        let day = |d| NaiveDate::from_ymd_opt(2024, 3, d).unwrap();
+        
+        assert_eq!(current_streak(&[], today), 0);
+        assert_eq!(current_streak(&[day(12), day(13), day(14), day(14)], today), 3);
+        
+        // Nothing yet today: the streak from yesterday still counts
+        assert_eq!(current_streak(&[day(11), day(12), day(13)], today), 3);
+        
+        // A gap of a full day resets it
+        assert_eq!(current_streak(&[day(10), day(11), day(12)], today), 0);
+    }
+    
+    #[test]
+    fn test_longest_streak() {
+        let day = |d| NaiveDate::from_ymd_opt(2024, 3, d).unwrap();
+        
+        assert_eq!(longest_streak(&[]), 0);
+        assert_eq!(longest_streak(&[day(1)]), 1);
+        assert_eq!(longest_streak(&[day(9), day(2), day(3), day(3), day(4), day(8)]), 3);
+    }
+    
+    #[test]
+    fn test_render_goals() {
+        let goals = [
+            GoalProgress { label: "Pomodoros", current: 2, target: 8, unit: "" },
+            GoalProgress { label: "Streak", current: 9, target: 7, unit: "days" },
+        ];
+        
+        let plain = render_goals(&goals, false);
+        let lines: Vec<&str> = plain.lines().collect();
+        assert_eq!(lines[0], "Pomodoros  [#####---------------]   25%  2/8       [ ]");
+        assert_eq!(lines[1], "Streak     [####################]  128%  9/7 days  [x]");
+        
+        let fancy = render_goals(&goals, true);
+        assert!(fancy.contains("⬜"));
+        assert!(fancy.contains("✅"));
+    }
+    
+    #[test]
+    fn test_render_leaderboard_shares_tied_ranks() {
+        let rows = vec![
+            ("docs".to_string(), 50),
+            ("rust".to_string(), 50),
+            ("(untagged)".to_string(), 25),
+        ];
+        
+        let plain = render_leaderboard(&rows, false);
+        let lines: Vec<&str> = plain.lines().collect();
+        assert_eq!(lines[0], " 1. docs        [####################]   50m");
+        assert_eq!(lines[1], " 1. rust        [####################]   50m");
+        assert_eq!(lines[2], " 3. (untagged)  [##########----------]   25m");
+        
+        assert_eq!(render_leaderboard(&[], false), "");
+    }
+    
+    #[test]
+    fn test_period_first_day() {
+        let today = NaiveDate::from_ymd_opt(2024, 3, 14).unwrap();
+        assert_eq!(Period::Week.first_day(today), NaiveDate::from_ymd_opt(2024, 3, 8));
+        assert_eq!(Period::All.first_day(today), None);
+    }
+    
+    #[test]
+    fn test_table_aligns_columns() {
+        let mut table = Table::new()
+            .header(&["Day", "Sessions"])
+            .align(1, Align::Right);
+        table.row(vec!["2024-03-14".into(), "3".into()]);
+        table.row(vec!["Mon".into(), Cell::colored("12", Color::Green)]);
+        
+        let plain = table.render();
+        let lines: Vec<&str> = plain.lines().collect();
+        assert_eq!(lines[0], "Day         Sessions");
+        assert_eq!(lines[1], "----------  --------");
+        assert_eq!(lines[2], "2024-03-14         3");
+        assert_eq!(lines[3], "Mon               12");
+        
+        // Colors are opt-in and don't change the padding
+        let colored = table.color(true).render();
+        assert!(colored.contains("\u{1b}["));
+        assert!(colored.contains("      12"));
+    }
+    
+    #[test]
+    fn test_render_tally() {
+        assert_eq!(render_tally(3, 5, "#", "."), "###..");
+        assert_eq!(render_tally(0, 3, "#", "."), "...");
+        assert_eq!(render_tally(7, 5, "#", "."), "#######");
+        assert_eq!(render_tally(2, 0, "#", "."), "##");
+        
+        // Long rows are capped
+        assert_eq!(render_tally(15, 8, "#", "."), format!("{} +3", "#".repeat(12)));
+        assert_eq!(render_tally(1, 20, "#", "."), format!("#{}", ".".repeat(11)));
+    }
+    
+    #[test]
+    fn test_first_session_nudge() {
+        // 2024-03-14 is a Thursday, 2024-03-16 a Saturday
+        let at = |day: u32, hour: u32| NaiveDate::from_ymd_opt(2024, 3, day).unwrap().and_hms_opt(hour, 0, 0).unwrap();
+        let nine = NaiveTime::from_hms_opt(9, 0, 0).unwrap();
+        
+        assert!(needs_first_session_nudge(at(14, 9), nine, 0));
+        assert!(!needs_first_session_nudge(at(14, 8), nine, 0));
+        assert!(!needs_first_session_nudge(at(14, 10), nine, 1));
+        assert!(!needs_first_session_nudge(at(16, 10), nine, 0));
+    }
+    
+    #[test]
+    fn test_focus_ratio_and_balance() {
+        assert_eq!(focus_ratio(100, 20), Some(5.0));
+        assert_eq!(focus_ratio(100, 0), None);
+        
+        assert_eq!(rest_balance(5.0), RestBalance::Balanced);
+        assert_eq!(rest_balance(8.0), RestBalance::Balanced);
+        assert_eq!(rest_balance(8.5), RestBalance::TooLittleRest);
+        assert_eq!(rest_balance(1.5), RestBalance::MostlyBreaks);
+    }
+    
+    #[test]
+    fn test_focus_score_with_and_without_energy() {
+        assert_eq!(focus_score(&[], true), None);
+        
+        // No ratings: purely the completion rate, whatever the setting
+        let unrated = [(true, None), (true, None), (false, None), (true, None)];
+        assert_eq!(focus_score(&unrated, true), Some(75));
+        assert_eq!(focus_score(&unrated, false), Some(75));
+        
+        // Ratings scale completed sessions only; unfinished ones add nothing
+        let rated = [(true, Some(5)), (true, Some(3)), (false, Some(5)), (true, None)];
+        assert_eq!(focus_score(&rated, true), Some(65));
+        assert_eq!(focus_score(&rated, false), Some(75));
+        
+        // Out-of-range ratings from old or edited rows are clamped
+        assert_eq!(focus_score(&[(true, Some(9)), (true, Some(0))], true), Some(60));
+    }
+    
+    #[test]
+    fn test_completion_trend() {
+        assert_eq!(completion_rate(3, 4), Some(75.0));
+        assert_eq!(completion_rate(0, 0), None);
+        
+        assert_eq!(completion_trend(80.0, 68.0), Trend::Improving);
+        assert_eq!(completion_trend(68.0, 80.0), Trend::Declining);
+        assert_eq!(completion_trend(70.5, 70.0), Trend::Steady);
+        
+        assert_eq!(format_trend(80.0, 68.0), "↑ +12 pts (80% vs 68%)");
+        assert_eq!(format_trend(50.0, 75.0), "↓ -25 pts (50% vs 75%)");
+        assert_eq!(format_trend(60.0, 60.0), "→ +0 pts (60% vs 60%)");
+    }
+    
+    #[test]
+    fn test_forecast_projects_weekly_goal() {
+        // 4 a day lately, 20 of 40 done with 4 days left: 5 days needed
+        let plan = forecast(&[4, 6, 2, 4], 20, 40, 4);
+        assert_eq!(plan.remaining, 20);
+        assert_eq!(plan.pace, 4.0);
+        assert_eq!(plan.days_needed, Some(5));
+        assert_eq!(plan.per_day_needed, 5);
+        assert!(!plan.on_track);
+        
+        assert!(forecast(&[8, 8], 20, 40, 4).on_track);
+        
+        // Goal already met
+        let done = forecast(&[1], 45, 40, 2);
+        assert_eq!((done.remaining, done.per_day_needed), (0, 0));
+        assert!(done.on_track);
+        
+        // No recent history
+        let idle = forecast(&[0, 0], 0, 10, 3);
+        assert_eq!(idle.days_needed, None);
+        assert!(!idle.on_track);
+        assert_eq!(forecast(&[], 0, 10, 3).pace, 0.0);
+    }
+    
+    #[test]
+    fn test_daily_pace_is_linear_over_working_hours() {
+        let at = |h: u32, m: u32| NaiveTime::from_hms_opt(h, m, 0).unwrap();
+        let (start, end) = (at(9, 0), at(17, 0));
+        
+        assert_eq!(expected_by(8, at(7, 30), start, end), 0);
+        assert_eq!(expected_by(8, at(9, 0), start, end), 0);
+        assert_eq!(expected_by(8, at(13, 0), start, end), 4);
+        // 5.6 pomodoros' worth of the day has gone by, so 5 are expected
+        assert_eq!(expected_by(8, at(14, 36), start, end), 5);
+        assert_eq!(expected_by(8, at(15, 0), start, end), 6);
+        assert_eq!(expected_by(8, at(22, 0), start, end), 8);
+        
+        assert_eq!(daily_pace(5, 8, at(15, 0), start, end), Pace::Behind(1));
+        assert_eq!(daily_pace(6, 8, at(15, 0), start, end), Pace::OnPace);
+        assert_eq!(daily_pace(9, 8, at(15, 0), start, end), Pace::OnPace);
+        assert_eq!(daily_pace(0, 8, at(23, 0), start, end).to_string(), "Behind by 8");
+        assert_eq!(Pace::OnPace.to_string(), "On pace ✓");
+    }
+    
+    #[test]
+    fn test_calendar_shades_scale_to_busiest_day() {
+        assert_eq!(calendar_shade(0, 120), 0);
+        assert_eq!(calendar_shade(10, 0), 0);
+        assert_eq!(calendar_shade(1, 120), 1);
+        assert_eq!(calendar_shade(40, 120), 1);
+        assert_eq!(calendar_shade(41, 120), 2);
+        assert_eq!(calendar_shade(80, 120), 2);
+        assert_eq!(calendar_shade(120, 120), 3);
+    }
+    
+    #[test]
+    fn test_calendar_grid_layout() {
+        let day = |d: u32| NaiveDate::from_ymd_opt(2024, 3, d).unwrap();
+        let minutes = BTreeMap::from([(day(4), 100), (day(5), 30), (day(12), 60), (day(13), 0), (day(20), 500)]);
+        
+        // A Wednesday: later days are left off, as is anything after today
+        let grid = render_calendar(&minutes, day(13), 2, false);
+        assert_eq!(grid, [
+            "    Mar",
+            "Mon #",
+            "    . :",
+            "Wed",
+            "",
+            "Fri",
+            "",
+            "",
+            "    Less   . : # More",
+        ].join("\n"));
+        
+        // Month names go over the week the month starts in
+        let spring = render_calendar(&BTreeMap::new(), NaiveDate::from_ymd_opt(2024, 4, 10).unwrap(), 4, false);
+        assert_eq!(spring.lines().next(), Some("    Mar Apr"));
+    }
+}
\ No newline at end of file
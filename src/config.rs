@@ -0,0 +1,668 @@
+use chrono::{DateTime, Local, NaiveDate, NaiveTime};
+use serde::{Deserialize, Serialize};
+use std::{
+    collections::{BTreeMap, BTreeSet},
+    env,
+    error::Error,
+    fmt::{self, Write},
+    fs,
+    path::PathBuf,
+};
+
+use crate::profile::{self, Profile};
+use crate::suggest::suggest;
+use crate::technique::TechniqueKind;
+use crate::timer::{self, BreakScaling, PausedCompletion};
+use crate::ui::{GaugeDirection, GaugeLabel};
+
+/// Work and break lengths in minutes, selectable by name with `--preset`.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct Preset {
+    pub work: u64,
+    #[serde(rename = "break")]
+    pub break_time: u64,
+}
+
+/// Environment variable overriding the default work length
+pub const WORK_ENV: &str = "RUSTY_POMODORO_WORK";
+
+/// Environment variable overriding the default break length
+pub const BREAK_ENV: &str = "RUSTY_POMODORO_BREAK";
+
+/// Longest allowed `tick_ms`; slower ticks would leave keys unanswered for too long
+const MAX_TICK_MS: u64 = 10_000;
+
+/// Presets available without any configuration
+const BUILTIN_PRESETS: [(&str, Preset); 3] = [
+    ("classic", Preset { work: 25, break_time: 5 }),
+    ("deepwork", Preset { work: 50, break_time: 10 }),
+    ("short", Preset { work: 15, break_time: 3 }),
+];
+
+/// Where an effective setting came from, for `config show`.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Source {
+    Default,
+    File,
+    Env,
+    Cli,
+    /// Remembered from the previous `start`
+    LastRun,
+}
+
+impl fmt::Display for Source {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str(match self {
+            Source::Default => "default",
+            Source::File => "file",
+            Source::Env => "env",
+            Source::Cli => "cli",
+            Source::LastRun => "last run",
+        })
+    }
+}
+
+/// A setting's effective value and where it came from.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Sourced<T> {
+    pub value: T,
+    pub source: Source,
+}
+
+impl<T> Sourced<T> {
+    /// The first of `candidates` that is set, in order, or else `fallback`.
+    pub fn first_of<const N: usize>(candidates: [(Option<T>, Source); N], fallback: Sourced<T>) -> Self {
+        candidates
+            .into_iter()
+            .find_map(|(value, source)| value.map(|value| Sourced { value, source }))
+            .unwrap_or(fallback)
+    }
+}
+
+/// User settings loaded from `config.toml` in the platform config directory.
+///
+/// Every field has a default, so the file only needs the keys a user wants
+/// to change.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Config {
+    /// Notify when a work session ends
+    pub notify_on_work_complete: bool,
+    
+    /// Notify when a break ends
+    pub notify_on_break_complete: bool,
+    
+    /// Notify when a work phase begins, including the first one
+    pub notify_on_work_start: bool,
+    
+    /// Notify when a break begins
+    pub notify_on_break_start: bool,
+    
+    /// Play a chime when a phase ends (needs the `sound` feature)
+    pub sound: bool,
+    
+    /// Delete sessions older than this many days on startup (unset keeps everything)
+    pub retention_days: Option<u32>,
+    
+    /// User-defined presets, which take precedence over built-in ones
+    pub presets: BTreeMap<String, Preset>,
+    
+    /// Preset used by `start` when no durations are given
+    pub preset: Option<String>,
+    
+    /// Custom rhythm of phases such as `["work:25", "break:5", "work:25",
+    /// "longbreak:20"]`, looped; replaces the preset when set
+    pub sequence: Option<Vec<String>>,
+    
+    /// Pomodoros to complete each day
+    pub daily_goal: u32,
+    
+    /// Minutes of focus time to log each day
+    pub daily_focus_goal: u32,
+    
+    /// Start of the working day ("HH:MM"); the timer paces the daily goal
+    /// evenly from here to `workday_end`
+    pub workday_start: String,
+    
+    /// End of the working day ("HH:MM"), by which the daily goal should be met
+    pub workday_end: String,
+    
+    /// Hour (0-23) each day starts at. Sessions before it count towards the
+    /// previous day's totals, goals and streak.
+    pub day_start_hour: u32,
+    
+    /// Consecutive active days to aim for
+    pub streak_goal: u32,
+    
+    /// Pomodoros to complete each week, counted from Monday
+    pub weekly_goal: u32,
+    
+    /// Pause work sessions while the screen is locked
+    pub pause_on_lock: bool,
+    
+    /// Largest share of a work phase that may be spent auto-paused (0.0-1.0)
+    /// for it to still count as a pomodoro
+    pub max_auto_pause: f64,
+    
+    /// Pomodoro variant to use unless `--technique` is given
+    pub technique: TechniqueKind,
+    
+    /// Milliseconds between timer ticks. Higher values wake the CPU less often
+    /// at the cost of slower key response and up to one tick of lag before a
+    /// finished phase is noticed.
+    pub tick_ms: u64,
+    
+    /// Local time ("HH:MM") at which to send a summary of the day and end
+    /// the running session (off when unset)
+    pub end_of_day: Option<String>,
+    
+    /// Command to run after each recorded session, e.g.
+    /// `"notify-send 'Logged {minutes} min on {tag}'"`
+    pub on_session_end: Option<String>,
+    
+    /// Tag untagged work sessions with the foreground window's title
+    pub auto_tag: bool,
+    
+    /// Add the unused part of a skipped break to the next break
+    pub bank_skipped_breaks: bool,
+    
+    /// Most break time that can be banked, in minutes
+    pub max_banked_minutes: u64,
+    
+    /// Reuse the last run's durations when `start` is given none
+    pub remember_last: bool,
+    
+    /// Minimum seconds between notifications; ones that come sooner are
+    /// dropped (0 sends them all)
+    pub notification_min_interval: u64,
+    
+    /// Bright, bold timer colors for low-vision users
+    pub high_contrast: bool,
+    
+    /// Describe every phase change in a notification instead of relying on color
+    pub announce: bool,
+    
+    /// Symbol for a finished pomodoro in the timer's progress row
+    /// (default 🍅, or `*` with `--no-emoji`)
+    pub filled_symbol: Option<String>,
+    
+    /// Symbol for a pomodoro still to go (default ⚪, or `.` with `--no-emoji`)
+    pub empty_symbol: Option<String>,
+    
+    /// Unfinished work sessions are only recorded if they ran longer than
+    /// this many whole seconds
+    pub min_session_seconds: u64,
+    
+    /// Local time ("HH:MM") after which `check` reminds you on weekdays if
+    /// you haven't started a session yet (off when unset)
+    pub first_session_reminder: Option<String>,
+    
+    /// Profile used when `--profile` isn't given. Only read from the default
+    /// profile's config file.
+    pub profile: Option<String>,
+    
+    /// Exit after the timer has sat stopped, with no key pressed, for this
+    /// many minutes (off when unset). Pauses never count
+    pub auto_quit_minutes: Option<u64>,
+    
+    /// Show the running time of all phases since the timer started, next to
+    /// today's pomodoros. Paused time isn't included
+    pub show_cycle_time: bool,
+    
+    /// What to do when a phase's time runs out while the timer is paused:
+    /// wait for resume ("defer") or move on to the next phase, paused ("complete")
+    pub paused_at_end: PausedCompletion,
+    
+    /// What `+` and `-` on a work phase do to the break after it: nothing
+    /// ("fixed") or scale it to keep the work:break ratio ("proportional")
+    pub break_scaling: BreakScaling,
+    
+    /// Let `start --rate` focus ratings weigh on the focus score in `stats`;
+    /// when off the score is just the completion rate
+    pub energy_in_focus_score: bool,
+    
+    /// Text on the timer's gauge, with `{remaining}`, `{elapsed}`, `{total}`
+    /// and `{percent}` filled in. Flow sessions always count up instead
+    pub gauge_label: String,
+    
+    /// Whether the gauge fills up as time passes ("fill") or starts full
+    /// and empties ("drain")
+    pub gauge_direction: GaugeDirection,
+    
+    /// Keep the session database encrypted; needs the `encryption` feature.
+    /// An existing plain database is encrypted the next time it's opened
+    pub encrypt_database: bool,
+    
+    /// Passphrase for the encrypted database. Unless set here or in
+    /// RUSTY_POMODORO_PASSPHRASE, every command asks for it
+    pub database_passphrase: Option<String>,
+    
+    /// Minutes between "stretch and blink" reminders during a work phase,
+    /// which keeps running (off when unset)
+    pub micro_break_interval: Option<u64>,
+    
+    /// Start the next work phase as soon as a break ends; when off it waits
+    /// for `p` or `resume`
+    pub auto_start_work: bool,
+    
+    /// Minutes after a break ends, with work not yet started, before a
+    /// reminder to get going (off when unset; needs auto_start_work off)
+    pub break_overrun_reminder: Option<u64>,
+    
+    /// Have `start` pick up a work phase quit part-way through earlier
+    /// today, for the time it had left, instead of starting a fresh one
+    pub resume_on_start: bool,
+    
+    /// After this many pomodoros in a row without a break taken in full,
+    /// the next break can't be skipped (off when unset)
+    pub max_pomodoros_before_forced_rest: Option<u32>,
+    
+    /// strftime-style format for times shown in `stats` and the history
+    /// panel. Stored and exported timestamps stay RFC 3339.
+    pub datetime_format: String,
+    
+    /// strftime-style format for days shown in `stats`
+    pub date_format: String,
+}
+
+impl Default for Config {
+    fn default() -> Self {
+        Self {
+            notify_on_work_complete: true,
+            notify_on_break_complete: true,
+            sound: false,
+            notify_on_work_start: false,
+            notify_on_break_start: false,
+            retention_days: None,
+            presets: BTreeMap::new(),
+            preset: None,
+            sequence: None,
+            daily_goal: 8,
+            daily_focus_goal: 200,
+            streak_goal: 7,
+            weekly_goal: 40,
+            pause_on_lock: false,
+            max_auto_pause: 0.5,
+            technique: TechniqueKind::Classic,
+            tick_ms: 100,
+            end_of_day: None,
+            workday_start: "09:00".to_string(),
+            workday_end: "17:00".to_string(),
+            day_start_hour: 0,
+            on_session_end: None,
+            auto_tag: false,
+            bank_skipped_breaks: false,
+            max_banked_minutes: 15,
+            remember_last: true,
+            notification_min_interval: 0,
+            high_contrast: false,
+            announce: false,
+            filled_symbol: None,
+            empty_symbol: None,
+            min_session_seconds: 60,
+            first_session_reminder: None,
+            profile: None,
+            auto_quit_minutes: None,
+            show_cycle_time: false,
+            paused_at_end: PausedCompletion::Defer,
+            break_scaling: BreakScaling::Fixed,
+            energy_in_focus_score: true,
+            gauge_label: "{remaining}".to_string(),
+            gauge_direction: GaugeDirection::Fill,
+            encrypt_database: false,
+            database_passphrase: None,
+            micro_break_interval: None,
+            auto_start_work: true,
+            resume_on_start: false,
+            max_pomodoros_before_forced_rest: None,
+            break_overrun_reminder: None,
+            datetime_format: "%Y-%m-%d %H:%M".to_string(),
+            date_format: "%Y-%m-%d".to_string(),
+        }
+    }
+}
+
+impl Config {
+    pub fn path() -> PathBuf {
+        Self::path_for(&profile::active())
+    }
+    
+    fn path_for(profile: &Profile) -> PathBuf {
+        let mut path = profile.config_dir();
+        path.push("config.toml");
+        path
+    }
+    
+    /// Whether there's a config file to load, either the active profile's
+    /// own or the shared one.
+    pub fn exists() -> bool {
+        Self::path().exists() || Self::path_for(&Profile::default()).exists()
+    }
+    
+    /// The file `load` reads: the active profile's, or else the shared one.
+    pub fn file_path() -> Option<PathBuf> {
+        [Self::path(), Self::path_for(&Profile::default())]
+            .into_iter()
+            .find(|path| path.exists())
+    }
+    
+    /// Reads the active profile's config file. Profiles without one share the
+    /// default profile's, and without either the defaults are used.
+    ///
+    /// The values aren't checked here; see `validate`.
+    pub fn load() -> Result<Self, Box<dyn Error>> {
+        let Some(path) = Self::file_path() else {
+            return Ok(Self::default());
+        };
+        
+        let contents = fs::read_to_string(&path)?;
+        Ok(toml::from_str(&contents)?)
+    }
+    
+    /// Rejects values that would otherwise only show up as a panic or odd
+    /// behaviour mid-session.
+    pub fn validate(&self) -> Result<(), Box<dyn Error>> {
+        check_format("datetime_format", &self.datetime_format, |format| Local::now().format(format))?;
+        check_format("date_format", &self.date_format, |format| NaiveDate::default().format(format))?;
+        GaugeLabel::parse(&self.gauge_label).map_err(|e| format!("invalid gauge_label: {}", e))?;
+        if let Some(sequence) = &self.sequence {
+            timer::parse_sequence(sequence).map_err(|e| format!("invalid sequence: {}", e))?;
+        }
+        if self.day_start_hour > 23 {
+            return Err(format!("day_start_hour must be between 0 and 23, got {}", self.day_start_hour).into());
+        }
+        self.workday()?;
+        if !(1..=MAX_TICK_MS).contains(&self.tick_ms) {
+            return Err(format!("tick_ms must be between 1 and {}, got {}", MAX_TICK_MS, self.tick_ms).into());
+        }
+        if self.max_pomodoros_before_forced_rest == Some(0) {
+            return Err("max_pomodoros_before_forced_rest must be at least 1; leave it unset to allow skipping every break".into());
+        }
+        
+        Ok(())
+    }
+    
+    /// Top-level keys set in the config file, to tell them apart from defaults.
+    pub fn keys_in_file() -> Result<BTreeSet<String>, Box<dyn Error>> {
+        let Some(path) = Self::file_path() else {
+            return Ok(BTreeSet::new());
+        };
+        
+        let table: toml::Table = toml::from_str(&fs::read_to_string(path)?)?;
+        Ok(table.keys().cloned().collect())
+    }
+    
+    /// Every setting with its value and whether the config file sets it,
+    /// sorted by key. Unset optional settings are shown as "(unset)".
+    pub fn describe(&self, file_keys: &BTreeSet<String>) -> serde_json::Result<Vec<(String, Sourced<String>)>> {
+        let serde_json::Value::Object(settings) = serde_json::to_value(self)? else {
+            unreachable!("Config serializes to a map");
+        };
+        
+        Ok(settings
+            .into_iter()
+            .map(|(key, value)| {
+                let value = match value {
+                    serde_json::Value::Null => "(unset)".to_string(),
+                    _ if key == "database_passphrase" => "(hidden)".to_string(),
+                    other => other.to_string(),
+                };
+                let source = if file_keys.contains(&key) { Source::File } else { Source::Default };
+                (key, Sourced { value, source })
+            })
+            .collect())
+    }
+    
+    /// Writes the config file, creating its directory if needed.
+    pub fn save(&self) -> Result<(), Box<dyn Error>> {
+        let path = Self::path();
+        if let Some(dir) = path.parent() {
+            fs::create_dir_all(dir)?;
+        }
+        
+        fs::write(&path, toml::to_string_pretty(self)?)?;
+        
+        Ok(())
+    }
+    
+    /// Parses `end_of_day`, rejecting values that aren't "HH:MM".
+    pub fn end_of_day_time(&self) -> Result<Option<NaiveTime>, Box<dyn Error>> {
+        parse_clock("end_of_day", self.end_of_day.as_deref())
+    }
+    
+    /// Parses `workday_start` and `workday_end`, which must be "HH:MM" with
+    /// the end after the start.
+    pub fn workday(&self) -> Result<(NaiveTime, NaiveTime), Box<dyn Error>> {
+        let start = parse_clock("workday_start", Some(&self.workday_start))?.unwrap_or(NaiveTime::MIN);
+        let end = parse_clock("workday_end", Some(&self.workday_end))?.unwrap_or(NaiveTime::MIN);
+        if end <= start {
+            return Err(format!("workday_end {} must be after workday_start {}", self.workday_end, self.workday_start).into());
+        }
+        Ok((start, end))
+    }
+    
+    /// Parses `first_session_reminder`, rejecting values that aren't "HH:MM".
+    pub fn first_session_reminder_time(&self) -> Result<Option<NaiveTime>, Box<dyn Error>> {
+        parse_clock("first_session_reminder", self.first_session_reminder.as_deref())
+    }
+    
+    /// Looks up a preset by name, suggesting the closest match on a typo.
+    pub fn preset(&self, name: &str) -> Result<Preset, Box<dyn Error>> {
+        if let Some(preset) = self.presets.get(name) {
+            return Ok(*preset);
+        }
+        
+        if let Some((_, preset)) = BUILTIN_PRESETS.iter().find(|(builtin, _)| *builtin == name) {
+            return Ok(*preset);
+        }
+        
+        let names = self.preset_names();
+        let message = match suggest(name, &names) {
+            Some(candidate) => format!("unknown preset '{}', did you mean '{}'?", name, candidate),
+            None => format!("unknown preset '{}' (available: {})", name, names.join(", ")),
+        };
+        
+        Err(message.into())
+    }
+    
+    /// A time for display, in the configured `datetime_format`.
+    pub fn format_time(&self, time: &DateTime<Local>) -> String {
+        time.format(&self.datetime_format).to_string()
+    }
+    
+    /// A day for display, in the configured `date_format`.
+    pub fn format_date(&self, day: NaiveDate) -> String {
+        day.format(&self.date_format).to_string()
+    }
+    
+    /// Symbols for finished and remaining pomodoros in the progress row.
+    pub fn tally_symbols(&self, emoji: bool) -> (&str, &str) {
+        let (filled, empty) = if emoji { ("🍅", "⚪") } else { ("*", ".") };
+        (
+            self.filled_symbol.as_deref().unwrap_or(filled),
+            self.empty_symbol.as_deref().unwrap_or(empty),
+        )
+    }
+    
+    /// Names of all user-defined and built-in presets.
+    pub fn preset_names(&self) -> Vec<&str> {
+        let mut names: Vec<&str> = self.presets.keys().map(String::as_str).collect();
+        for (builtin, _) in BUILTIN_PRESETS.iter() {
+            if !names.contains(builtin) {
+                names.push(builtin);
+            }
+        }
+        names
+    }
+}
+
+/// Rejects strftime formats chrono can't render for the values `render`
+/// formats, such as times in a date format, naming the key in the error.
+fn check_format<'a, D: fmt::Display>(
+    key: &str,
+    format: &'a str,
+    render: impl FnOnce(&'a str) -> D,
+) -> Result<(), Box<dyn Error>> {
+    if write!(String::new(), "{}", render(format)).is_err() {
+        return Err(format!("invalid {} '{}', see strftime for the supported fields", key, format).into());
+    }
+    Ok(())
+}
+
+/// Parses an optional "HH:MM" setting, naming the key in the error.
+fn parse_clock(key: &str, value: Option<&str>) -> Result<Option<NaiveTime>, Box<dyn Error>> {
+    match value {
+        Some(value) => NaiveTime::parse_from_str(value, "%H:%M")
+            .map(Some)
+            .map_err(|_| format!("invalid {} '{}', expected HH:MM", key, value).into()),
+        None => Ok(None),
+    }
+}
+
+/// Reads a duration in minutes from the environment, if set.
+pub fn env_minutes(name: &str) -> Result<Option<u64>, Box<dyn Error>> {
+    match env::var(name) {
+        Ok(value) => parse_minutes(name, &value).map(Some),
+        Err(env::VarError::NotPresent) => Ok(None),
+        Err(e) => Err(format!("invalid {}: {}", name, e).into()),
+    }
+}
+
+/// Parses minutes the same way as `--work`/`--break-time`.
+fn parse_minutes(name: &str, value: &str) -> Result<u64, Box<dyn Error>> {
+    value
+        .trim()
+        .parse()
+        .map_err(|_| format!("invalid {} '{}', expected a whole number of minutes", name, value).into())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    
+    #[test]
+    fn test_missing_keys_use_defaults() {
+        let config: Config = toml::from_str("notify_on_work_complete = false").unwrap();
+        
+        assert!(!config.notify_on_work_complete);
+        assert!(config.notify_on_break_complete);
+        // Start notifications stay off unless asked for
+        assert!(!config.notify_on_work_start && !config.notify_on_break_start);
+    }
+    
+    #[test]
+    fn test_preset_lookup() {
+        let config: Config = toml::from_str(
+            "[presets.deepwork]\nwork = 90\nbreak = 20"
+        ).unwrap();
+        
+        // User presets override built-in ones of the same name
+        assert_eq!(config.preset("deepwork").unwrap(), Preset { work: 90, break_time: 20 });
+        assert_eq!(config.preset("short").unwrap(), Preset { work: 15, break_time: 3 });
+        
+        let err = config.preset("deepwrok").unwrap_err();
+        assert_eq!(err.to_string(), "unknown preset 'deepwrok', did you mean 'deepwork'?");
+    }
+    
+    #[test]
+    fn test_serialized_config_round_trips() {
+        let mut config = Config {
+            preset: Some("deepwork".to_string()),
+            ..Default::default()
+        };
+        config.presets.insert("writing".to_string(), Preset { work: 40, break_time: 8 });
+        
+        let text = toml::to_string_pretty(&config).unwrap();
+        let parsed: Config = toml::from_str(&text).unwrap();
+        
+        assert_eq!(parsed.preset.as_deref(), Some("deepwork"));
+        assert_eq!(parsed.preset("writing").unwrap(), Preset { work: 40, break_time: 8 });
+        assert_eq!(parsed.retention_days, None);
+    }
+    
+    #[test]
+    fn test_parse_env_minutes() {
+        assert_eq!(parse_minutes(WORK_ENV, "50").unwrap(), 50);
+        assert_eq!(parse_minutes(WORK_ENV, " 45\n").unwrap(), 45);
+        
+        let err = parse_minutes(BREAK_ENV, "ten").unwrap_err().to_string();
+        assert!(err.contains("RUSTY_POMODORO_BREAK"));
+        assert!(parse_minutes(BREAK_ENV, "-5").is_err());
+    }
+    
+    #[test]
+    fn test_display_formats() {
+        let config: Config = toml::from_str("datetime_format = \"%d/%m %I:%M %p\"").unwrap();
+        let time = NaiveDate::from_ymd_opt(2024, 3, 9)
+            .unwrap()
+            .and_hms_opt(14, 5, 0)
+            .unwrap()
+            .and_local_timezone(Local)
+            .unwrap();
+        
+        assert_eq!(config.format_time(&time), "09/03 02:05 PM");
+        assert_eq!(config.format_date(time.date_naive()), "2024-03-09");
+        
+        let time_format = |format| time.format(format);
+        assert!(check_format("datetime_format", "%Y-%m-%d %H:%M", time_format).is_ok());
+        assert!(check_format("datetime_format", "%Q", time_format).is_err());
+        
+        // Times can't be shown from a day alone
+        let day_format = |format| time.date_naive().format(format);
+        assert!(check_format("date_format", "%d %b", day_format).is_ok());
+        assert!(check_format("date_format", "%d %H", day_format).is_err());
+    }
+    
+    #[test]
+    fn test_describe_marks_file_keys() {
+        let config: Config = toml::from_str("daily_goal = 6\npreset = \"deepwork\"").unwrap();
+        let file_keys: BTreeSet<String> = ["daily_goal", "preset"].iter().map(|k| k.to_string()).collect();
+        let settings: BTreeMap<String, Sourced<String>> = config.describe(&file_keys).unwrap().into_iter().collect();
+        
+        assert_eq!(settings["daily_goal"], Sourced { value: "6".to_string(), source: Source::File });
+        assert_eq!(settings["preset"].value, "\"deepwork\"");
+        assert_eq!(settings["weekly_goal"].source, Source::Default);
+        assert_eq!(settings["end_of_day"].value, "(unset)");
+    }
+    
+    #[test]
+    fn test_validate_rejects_forced_rest_of_zero() {
+        let config = |max| Config { max_pomodoros_before_forced_rest: max, ..Config::default() };
+        
+        assert!(config(None).validate().is_ok());
+        assert!(config(Some(4)).validate().is_ok());
+        assert!(config(Some(0)).validate().is_err());
+    }
+    
+    #[test]
+    fn test_validate_checks_workday() {
+        let config = |start: &str, end: &str| Config {
+            workday_start: start.to_string(),
+            workday_end: end.to_string(),
+            ..Config::default()
+        };
+        
+        assert!(config("08:30", "16:30").validate().is_ok());
+        assert!(config("9am", "17:00").validate().is_err());
+        assert!(config("17:00", "09:00").validate().unwrap_err().to_string().contains("must be after"));
+    }
+    
+    #[test]
+    fn test_validate_bounds_tick_ms() {
+        let config = |tick_ms| Config { tick_ms, ..Config::default() };
+        
+        assert!(config(1).validate().is_ok());
+        assert!(config(MAX_TICK_MS).validate().is_ok());
+        assert!(config(0).validate().is_err());
+        assert!(config(MAX_TICK_MS + 1).validate().is_err());
+    }
+    
+    #[test]
+    fn test_first_of_takes_first_set_value() {
+        let fallback = Sourced { value: 25, source: Source::Default };
+        
+        let picked = Sourced::first_of([(None, Source::Cli), (Some(40), Source::Env), (Some(30), Source::LastRun)], fallback.clone());
+        assert_eq!(picked, Sourced { value: 40, source: Source::Env });
+        assert_eq!(Sourced::first_of([(None, Source::Cli)], fallback.clone()), fallback);
+    }
+}
\ No newline at end of file
@@ -0,0 +1,117 @@
+use chrono::{DateTime, Duration, Local, NaiveDate, NaiveDateTime, NaiveTime, TimeZone};
+use std::sync::OnceLock;
+
+// Every "which day is this" question goes through here: today's totals,
+// daily goals, streaks, pacing and per-day queries. A day starts at
+// `day_start_hour` local time rather than midnight, so for a night owl with
+// day_start_hour = 4 a session at 2am still counts towards the day before.
+
+static START_HOUR: OnceLock<u32> = OnceLock::new();
+
+/// Sets the hour days start at, from the config. Only the first call has
+/// an effect; until then days start at midnight.
+pub fn set_start_hour(hour: u32) {
+    let _ = START_HOUR.set(hour);
+}
+
+fn start_hour() -> u32 {
+    START_HOUR.get().copied().unwrap_or(0)
+}
+
+/// The day `time` counts towards.
+pub fn of<Tz: TimeZone>(time: &DateTime<Tz>) -> NaiveDate {
+    day_of(time.with_timezone(&Local).naive_local(), start_hour())
+}
+
+/// The day it is now.
+pub fn today() -> NaiveDate {
+    of(&Local::now())
+}
+
+/// When `day` starts and when the next one does.
+pub fn bounds(day: NaiveDate) -> (DateTime<Local>, DateTime<Local>) {
+    bounds_at(day, start_hour())
+}
+
+/// `time` on a clock that reads midnight when the day starts, so that 2am
+/// comes after the evening when days start at 4am. For comparing times of
+/// day, e.g. against the working hours.
+pub fn clock(time: NaiveTime) -> NaiveTime {
+    clock_at(time, start_hour())
+}
+
+/// SQLite date modifier that moves a local time back by the start hour,
+/// so `date()` of the shifted time is the day it counts towards.
+pub fn sql_shift() -> String {
+    format!("-{} hours", start_hour())
+}
+
+fn day_of(time: NaiveDateTime, start_hour: u32) -> NaiveDate {
+    (time - Duration::hours(start_hour as i64)).date()
+}
+
+fn clock_at(time: NaiveTime, start_hour: u32) -> NaiveTime {
+    time - Duration::hours(start_hour as i64)
+}
+
+fn bounds_at(day: NaiveDate, start_hour: u32) -> (DateTime<Local>, DateTime<Local>) {
+    let start = |date: NaiveDate| {
+        let naive = date.and_hms_opt(start_hour, 0, 0).unwrap_or_default();
+        // A start skipped by a DST change is taken as the hour after it
+        naive
+            .and_local_timezone(Local)
+            .earliest()
+            .or_else(|| (naive + Duration::hours(1)).and_local_timezone(Local).earliest())
+            .unwrap_or_else(Local::now)
+    };
+    
+    (start(day), start(day + Duration::days(1)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    
+    fn at(day: u32, hour: u32, minute: u32) -> NaiveDateTime {
+        NaiveDate::from_ymd_opt(2024, 3, day).unwrap().and_hms_opt(hour, minute, 0).unwrap()
+    }
+    
+    #[test]
+    fn test_day_starts_at_configured_hour() {
+        let march = |day| NaiveDate::from_ymd_opt(2024, 3, day).unwrap();
+        
+        // Midnight by default
+        assert_eq!(day_of(at(14, 23, 59), 0), march(14));
+        assert_eq!(day_of(at(15, 0, 0), 0), march(15));
+        
+        // Around the 2am edge with days starting at 2am
+        assert_eq!(day_of(at(15, 1, 59), 2), march(14));
+        assert_eq!(day_of(at(15, 2, 0), 2), march(15));
+        assert_eq!(day_of(at(15, 0, 30), 2), march(14));
+    }
+    
+    #[test]
+    fn test_bounds_match_day_of() {
+        let day = NaiveDate::from_ymd_opt(2024, 3, 14).unwrap();
+        let (start, end) = bounds_at(day, 2);
+        
+        assert_eq!(start.naive_local(), at(14, 2, 0));
+        assert_eq!(end.naive_local(), at(15, 2, 0));
+        assert_eq!(day_of(start.naive_local(), 2), day);
+        assert_eq!(day_of(end.naive_local() - Duration::seconds(1), 2), day);
+        
+        let (midnight, _) = bounds_at(day, 0);
+        assert_eq!(midnight.naive_local(), at(14, 0, 0));
+    }
+    
+    #[test]
+    fn test_clock_runs_from_day_start() {
+        let time = |hour, minute| NaiveTime::from_hms_opt(hour, minute, 0).unwrap();
+        
+        assert_eq!(clock_at(time(14, 30), 0), time(14, 30));
+        // 2am is late in a day that started at 4am, after the 17:00 end of work
+        assert_eq!(clock_at(time(2, 0), 4), time(22, 0));
+        assert!(clock_at(time(2, 0), 4) > clock_at(time(17, 0), 4));
+        assert!(clock_at(time(9, 0), 4) > clock_at(time(4, 0), 4));
+    }
+}
\ No newline at end of file
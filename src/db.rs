@@ -1,28 +1,179 @@
-use chrono::{DateTime, Local};
-use rusqlite::{params, Connection, Result};
-use std::path::PathBuf;
+use chrono::{DateTime, FixedOffset, Local, NaiveDate};
+use rusqlite::{
+    params,
+    types::{Type, ValueRef},
+    Connection, DatabaseName, OpenFlags, OptionalExtension, Result, Row,
+};
+use std::{
+    collections::{BTreeMap, HashMap},
+    fmt,
+    path::Path,
+};
+
+use crate::day;
+use crate::encryption;
+use crate::models::Session;
+use crate::timer::TimerType;
+
+// Day attribution: a session belongs entirely to the day it started on,
+// even when it runs past the end of it. Days start at `day_start_hour` local
+// time, via the `day` module. Per-day queries either compare start_time
+// against `day::bounds` or shift the local part of the stored timestamp by
+// `day::sql_shift`. SQLite's date() must only see that local part, since it
+// converts anything with an offset to UTC first.
 
 pub struct Database {
     pub conn: Connection,
 }
 
+/// Totals for a single calendar day.
+#[derive(Debug, Default, PartialEq)]
+pub struct DaySummary {
+    pub pomodoros: i64,
+    pub focus_minutes: i64,
+}
+
+/// Work sessions, finished pomodoros and focus minutes over a period.
+pub struct WorkTotals {
+    pub sessions: i64,
+    pub pomodoros: i64,
+    pub minutes: i64,
+}
+
+/// Sessions and focus minutes logged on one day.
+pub struct DayTotal {
+    pub day: String,
+    pub sessions: i64,
+    pub minutes: i64,
+}
+
+/// A problem found by `validate`.
+#[derive(Debug, PartialEq)]
+pub enum Issue {
+    /// The sessions table lacks a column this version expects
+    MissingColumn(String),
+    /// A timestamp that isn't RFC 3339
+    UnreadableTime { id: i64, column: &'static str, value: String },
+    EndsBeforeStart { id: i64 },
+    /// A `kind` other than work or break
+    UnknownKind { id: i64, kind: String },
+}
+
+impl fmt::Display for Issue {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Issue::MissingColumn(column) => write!(f, "sessions table has no '{}' column", column),
+            Issue::UnreadableTime { id, column, value } => {
+                write!(f, "session {}: {} '{}' isn't a valid timestamp", id, column, value)
+            }
+            Issue::EndsBeforeStart { id } => write!(f, "session {}: ends before it starts", id),
+            Issue::UnknownKind { id, kind } => write!(f, "session {}: unknown kind '{}'", id, kind),
+        }
+    }
+}
+
+/// Outcome of `merge_from`.
+#[derive(Debug, PartialEq)]
+pub struct MergeCounts {
+    pub inserted: usize,
+    /// Sessions already present in this database
+    pub skipped: usize,
+}
+
+/// Recorded breaks in a period, from the rows saved with kind 'break'.
+#[derive(Debug, PartialEq)]
+pub struct BreakSummary {
+    /// Breaks that ran to the end
+    pub taken: i64,
+    /// Breaks cut short with the skip key
+    pub skipped: i64,
+    /// Minutes spent on all breaks, skipped ones included
+    pub rest_minutes: i64,
+}
+
+impl BreakSummary {
+    /// Mean length of a break in minutes, or `None` without breaks.
+    pub fn average_minutes(&self) -> Option<f64> {
+        let count = self.taken + self.skipped;
+        (count > 0).then(|| self.rest_minutes as f64 / count as f64)
+    }
+}
+
+/// Which sessions `query_sessions` returns; unset fields match everything.
+#[derive(Debug, Default, Clone)]
+pub struct SessionFilter {
+    /// Breaks as well as work sessions
+    pub breaks: bool,
+    /// Keep only the newest this many
+    pub limit: Option<usize>,
+    /// Sessions with this tag, ignoring surrounding whitespace
+    pub tag: Option<String>,
+    /// First local day to include
+    pub from: Option<NaiveDate>,
+    /// Last local day to include
+    pub to: Option<NaiveDate>,
+}
+
+/// Focus minutes logged under one tag.
+pub struct TagTotal {
+    /// `None` for sessions without a tag
+    pub tag: Option<String>,
+    pub minutes: i64,
+}
+
 impl Database {
-    pub fn new() -> Result<Self> {
-        // Get the user's data directory
-        let mut data_dir = dirs_next::data_dir().unwrap_or_else(|| PathBuf::from("."));
-        data_dir.push("rusty_pomodoro");
+    /// Opens the active profile's database, encrypted with `passphrase`
+    /// when one is given. A plain database is encrypted on the first open
+    /// with a passphrase.
+    pub fn new(passphrase: Option<&str>) -> std::result::Result<Self, Box<dyn std::error::Error>> {
+        // Get the active profile's data directory
+        let profile = crate::profile::active();
+        let data_dir = profile.data_dir();
         
         // Create directory if it doesn't exist
         std::fs::create_dir_all(&data_dir)?;
         
-        // Create database file path
-        let mut db_path = data_dir;
-        db_path.push("sessions.db");
+        let db_path = profile.database_path();
         
-        // Connect to database
-        let conn = Connection::open(db_path)?;
+        let encrypted = encryption::is_encrypted(&db_path)?;
+        let Some(passphrase) = passphrase else {
+            if encrypted {
+                return Err(if cfg!(feature = "encryption") {
+                    "sessions.db is encrypted; set encrypt_database = true in the config to open it"
+                } else {
+                    "sessions.db is encrypted, but this build has no encryption support; \
+                     reinstall with `--features encryption` to open it"
+                }
+                .into());
+            }
+            return Ok(Database { conn: Connection::open(db_path)? });
+        };
         
-        Ok(Database { conn })
+        #[cfg(feature = "encryption")]
+        {
+            if !encrypted && db_path.exists() {
+                encryption::encrypt_in_place(&db_path, passphrase)?;
+                eprintln!("Encrypted {}", db_path.display());
+            }
+            
+            let conn = Connection::open(db_path)?;
+            conn.pragma_update(None, "key", passphrase)?;
+            // The key is only checked on the first read
+            conn.query_row("SELECT COUNT(*) FROM sqlite_master", [], |_| Ok(()))
+                .map_err(|_| "wrong passphrase for the session database")?;
+            Ok(Database { conn })
+        }
+        #[cfg(not(feature = "encryption"))]
+        {
+            let _ = (passphrase, encrypted);
+            Err("this build has no encryption support; reinstall with `--features encryption`".into())
+        }
+    }
+    
+    /// Opens the database at `path` without writing to it, e.g. another
+    /// profile's.
+    pub fn open_read_only(path: &Path) -> Result<Self> {
+        Ok(Database { conn: Connection::open_with_flags(path, OpenFlags::SQLITE_OPEN_READ_ONLY)? })
     }
     
     pub fn initialize(&self) -> Result<()> {
@@ -32,34 +183,802 @@ impl Database {
                 start_time TEXT NOT NULL,
                 end_time TEXT NOT NULL,
                 pomodoro_count INTEGER NOT NULL,
-                completed BOOLEAN NOT NULL
+                completed INTEGER NOT NULL,
+                energy INTEGER,
+                notes TEXT,
+                tag TEXT,
+                kind TEXT NOT NULL DEFAULT 'work',
+                intention_met INTEGER
+            )",
+            [],
+        )?;
+        
+        // Columns added after the first release
+        self.add_column_if_missing("energy", "INTEGER")?;
+        self.add_column_if_missing("notes", "TEXT")?;
+        self.add_column_if_missing("tag", "TEXT")?;
+        // Older databases only ever recorded work
+        self.add_column_if_missing("kind", "TEXT NOT NULL DEFAULT 'work'")?;
+        self.add_column_if_missing("intention_met", "INTEGER")?;
+        
+        self.conn.execute(
+            "CREATE TABLE IF NOT EXISTS achievements (
+                id TEXT PRIMARY KEY,
+                unlocked_at TEXT NOT NULL
             )",
             [],
         )?;
         
+        self.normalize_completed()?;
+        self.create_notes_index()?;
+        
+        Ok(())
+    }
+    
+    /// Sets up the FTS5 index behind `search_notes`, kept in step with the
+    /// sessions table by triggers, and fills it from existing notes the
+    /// first time. SQLite builds without FTS5 skip this and search with LIKE.
+    fn create_notes_index(&self) -> Result<()> {
+        if self.has_notes_index()? {
+            return Ok(());
+        }
+        
+        let created = self.conn.execute(
+            "CREATE VIRTUAL TABLE notes_fts USING fts5(notes, content = 'sessions', content_rowid = 'id')",
+            [],
+        );
+        if created.is_err() {
+            return Ok(());
+        }
+        
+        self.conn.execute_batch(
+            "CREATE TRIGGER IF NOT EXISTS sessions_notes_insert AFTER INSERT ON sessions BEGIN
+                 INSERT INTO notes_fts (rowid, notes) VALUES (new.id, new.notes);
+             END;
+             CREATE TRIGGER IF NOT EXISTS sessions_notes_delete AFTER DELETE ON sessions BEGIN
+                 INSERT INTO notes_fts (notes_fts, rowid, notes) VALUES ('delete', old.id, old.notes);
+             END;
+             CREATE TRIGGER IF NOT EXISTS sessions_notes_update AFTER UPDATE OF notes ON sessions BEGIN
+                 INSERT INTO notes_fts (notes_fts, rowid, notes) VALUES ('delete', old.id, old.notes);
+                 INSERT INTO notes_fts (rowid, notes) VALUES (new.id, new.notes);
+             END;
+             INSERT INTO notes_fts (notes_fts) VALUES ('rebuild');",
+        )
+    }
+    
+    fn has_notes_index(&self) -> Result<bool> {
+        self.conn.query_row(
+            "SELECT EXISTS(SELECT 1 FROM sqlite_master WHERE type = 'table' AND name = 'notes_fts')",
+            [],
+            |row| row.get(0),
+        )
+    }
+    
+    /// `completed` used to be declared BOOLEAN and imported rows may hold
+    /// 'true'/'false' text; store it as a plain 0/1 integer everywhere,
+    /// reading each value the way `read_completed` does.
+    fn normalize_completed(&self) -> Result<()> {
+        self.conn.execute(
+            "UPDATE sessions
+             SET completed = CASE
+                 WHEN typeof(completed) IN ('integer', 'real') THEN completed != 0
+                 WHEN lower(trim(CAST(completed AS TEXT))) IN ('1', '1.0', 'true', 't', 'yes', 'y') THEN 1
+                 ELSE 0
+             END
+             WHERE typeof(completed) != 'integer' OR completed NOT IN (0, 1)",
+            [],
+        )?;
+        
+        Ok(())
+    }
+    
+    /// Adds a column to `sessions` when an older database doesn't have it yet.
+    fn add_column_if_missing(&self, column: &str, definition: &str) -> Result<()> {
+        let mut stmt = self.conn.prepare("SELECT name FROM pragma_table_info('sessions')")?;
+        let exists = stmt
+            .query_map([], |row| row.get::<_, String>(0))?
+            .filter_map(|name| name.ok())
+            .any(|name| name == column);
+        
+        if !exists {
+            self.conn.execute(
+                &format!("ALTER TABLE sessions ADD COLUMN {} {}", column, definition),
+                [],
+            )?;
+        }
+        
         Ok(())
     }
     
-    pub fn save_session(
-        &self, 
-        start_time: DateTime<Local>,
-        end_time: DateTime<Local>,
-        pomodoro_count: u64,
-        completed: bool,
-    ) -> Result<()> {
+    /// Records a session and returns its new id. The session's own `id` is ignored.
+    pub fn save_session(&self, session: &Session) -> Result<i64> {
         self.conn.execute(
-            "INSERT INTO sessions (start_time, end_time, pomodoro_count, completed) 
-             VALUES (?, ?, ?, ?)",
+            "INSERT INTO sessions (start_time, end_time, pomodoro_count, completed, energy, notes, tag, kind, intention_met) 
+             VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?)",
             params![
-                start_time.to_rfc3339(),
-                end_time.to_rfc3339(),
-                pomodoro_count as i64,
-                completed,
+                session.start.to_rfc3339(),
+                session.end.to_rfc3339(),
+                session.pomodoro_count as i64,
+                session.completed,
+                session.energy,
+                session.notes,
+                session.tag,
+                kind_name(session.kind),
+                session.intention_met,
             ],
         )?;
         
+        Ok(self.conn.last_insert_rowid())
+    }
+    
+    /// Saves sessions from another tracker in one transaction, skipping any
+    /// with the same start and end as a session already stored, so an
+    /// import can be repeated safely. Returns how many were added.
+    pub fn import_sessions(&self, sessions: &[Session]) -> Result<usize> {
+        let tx = self.conn.unchecked_transaction()?;
+        
+        let mut added = 0;
+        for session in sessions {
+            let exists: bool = tx.query_row(
+                "SELECT EXISTS(SELECT 1 FROM sessions
+                               WHERE julianday(start_time) = julianday(?1) AND julianday(end_time) = julianday(?2))",
+                params![session.start.to_rfc3339(), session.end.to_rfc3339()],
+                |row| row.get(0),
+            )?;
+            if !exists {
+                self.save_session(session)?;
+                added += 1;
+            }
+        }
+        
+        tx.commit()?;
+        Ok(added)
+    }
+    
+    /// Id of the most recently started work session, if there is one.
+    pub fn latest_session_id(&self) -> Result<Option<i64>> {
+        self.conn.query_row(
+            "SELECT id FROM sessions WHERE kind = 'work' ORDER BY start_time DESC, id DESC LIMIT 1",
+            [],
+            |row| row.get(0),
+        ).optional()
+    }
+    
+    /// Sets a session's note, or adds a line to the existing one when `append` is set.
+    pub fn update_note(&self, id: i64, note: &str, append: bool) -> Result<()> {
+        let sql = if append {
+            "UPDATE sessions
+             SET notes = CASE WHEN notes IS NULL OR notes = '' THEN ?1 ELSE notes || char(10) || ?1 END
+             WHERE id = ?2"
+        } else {
+            "UPDATE sessions SET notes = ?1 WHERE id = ?2"
+        };
+        
+        self.conn.execute(sql, params![note, id])?;
+        
         Ok(())
     }
+    
+    /// The session with row id `id`, work or break.
+    pub fn session(&self, id: i64) -> Result<Option<Session>> {
+        self.conn
+            .query_row(
+                &format!("SELECT {} FROM sessions WHERE id = ?", SESSION_COLUMNS),
+                [id],
+                session_from_row,
+            )
+            .optional()
+    }
+    
+    /// Records a session as `kind`, returning false if there's no session `id`.
+    ///
+    /// `completed` means a counted pomodoro for work and a break taken in
+    /// full for breaks. A break turned into work doesn't become a pomodoro
+    /// after the fact, so it's cleared; work turned into a break keeps it.
+    /// Focus ratings and intentions met only apply to work and are dropped
+    /// from breaks.
+    pub fn reclassify_session(&self, id: i64, kind: TimerType) -> Result<bool> {
+        let sql = match kind {
+            TimerType::Work => "UPDATE sessions SET kind = 'work', completed = 0 WHERE id = ?1 AND kind != 'work'",
+            TimerType::Break => "UPDATE sessions SET kind = 'break', energy = NULL, intention_met = NULL WHERE id = ?1 AND kind != 'break'",
+        };
+        self.conn.execute(sql, [id])?;
+        
+        self.conn.query_row("SELECT EXISTS(SELECT 1 FROM sessions WHERE id = ?)", [id], |row| row.get(0))
+    }
+    
+    /// Copies the live database to `path` using SQLite's online backup API.
+    ///
+    /// Unlike copying the file directly, this yields a consistent snapshot
+    /// even while another connection is writing.
+    pub fn backup(&self, path: &Path) -> Result<()> {
+        self.conn.backup(DatabaseName::Main, path, None)
+    }
+    
+    /// Copies the sessions of another database file into this one, skipping
+    /// any with the same start and end as a session already stored, like
+    /// `import_sessions`. The other file is opened read-only.
+    ///
+    /// Databases from older versions without the newer columns can be
+    /// merged too; the missing values are left empty.
+    pub fn merge_from(&self, path: &Path) -> Result<MergeCounts> {
+        self.conn.execute("ATTACH DATABASE ?1 AS other", [read_only_uri(path)])?;
+        let merged = self.merge_attached();
+        self.conn.execute("DETACH DATABASE other", [])?;
+        merged
+    }
+    
+    fn merge_attached(&self) -> Result<MergeCounts> {
+        let mut stmt = self.conn.prepare("SELECT name FROM pragma_table_info('sessions', 'other')")?;
+        let columns: Vec<String> = stmt.query_map([], |row| row.get(0))?.collect::<Result<_>>()?;
+        let column = |name: &str, fallback: &str| {
+            if columns.iter().any(|column| column == name) {
+                format!("o.{}", name)
+            } else {
+                fallback.to_string()
+            }
+        };
+        
+        let tx = self.conn.unchecked_transaction()?;
+        
+        let total: usize = tx.query_row("SELECT COUNT(*) FROM other.sessions", [], |row| row.get(0))?;
+        let inserted = tx.execute(
+            &format!(
+                "INSERT INTO main.sessions (start_time, end_time, pomodoro_count, completed, energy, notes, tag, kind, intention_met)
+                 SELECT o.start_time, o.end_time, o.pomodoro_count, o.completed, {}, {}, {}, {}, {}
+                 FROM other.sessions o
+                 WHERE NOT EXISTS (SELECT 1 FROM main.sessions m
+                                   WHERE julianday(m.start_time) = julianday(o.start_time)
+                                     AND julianday(m.end_time) = julianday(o.end_time))",
+                column("energy", "NULL"),
+                column("notes", "NULL"),
+                column("tag", "NULL"),
+                column("kind", "'work'"),
+                column("intention_met", "NULL"),
+            ),
+            [],
+        )?;
+        self.normalize_completed()?;
+        
+        tx.commit()?;
+        Ok(MergeCounts { inserted, skipped: total - inserted })
+    }
+    
+    /// Deletes sessions that started more than `days` days ago.
+    ///
+    /// Returns how many rows were removed.
+    pub fn prune_older_than(&self, days: u32) -> Result<usize> {
+        let cutoff = Local::now() - chrono::Duration::days(days as i64);
+        
+        self.conn.execute(
+            "DELETE FROM sessions WHERE julianday(start_time) < julianday(?)",
+            params![cutoff.to_rfc3339()],
+        )
+    }
+    
+    /// Counts every completed work session ever recorded.
+    ///
+    /// Each finished work phase is saved as its own row, so this is the
+    /// lifetime pomodoro total. `pomodoro_count` is only the running tally
+    /// within a single `start` invocation and must not be summed.
+    pub fn lifetime_pomodoros(&self) -> Result<i64> {
+        self.conn.query_row(
+            "SELECT COUNT(*) FROM sessions WHERE kind = 'work' AND completed = 1",
+            [],
+            |row| row.get(0),
+        )
+    }
+    
+    /// Number of recorded work sessions, finished or not.
+    pub fn total_sessions(&self) -> Result<i64> {
+        self.conn.query_row(
+            "SELECT COUNT(*) FROM sessions WHERE kind = 'work'", 
+            [], 
+            |row| row.get(0)
+        )
+    }
+    
+    /// Minutes spent across all recorded work sessions.
+    pub fn total_focus_minutes(&self) -> Result<i64> {
+        self.conn.query_row(
+            "SELECT COALESCE(SUM(CAST(ROUND((julianday(end_time) - julianday(start_time)) * 24 * 60) As Integer)), 0)
+             FROM sessions
+             WHERE kind = 'work'", 
+            [], 
+            |row| row.get(0)
+        )
+    }
+    
+    /// Work totals since `since` (or ever), for `stats --period`.
+    pub fn work_totals(&self, since: Option<DateTime<Local>>) -> Result<WorkTotals> {
+        self.conn.query_row(
+            "SELECT COUNT(*),
+                    COALESCE(SUM(completed = 1), 0),
+                    COALESCE(SUM(CAST(ROUND((julianday(end_time) - julianday(start_time)) * 24 * 60) As Integer)), 0)
+             FROM sessions
+             WHERE kind = 'work' AND (?1 IS NULL OR julianday(start_time) >= julianday(?1))",
+            params![since.map(|s| s.to_rfc3339())],
+            |row| Ok(WorkTotals {
+                sessions: row.get(0)?,
+                pomodoros: row.get(1)?,
+                minutes: row.get(2)?,
+            }),
+        )
+    }
+    
+    /// The `limit` days with the most focus minutes since `since` (or
+    /// ever), best first, by the day each session started on.
+    ///
+    /// A day whose rows all have unreadable timestamps sums to NULL, which
+    /// counts as zero minutes rather than failing the query.
+    pub fn top_days(&self, limit: usize, since: Option<DateTime<Local>>) -> Result<Vec<DayTotal>> {
+        let mut stmt = self.conn.prepare(
+            "SELECT COALESCE(date(substr(start_time, 1, 19), ?2), substr(start_time, 1, 10)) as day, 
+                    COUNT(*) as sessions,
+                    COALESCE(SUM(CAST(ROUND((julianday(end_time) - julianday(start_time)) * 24 * 60) As Integer)), 0) as minutes
+             FROM sessions 
+             WHERE kind = 'work' AND (?3 IS NULL OR julianday(start_time) >= julianday(?3))
+             GROUP BY day 
+             ORDER BY minutes DESC
+             LIMIT ?1"
+        )?;
+        
+        let days = stmt.query_map(params![limit as i64, day::sql_shift(), since.map(|s| s.to_rfc3339())], |row| {
+            Ok(DayTotal {
+                day: row.get(0)?,
+                sessions: row.get(1)?,
+                minutes: row.get(2)?,
+            })
+        })?;
+        
+        days.collect()
+    }
+    
+    /// Minutes of work and of break since `since` (or ever), for the focus ratio.
+    pub fn work_and_break_minutes(&self, since: Option<DateTime<Local>>) -> Result<(i64, i64)> {
+        self.conn.query_row(
+            "SELECT COALESCE(SUM(CASE WHEN kind = 'work' THEN minutes END), 0),
+                    COALESCE(SUM(CASE WHEN kind = 'break' THEN minutes END), 0)
+             FROM (SELECT kind,
+                          CAST(ROUND((julianday(end_time) - julianday(start_time)) * 24 * 60) As Integer) as minutes
+                   FROM sessions
+                   WHERE ?1 IS NULL OR julianday(start_time) >= julianday(?1))",
+            params![since.map(|s| s.to_rfc3339())],
+            |row| Ok((row.get(0)?, row.get(1)?)),
+        )
+    }
+    
+    /// Completion and focus rating of each work session since `since` (or
+    /// ever), for the focus score.
+    pub fn focus_inputs(&self, since: Option<DateTime<Local>>) -> Result<Vec<(bool, Option<u8>)>> {
+        let mut stmt = self.conn.prepare(
+            "SELECT completed, energy FROM sessions
+             WHERE kind = 'work' AND (?1 IS NULL OR julianday(start_time) >= julianday(?1))"
+        )?;
+        
+        let rows = stmt.query_map(params![since.map(|s| s.to_rfc3339())], |row| {
+            Ok((read_completed(row, 0)?, row.get(1)?))
+        })?;
+        rows.collect()
+    }
+    
+    /// Breaks taken and skipped since `since` (or ever), and the time they took.
+    pub fn break_summary(&self, since: Option<DateTime<Local>>) -> Result<BreakSummary> {
+        self.conn.query_row(
+            "SELECT COALESCE(SUM(completed = 1), 0),
+                    COALESCE(SUM(completed = 0), 0),
+                    COALESCE(SUM(CAST(ROUND((julianday(end_time) - julianday(start_time)) * 24 * 60) As Integer)), 0)
+             FROM sessions
+             WHERE kind = 'break' AND (?1 IS NULL OR julianday(start_time) >= julianday(?1))",
+            params![since.map(|s| s.to_rfc3339())],
+            |row| Ok(BreakSummary { taken: row.get(0)?, skipped: row.get(1)?, rest_minutes: row.get(2)? }),
+        )
+    }
+    
+    /// Tags with the most focus minutes since `since` (or ever), best first.
+    ///
+    /// Untagged sessions, including blank tags, form one bucket that ranks
+    /// like any other. Ties are broken by tag name, with the untagged bucket last.
+    pub fn top_tags(&self, since: Option<DateTime<Local>>, limit: usize) -> Result<Vec<TagTotal>> {
+        let mut stmt = self.conn.prepare(
+            "SELECT NULLIF(TRIM(tag), '') as label,
+                    COALESCE(SUM(CAST(ROUND((julianday(end_time) - julianday(start_time)) * 24 * 60) As Integer)), 0) as minutes
+             FROM sessions
+             WHERE kind = 'work' AND (?1 IS NULL OR julianday(start_time) >= julianday(?1))
+             GROUP BY label
+             ORDER BY minutes DESC, label IS NULL, label
+             LIMIT ?2"
+        )?;
+        
+        let tags = stmt.query_map(params![since.map(|s| s.to_rfc3339()), limit as i64], |row| {
+            Ok(TagTotal {
+                tag: row.get(0)?,
+                minutes: row.get(1)?,
+            })
+        })?;
+        
+        tags.collect()
+    }
+    
+    /// Every tag used on a work session with its number of sessions, in
+    /// alphabetical order ignoring case, so variants like "Writing" and
+    /// "writing" end up next to each other. Untagged sessions, including
+    /// blank tags, come last as `None`.
+    pub fn list_tags(&self) -> Result<Vec<(Option<String>, i64)>> {
+        let mut stmt = self.conn.prepare(
+            "SELECT NULLIF(TRIM(tag), '') as label, COUNT(*)
+             FROM sessions
+             WHERE kind = 'work'
+             GROUP BY label
+             ORDER BY label IS NULL, label COLLATE NOCASE, label"
+        )?;
+        
+        let tags = stmt.query_map([], |row| Ok((row.get(0)?, row.get(1)?)))?;
+        tags.collect()
+    }
+    
+    /// Start and end times of every recorded work session, oldest first.
+    pub fn session_times(&self) -> Result<Vec<(DateTime<Local>, DateTime<Local>)>> {
+        let mut stmt = self.conn.prepare(
+            "SELECT start_time, end_time FROM sessions WHERE kind = 'work' ORDER BY start_time ASC"
+        )?;
+        
+        let times = stmt.query_map([], |row| {
+            let start: String = row.get(0)?;
+            let end: String = row.get(1)?;
+            
+            Ok((parse_time(0, &start)?, parse_time(1, &end)?))
+        })?;
+        
+        times.collect()
+    }
+    
+    /// Completed pomodoros and focus minutes for work sessions started on `day`.
+    pub fn day_summary(&self, day: NaiveDate) -> Result<DaySummary> {
+        let (start, end) = day::bounds(day);
+        
+        self.conn.query_row(
+            "SELECT COALESCE(SUM(completed = 1), 0),
+                    COALESCE(SUM(CAST(ROUND((julianday(end_time) - julianday(start_time)) * 24 * 60) As Integer)), 0)
+             FROM sessions
+             WHERE kind = 'work' AND julianday(start_time) >= julianday(?) AND julianday(start_time) < julianday(?)",
+            params![start.to_rfc3339(), end.to_rfc3339()],
+            |row| {
+                Ok(DaySummary {
+                    pomodoros: row.get(0)?,
+                    focus_minutes: row.get(1)?,
+                })
+            },
+        )
+    }
+    
+    /// Work sessions started on `day`, earliest first.
+    pub fn sessions_on(&self, day: NaiveDate) -> Result<Vec<Session>> {
+        let (start, end) = day::bounds(day);
+        let mut stmt = self.conn.prepare(&format!(
+            "SELECT {} FROM sessions
+             WHERE kind = 'work' AND julianday(start_time) >= julianday(?) AND julianday(start_time) < julianday(?)
+             ORDER BY julianday(start_time) ASC, id ASC",
+            SESSION_COLUMNS
+        ))?;
+        
+        let sessions = stmt.query_map(params![start.to_rfc3339(), end.to_rfc3339()], session_from_row)?;
+        sessions.collect()
+    }
+    
+    /// Completed and total work sessions over `days` days from `first_day`.
+    pub fn completion_counts(&self, first_day: NaiveDate, days: i64) -> Result<(i64, i64)> {
+        let (start, _) = day::bounds(first_day);
+        let (_, end) = day::bounds(first_day + chrono::Duration::days(days - 1));
+        
+        self.conn.query_row(
+            "SELECT COALESCE(SUM(completed = 1), 0), COUNT(*) FROM sessions
+             WHERE kind = 'work' AND julianday(start_time) >= julianday(?) AND julianday(start_time) < julianday(?)",
+            params![start.to_rfc3339(), end.to_rfc3339()],
+            |row| Ok((row.get(0)?, row.get(1)?)),
+        )
+    }
+    
+    /// Completed pomodoros started on `day` since its last break taken in
+    /// full, for max_pomodoros_before_forced_rest.
+    pub fn pomodoros_since_rest(&self, day: NaiveDate) -> Result<u32> {
+        let (start, end) = day::bounds(day);
+        
+        self.conn.query_row(
+            "SELECT COUNT(*) FROM sessions
+             WHERE kind = 'work' AND completed = 1
+               AND julianday(start_time) >= julianday(?1) AND julianday(start_time) < julianday(?2)
+               AND julianday(start_time) > COALESCE(
+                   (SELECT MAX(julianday(start_time)) FROM sessions
+                    WHERE kind = 'break' AND completed = 1
+                      AND julianday(start_time) >= julianday(?1) AND julianday(start_time) < julianday(?2)),
+                   0)",
+            params![start.to_rfc3339(), end.to_rfc3339()],
+            |row| row.get(0),
+        )
+    }
+    
+    /// Number of work sessions, finished or not, started on `day`.
+    pub fn session_count_on(&self, day: NaiveDate) -> Result<i64> {
+        let (start, end) = day::bounds(day);
+        
+        self.conn.query_row(
+            "SELECT COUNT(*) FROM sessions
+             WHERE kind = 'work' AND julianday(start_time) >= julianday(?) AND julianday(start_time) < julianday(?)",
+            params![start.to_rfc3339(), end.to_rfc3339()],
+            |row| row.get(0),
+        )
+    }
+    
+    /// Focus minutes per day for the work sessions started since `since`.
+    pub fn focus_minutes_by_day(&self, since: DateTime<Local>) -> Result<BTreeMap<NaiveDate, i64>> {
+        let mut stmt = self.conn.prepare(
+            "SELECT start_time, CAST(ROUND((julianday(end_time) - julianday(start_time)) * 24 * 60) As Integer)
+             FROM sessions
+             WHERE kind = 'work' AND julianday(start_time) >= julianday(?1)"
+        )?;
+        
+        let mut days = BTreeMap::new();
+        let rows = stmt.query_map([since.to_rfc3339()], |row| {
+            Ok((row.get::<_, String>(0)?, row.get::<_, Option<i64>>(1)?))
+        })?;
+        for row in rows {
+            let (start, minutes) = row?;
+            *days.entry(day::of(&parse_time(0, &start)?)).or_insert(0) += minutes.unwrap_or(0);
+        }
+        
+        Ok(days)
+    }
+    
+    /// Days with at least one completed pomodoro, oldest first.
+    pub fn completed_days(&self) -> Result<Vec<NaiveDate>> {
+        let mut stmt = self.conn.prepare(
+            "SELECT start_time FROM sessions WHERE kind = 'work' AND completed = 1 ORDER BY start_time ASC"
+        )?;
+        
+        let mut days = Vec::new();
+        for start in stmt.query_map([], |row| row.get::<_, String>(0))? {
+            let day = day::of(&parse_time(0, &start?)?);
+            if days.last() != Some(&day) {
+                days.push(day);
+            }
+        }
+        
+        Ok(days)
+    }
+    
+    /// Records an achievement as unlocked at `at`. Returns false if it
+    /// already was, keeping the original time.
+    pub fn unlock_achievement(&self, id: &str, at: DateTime<Local>) -> Result<bool> {
+        let inserted = self.conn.execute(
+            "INSERT OR IGNORE INTO achievements (id, unlocked_at) VALUES (?1, ?2)",
+            params![id, at.to_rfc3339()],
+        )?;
+        
+        Ok(inserted > 0)
+    }
+    
+    /// Unlocked achievements by id, with when they were unlocked.
+    pub fn unlocked_achievements(&self) -> Result<HashMap<String, DateTime<Local>>> {
+        let mut stmt = self.conn.prepare("SELECT id, unlocked_at FROM achievements")?;
+        let rows = stmt.query_map([], |row| {
+            let unlocked_at: String = row.get(1)?;
+            Ok((row.get(0)?, parse_time(1, &unlocked_at)?))
+        })?;
+        
+        rows.collect()
+    }
+    
+    /// Checks the schema and every row for problems that manual edits or
+    /// buggy imports can leave behind. Rows with unreadable timestamps
+    /// would otherwise make `sessions` and the exports fail.
+    pub fn validate(&self) -> Result<Vec<Issue>> {
+        let mut issues = Vec::new();
+        
+        let mut stmt = self.conn.prepare("SELECT name FROM pragma_table_info('sessions')")?;
+        let columns: Vec<String> = stmt.query_map([], |row| row.get(0))?.collect::<Result<_>>()?;
+        for expected in EXPECTED_COLUMNS {
+            if !columns.iter().any(|column| column == expected) {
+                issues.push(Issue::MissingColumn(expected.to_string()));
+            }
+        }
+        if !issues.is_empty() {
+            return Ok(issues);
+        }
+        
+        let mut stmt = self.conn.prepare("SELECT id, start_time, end_time, kind FROM sessions ORDER BY id")?;
+        let mut rows = stmt.query([])?;
+        while let Some(row) = rows.next()? {
+            let id: i64 = row.get(0)?;
+            let start: String = row.get(1)?;
+            let end: String = row.get(2)?;
+            let kind: String = row.get(3)?;
+            
+            let start_time = parse_time(1, &start).ok();
+            let end_time = parse_time(2, &end).ok();
+            if start_time.is_none() {
+                issues.push(Issue::UnreadableTime { id, column: "start_time", value: start });
+            }
+            if end_time.is_none() {
+                issues.push(Issue::UnreadableTime { id, column: "end_time", value: end });
+            }
+            if let (Some(start_time), Some(end_time)) = (start_time, end_time) {
+                if end_time < start_time {
+                    issues.push(Issue::EndsBeforeStart { id });
+                }
+            }
+            if kind != "work" && kind != "break" {
+                issues.push(Issue::UnknownKind { id, kind });
+            }
+        }
+        
+        Ok(issues)
+    }
+    
+    /// Work sessions whose notes contain every word of `query`, best match
+    /// first. Words also match as prefixes, so "refac" finds "refactoring".
+    pub fn search_notes(&self, query: &str, limit: usize) -> Result<Vec<Session>> {
+        if self.has_notes_index()? {
+            self.search_notes_fts(query, limit)
+        } else {
+            self.search_notes_like(query, limit)
+        }
+    }
+    
+    fn search_notes_fts(&self, query: &str, limit: usize) -> Result<Vec<Session>> {
+        let Some(query) = fts_query(query) else {
+            return Ok(Vec::new());
+        };
+        
+        let mut stmt = self.conn.prepare(&format!(
+            "SELECT {} FROM sessions
+             JOIN (SELECT rowid AS hit, rank FROM notes_fts WHERE notes_fts MATCH ?1) ON hit = sessions.id
+             WHERE kind = 'work'
+             ORDER BY rank, start_time DESC
+             LIMIT ?2",
+            SESSION_COLUMNS
+        ))?;
+        
+        let sessions = stmt.query_map(params![query, limit as i64], session_from_row)?;
+        
+        sessions.collect()
+    }
+    
+    /// Unranked fallback: newest first.
+    fn search_notes_like(&self, query: &str, limit: usize) -> Result<Vec<Session>> {
+        let words: Vec<String> = query
+            .split_whitespace()
+            .map(|word| format!("%{}%", word.replace('\\', "\\\\").replace('%', "\\%").replace('_', "\\_")))
+            .collect();
+        if words.is_empty() {
+            return Ok(Vec::new());
+        }
+        
+        let conditions = vec!["notes LIKE ? ESCAPE '\\'"; words.len()].join(" AND ");
+        let mut stmt = self.conn.prepare(&format!(
+            "SELECT {} FROM sessions WHERE kind = 'work' AND {} ORDER BY start_time DESC LIMIT {}",
+            SESSION_COLUMNS, conditions, limit
+        ))?;
+        
+        let sessions = stmt.query_map(rusqlite::params_from_iter(&words), session_from_row)?;
+        
+        sessions.collect()
+    }
+    
+    /// Sessions matching `filter`, newest first.
+    pub fn query_sessions(&self, filter: &SessionFilter) -> Result<Vec<Session>> {
+        let mut stmt = self.conn.prepare(&format!(
+            "SELECT {} FROM sessions
+             WHERE (?5 OR kind = 'work')
+               AND (?1 IS NULL OR julianday(start_time) >= julianday(?1))
+               AND (?2 IS NULL OR julianday(start_time) < julianday(?2))
+               AND (?3 IS NULL OR TRIM(tag) = TRIM(?3))
+             ORDER BY julianday(start_time) DESC, id DESC
+             LIMIT ?4",
+            SESSION_COLUMNS
+        ))?;
+        
+        let from = filter.from.map(|day| day::bounds(day).0.to_rfc3339());
+        let until = filter.to.map(|day| day::bounds(day).1.to_rfc3339());
+        // A negative LIMIT means no limit to SQLite
+        let limit = filter.limit.map_or(-1, |limit| limit as i64);
+        let sessions = stmt.query_map(params![from, until, filter.tag, limit, filter.breaks], session_from_row)?;
+        
+        sessions.collect()
+    }
+}
+
+/// Columns of the current schema, checked by `validate`
+const EXPECTED_COLUMNS: [&str; 10] = [
+    "id",
+    "start_time",
+    "end_time",
+    "pomodoro_count",
+    "completed",
+    "energy",
+    "notes",
+    "tag",
+    "kind",
+    "intention_met",
+];
+
+/// Columns read by `session_from_row`, in order
+const SESSION_COLUMNS: &str = "id, start_time, end_time, pomodoro_count, completed, energy, notes, tag, kind, intention_met";
+
+/// Builds a session from a row selected with `SESSION_COLUMNS`.
+fn session_from_row(row: &Row) -> Result<Session> {
+    let start: String = row.get(1)?;
+    let end: String = row.get(2)?;
+    
+    Ok(Session {
+        id: row.get(0)?,
+        start: parse_recorded_time(1, &start)?,
+        end: parse_recorded_time(2, &end)?,
+        pomodoro_count: row.get::<_, i64>(3)?.max(0) as u64,
+        completed: read_completed(row, 4)?,
+        energy: row.get(5)?,
+        notes: row.get(6)?,
+        tag: row.get(7)?,
+        kind: match row.get::<_, String>(8)?.as_str() {
+            "break" => TimerType::Break,
+            _ => TimerType::Work,
+        },
+        intention_met: row.get(9)?,
+    })
+}
+
+/// Turns free text into an FTS5 query matching every word as a prefix.
+/// Each word is quoted, so punctuation can't be read as query syntax.
+fn fts_query(text: &str) -> Option<String> {
+    let terms: Vec<String> = text
+        .split_whitespace()
+        .map(|word| format!("\"{}\"*", word.replace('"', "\"\"")))
+        .collect();
+    (!terms.is_empty()).then(|| terms.join(" "))
+}
+
+/// A `file:` URI opening `path` read-only, for ATTACH.
+fn read_only_uri(path: &Path) -> String {
+    let path = path.to_string_lossy().replace('%', "%25").replace('?', "%3f").replace('#', "%23");
+    format!("file:{}?mode=ro", path)
+}
+
+/// Value stored in the `kind` column.
+fn kind_name(kind: TimerType) -> &'static str {
+    match kind {
+        TimerType::Work => "work",
+        TimerType::Break => "break",
+    }
+}
+
+/// Reads a `completed` flag, accepting integers as well as text such as
+/// `'true'` left behind by manual edits or imports.
+fn read_completed(row: &Row, column: usize) -> Result<bool> {
+    match row.get_ref(column)? {
+        ValueRef::Integer(value) => Ok(value != 0),
+        ValueRef::Real(value) => Ok(value != 0.0),
+        ValueRef::Text(text) => {
+            let text = String::from_utf8_lossy(text).trim().to_lowercase();
+            Ok(matches!(text.as_str(), "1" | "1.0" | "true" | "t" | "yes" | "y"))
+        }
+        ValueRef::Null => Ok(false),
+        ValueRef::Blob(_) => Err(rusqlite::Error::InvalidColumnType(
+            column,
+            "completed".to_string(),
+            Type::Blob,
+        )),
+    }
+}
+
+/// Parses a stored RFC 3339 timestamp into local time, reporting failures as a column conversion error.
+fn parse_time(column: usize, value: &str) -> Result<DateTime<Local>> {
+    parse_recorded_time(column, value).map(|time| time.with_timezone(&Local))
+}
+
+/// Like `parse_time`, but keeps the offset the timestamp was stored with.
+fn parse_recorded_time(column: usize, value: &str) -> Result<DateTime<FixedOffset>> {
+    DateTime::parse_from_rfc3339(value)
+        .map_err(|e| rusqlite::Error::FromSqlConversionFailure(column, Type::Text, Box::new(e)))
 }
 
 #[cfg(test)]
@@ -67,6 +986,23 @@ mod tests {
     use super::*;
     use chrono::Duration;
     
+    impl Database {
+        /// Every recorded work session, newest first.
+        fn sessions(&self) -> Result<Vec<Session>> {
+            self.query_sessions(&SessionFilter::default())
+        }
+    }
+    
+    fn test_db() -> Result<Database> {
+        let db = Database { conn: Connection::open(":memory:")? };
+        db.initialize()?;
+        Ok(db)
+    }
+    
+    fn work(start: DateTime<Local>, end: DateTime<Local>, pomodoro_count: u64, completed: bool) -> Session {
+        Session { pomodoro_count, completed, ..Session::new(start, end) }
+    }
+    
     #[test]
     fn test_db_create_and_query() -> Result<()> {
         // Use in-memory database for testing
@@ -80,7 +1016,7 @@ mod tests {
         let now = Local::now();
         let end = now + Duration::minutes(25);
         
-        db.save_session(now, end, 1, true)?;
+        db.save_session(&work(now, end, 1, true))?;
         
         // Query for saved session
         let mut stmt = db.conn.prepare("SELECT * FROM sessions")?;
@@ -93,4 +1029,768 @@ mod tests {
         
         Ok(())
     }
+    
+    #[test]
+    fn test_energy_rating_is_optional() -> Result<()> {
+        let db = test_db()?;
+        
+        let now = Local::now();
+        let end = now + Duration::minutes(25);
+        
+        db.save_session(&Session { energy: Some(4), ..work(now, end, 1, true) })?;
+        db.save_session(&work(now, end, 2, true))?;
+        
+        let mut stmt = db.conn.prepare("SELECT energy FROM sessions ORDER BY id")?;
+        let energies: Vec<Option<u8>> = stmt
+            .query_map([], |row| row.get(0))?
+            .collect::<Result<_>>()?;
+        
+        assert_eq!(energies, vec![Some(4), None]);
+        
+        Ok(())
+    }
+    
+    #[test]
+    fn test_lifetime_pomodoros_counts_completed_rows() -> Result<()> {
+        let db = test_db()?;
+        
+        let now = Local::now();
+        let end = now + Duration::minutes(25);
+        
+        // Two pomodoros in one run, then an abandoned one
+        db.save_session(&work(now, end, 1, true))?;
+        db.save_session(&work(now, end, 2, true))?;
+        db.save_session(&work(now, end, 2, false))?;
+        
+        assert_eq!(db.lifetime_pomodoros()?, 2);
+        
+        Ok(())
+    }
+    
+    #[test]
+    fn test_prune_keeps_recent_sessions() -> Result<()> {
+        let db = test_db()?;
+        
+        let now = Local::now();
+        let old = now - Duration::days(40);
+        let recent = now - Duration::days(1);
+        
+        db.save_session(&work(old, old + Duration::minutes(25), 1, true))?;
+        db.save_session(&work(recent, recent + Duration::minutes(25), 1, true))?;
+        
+        assert_eq!(db.prune_older_than(30)?, 1);
+        
+        let times = db.session_times()?;
+        assert_eq!(times.len(), 1);
+        assert_eq!(times[0].0, recent);
+        
+        Ok(())
+    }
+    
+    #[test]
+    fn test_day_summary_and_completed_days() -> Result<()> {
+        let db = test_db()?;
+        
+        use chrono::TimeZone;
+        
+        let now = Local.with_ymd_and_hms(2024, 3, 14, 10, 0, 0).unwrap();
+        let today = now.date_naive();
+        let yesterday = now - Duration::days(1);
+        
+        db.save_session(&work(now, now + Duration::minutes(25), 1, true))?;
+        db.save_session(&work(now, now + Duration::minutes(10), 1, false))?;
+        db.save_session(&work(yesterday, yesterday + Duration::minutes(25), 1, true))?;
+        
+        let summary = db.day_summary(today)?;
+        assert_eq!(summary, DaySummary { pomodoros: 1, focus_minutes: 35 });
+        
+        assert_eq!(db.completed_days()?, vec![yesterday.date_naive(), today]);
+        
+        Ok(())
+    }
+    
+    #[test]
+    fn test_backup_round_trip() -> Result<()> {
+        let db = test_db()?;
+        
+        let now = Local::now();
+        db.save_session(&Session { energy: Some(3), ..work(now, now + Duration::minutes(25), 1, true) })?;
+        
+        let path = std::env::temp_dir().join(format!("rusty_pomodoro_backup_{}.db", std::process::id()));
+        db.backup(&path)?;
+        
+        let restored = Database { conn: Connection::open(&path)? };
+        let energy: Option<u8> = restored.conn.query_row("SELECT energy FROM sessions", [], |row| row.get(0))?;
+        assert_eq!(energy, Some(3));
+        assert_eq!(restored.lifetime_pomodoros()?, 1);
+        
+        drop(restored);
+        std::fs::remove_file(&path).ok();
+        
+        Ok(())
+    }
+    
+    #[test]
+    fn test_export_json_shape() -> Result<()> {
+        let db = test_db()?;
+        
+        let start = Local::now() - Duration::hours(1);
+        db.save_session(&work(start, start + Duration::minutes(25), 1, true))?;
+        db.save_session(&work(start + Duration::minutes(30), start + Duration::minutes(42), 1, false))?;
+        
+        let json: serde_json::Value = serde_json::from_str(&crate::export::to_json(&db.sessions()?).unwrap()).unwrap();
+        let sessions = json.as_array().unwrap();
+        assert_eq!(sessions.len(), 2);
+        
+        // Newest first, with exactly the documented fields
+        let latest = sessions[0].as_object().unwrap();
+        let mut keys: Vec<&str> = latest.keys().map(String::as_str).collect();
+        keys.sort_unstable();
+        assert_eq!(
+            keys,
+            ["completed", "duration_minutes", "end_time", "id", "notes", "pomodoro_count", "start_time"]
+        );
+        
+        assert_eq!(latest["completed"], false);
+        assert_eq!(latest["duration_minutes"], 12);
+        assert_eq!(latest["pomodoro_count"], 1);
+        assert_eq!(sessions[1]["duration_minutes"], 25);
+        assert_eq!(sessions[1]["start_time"], start.to_rfc3339());
+        
+        Ok(())
+    }
+    
+    #[test]
+    fn test_sessions_keep_their_recorded_offset() -> Result<()> {
+        let db = test_db()?;
+        
+        let tokyo = FixedOffset::east_opt(9 * 3600).unwrap();
+        let start = (Local::now() - Duration::hours(1)).with_timezone(&tokyo);
+        db.save_session(&Session::new(start, start + Duration::minutes(25)))?;
+        
+        let session = &db.sessions()?[0];
+        assert_eq!(session.start.offset(), &tokyo);
+        assert_eq!(session.start, start);
+        assert_eq!(session.minutes(), 25);
+        
+        // Local-time queries see the same instant
+        assert_eq!(db.session_times()?[0].0, start);
+        
+        Ok(())
+    }
+    
+    #[test]
+    fn test_stats_queries_on_empty_database() -> Result<()> {
+        let db = test_db()?;
+        
+        assert_eq!(db.total_sessions()?, 0);
+        assert_eq!(db.lifetime_pomodoros()?, 0);
+        assert_eq!(db.total_focus_minutes()?, 0);
+        assert!(db.top_days(5, None)?.is_empty());
+        
+        Ok(())
+    }
+    
+    #[test]
+    fn test_note_on_latest_session() -> Result<()> {
+        let db = test_db()?;
+        
+        assert_eq!(db.latest_session_id()?, None);
+        
+        let now = Local::now();
+        db.save_session(&work(now - Duration::hours(1), now - Duration::minutes(35), 1, true))?;
+        db.save_session(&work(now - Duration::minutes(30), now - Duration::minutes(5), 2, true))?;
+        
+        let latest = db.latest_session_id()?.unwrap();
+        db.update_note(latest, "fixed the parser bug", true)?;
+        db.update_note(latest, "wrote tests", true)?;
+        
+        let notes: Vec<Option<String>> = db.conn
+            .prepare("SELECT notes FROM sessions ORDER BY id")?
+            .query_map([], |row| row.get(0))?
+            .collect::<Result<_>>()?;
+        assert_eq!(notes, vec![None, Some("fixed the parser bug\nwrote tests".to_string())]);
+        
+        db.update_note(latest, "replaced", false)?;
+        let note: String = db.conn.query_row("SELECT notes FROM sessions WHERE id = ?", [latest], |row| row.get(0))?;
+        assert_eq!(note, "replaced");
+        
+        Ok(())
+    }
+    
+    #[test]
+    fn test_completed_accepts_mixed_representations() -> Result<()> {
+        let db = test_db()?;
+        
+        let start = Local::now().to_rfc3339();
+        for completed in ["1", "'true'", "'false'", "0", "'TRUE '", "2", "0.5"] {
+            db.conn.execute(
+                &format!(
+                    "INSERT INTO sessions (start_time, end_time, pomodoro_count, completed)
+                     VALUES (?1, ?1, 1, {})",
+                    completed
+                ),
+                params![start],
+            )?;
+        }
+        
+        // Dirty rows read back correctly before any migration
+        let mut flags: Vec<bool> = db.sessions()?.iter().map(|s| s.completed).collect();
+        flags.sort_unstable();
+        assert_eq!(flags, vec![false, false, true, true, true, true, true]);
+        
+        // Re-running initialize normalizes storage to 0/1 integers
+        db.initialize()?;
+        let non_integer: i64 = db.conn.query_row(
+            "SELECT COUNT(*) FROM sessions WHERE typeof(completed) != 'integer' OR completed NOT IN (0, 1)",
+            [],
+            |row| row.get(0),
+        )?;
+        assert_eq!(non_integer, 0);
+        // Any nonzero number counted as completed before, and still does
+        assert_eq!(db.lifetime_pomodoros()?, 5);
+        
+        Ok(())
+    }
+    
+    #[test]
+    fn test_top_tags_ranks_and_buckets() -> Result<()> {
+        let db = test_db()?;
+        
+        let start = Local::now() - Duration::days(1);
+        let log = |minutes: i64, tag: Option<&str>| {
+            let session = work(start, start + Duration::minutes(minutes), 1, true);
+            db.save_session(&Session { tag: tag.map(str::to_string), ..session })
+        };
+        log(25, Some("rust"))?;
+        log(25, Some("rust"))?;
+        log(50, Some("docs"))?;
+        log(30, None)?;
+        log(20, Some("  "))?;
+        log(10, Some("email"))?;
+        
+        let top = db.top_tags(None, 10)?;
+        let ranked: Vec<(Option<&str>, i64)> = top.iter().map(|t| (t.tag.as_deref(), t.minutes)).collect();
+        
+        // Blank tags join the untagged bucket; ties sort by name with untagged last
+        assert_eq!(
+            ranked,
+            vec![(Some("docs"), 50), (Some("rust"), 50), (None, 50), (Some("email"), 10)]
+        );
+        
+        assert_eq!(db.top_tags(None, 2)?.len(), 2);
+        assert!(db.top_tags(Some(Local::now()), 10)?.is_empty());
+        
+        Ok(())
+    }
+    
+    #[test]
+    fn test_sessions_spanning_midnight_count_for_start_day() -> Result<()> {
+        use chrono::TimeZone;
+        
+        let db = test_db()?;
+        
+        let start = Local.with_ymd_and_hms(2024, 3, 14, 23, 50, 0).unwrap();
+        db.save_session(&work(start, start + Duration::minutes(25), 1, true))?;
+        
+        let start_day = start.date_naive();
+        let next_day = start_day.succ_opt().unwrap();
+        assert_eq!(db.day_summary(start_day)?, DaySummary { pomodoros: 1, focus_minutes: 25 });
+        assert_eq!(db.day_summary(next_day)?, DaySummary::default());
+        
+        let top = db.top_days(5, None)?;
+        assert_eq!(top.len(), 1);
+        assert_eq!(top[0].day, "2024-03-14");
+        assert_eq!(top[0].minutes, 25);
+        
+        Ok(())
+    }
+    
+    #[test]
+    fn test_total_and_top_days_round_alike() -> Result<()> {
+        use chrono::TimeZone;
+        
+        let db = test_db()?;
+        
+        let start = Local.with_ymd_and_hms(2024, 3, 14, 9, 0, 0).unwrap();
+        db.save_session(&work(start, start + Duration::seconds(25 * 60 + 40), 1, true))?;
+        
+        assert_eq!(db.total_focus_minutes()?, 26);
+        assert_eq!(db.top_days(5, None)?[0].minutes, 26);
+        
+        Ok(())
+    }
+    
+    #[test]
+    fn test_work_totals_and_top_days_respect_since() -> Result<()> {
+        use chrono::TimeZone;
+        
+        let db = test_db()?;
+        
+        let old = Local.with_ymd_and_hms(2024, 3, 1, 9, 0, 0).unwrap();
+        let recent = Local.with_ymd_and_hms(2024, 3, 14, 9, 0, 0).unwrap();
+        db.save_session(&work(old, old + Duration::minutes(25), 1, true))?;
+        db.save_session(&work(recent, recent + Duration::minutes(10), 1, false))?;
+        
+        let all = db.work_totals(None)?;
+        assert_eq!((all.sessions, all.pomodoros, all.minutes), (2, 1, 35));
+        
+        let since = Local.with_ymd_and_hms(2024, 3, 10, 0, 0, 0).unwrap();
+        let recent_totals = db.work_totals(Some(since))?;
+        assert_eq!((recent_totals.sessions, recent_totals.pomodoros, recent_totals.minutes), (1, 0, 10));
+        
+        let days = db.top_days(5, Some(since))?;
+        assert_eq!(days.len(), 1);
+        assert_eq!(days[0].minutes, 10);
+        
+        Ok(())
+    }
+    
+    #[test]
+    fn test_pomodoros_since_rest_restarts_after_a_full_break() -> Result<()> {
+        use chrono::TimeZone;
+        
+        let db = test_db()?;
+        
+        let at = |minute: i64| Local.with_ymd_and_hms(2024, 3, 14, 9, 0, 0).unwrap() + Duration::minutes(minute);
+        let rest = |start: i64, taken: bool| Session {
+            kind: TimerType::Break,
+            completed: taken,
+            ..Session::new(at(start), at(start + 5))
+        };
+        let day = at(0).date_naive();
+        
+        db.save_session(&work(at(0), at(25), 1, true))?;
+        db.save_session(&rest(25, true))?;
+        db.save_session(&work(at(30), at(55), 2, true))?;
+        db.save_session(&rest(55, false))?;
+        db.save_session(&work(at(60), at(85), 2, false))?;
+        db.save_session(&work(at(90), at(115), 3, true))?;
+        assert_eq!(db.pomodoros_since_rest(day)?, 2);
+        
+        db.save_session(&rest(115, true))?;
+        assert_eq!(db.pomodoros_since_rest(day)?, 0);
+        assert_eq!(db.pomodoros_since_rest(day.succ_opt().unwrap())?, 0);
+        
+        Ok(())
+    }
+    
+    #[test]
+    fn test_import_skips_duplicates() -> Result<()> {
+        let db = test_db()?;
+        
+        let start = Local::now() - Duration::hours(2);
+        let sessions = vec![
+            Session::new(start, start + Duration::minutes(50)),
+            Session::new(start + Duration::hours(1), start + Duration::minutes(90)),
+        ];
+        
+        assert_eq!(db.import_sessions(&sessions)?, 2);
+        assert_eq!(db.import_sessions(&sessions)?, 0);
+        assert_eq!(db.total_sessions()?, 2);
+        
+        // Imported entries aren't pomodoros
+        assert_eq!(db.lifetime_pomodoros()?, 0);
+        
+        Ok(())
+    }
+    
+    #[test]
+    fn test_breaks_are_kept_out_of_work_stats() -> Result<()> {
+        let db = test_db()?;
+        
+        let start = Local::now() - Duration::hours(1);
+        db.save_session(&work(start, start + Duration::minutes(25), 1, true))?;
+        let rest = Session {
+            kind: TimerType::Break,
+            completed: true,
+            ..Session::new(start + Duration::minutes(25), start + Duration::minutes(30))
+        };
+        db.save_session(&rest)?;
+        
+        assert_eq!(db.total_sessions()?, 1);
+        assert_eq!(db.lifetime_pomodoros()?, 1);
+        assert_eq!(db.day_summary(start.date_naive())?.focus_minutes, 25);
+        assert_eq!(db.sessions()?.len(), 1);
+        assert_eq!(db.work_and_break_minutes(None)?, (25, 5));
+        assert_eq!(db.work_and_break_minutes(Some(Local::now()))?, (0, 0));
+        
+        Ok(())
+    }
+    
+    #[test]
+    fn test_stats_tolerate_zero_and_null_durations() -> Result<()> {
+        let db = test_db()?;
+        
+        // A zero-length row today, and yesterday a row whose end time
+        // SQLite can't parse, so that day's SUM is NULL
+        use chrono::TimeZone;
+        
+        let now = Local.with_ymd_and_hms(2024, 3, 14, 10, 0, 0).unwrap();
+        let today = now.to_rfc3339();
+        let yesterday = (now - Duration::days(1)).to_rfc3339();
+        for (start, end) in [(today.as_str(), today.as_str()), (yesterday.as_str(), "not a time")] {
+            db.conn.execute(
+                "INSERT INTO sessions (start_time, end_time, pomodoro_count, completed)
+                 VALUES (?1, ?2, 1, 1)",
+                params![start, end],
+            )?;
+        }
+        
+        let days = db.top_days(5, None)?;
+        assert_eq!(days.len(), 2);
+        assert!(days.iter().all(|day| day.minutes == 0));
+        assert_eq!(db.total_focus_minutes()?, 0);
+        assert_eq!(db.day_summary(now.date_naive())?.focus_minutes, 0);
+        assert_eq!(db.top_tags(None, 5)?[0].minutes, 0);
+        assert_eq!(db.work_and_break_minutes(None)?, (0, 0));
+        
+        Ok(())
+    }
+    
+    #[test]
+    fn test_merge_skips_sessions_already_present() -> Result<()> {
+        let db = test_db()?;
+        
+        let start = Local::now() - Duration::hours(3);
+        let shared = work(start, start + Duration::minutes(25), 1, true);
+        db.save_session(&shared)?;
+        
+        // The other machine ran an older version, without tags or kinds
+        let path = std::env::temp_dir().join(format!("rusty_pomodoro_merge_{}.db", std::process::id()));
+        std::fs::remove_file(&path).ok();
+        let other = Connection::open(&path)?;
+        other.execute(
+            "CREATE TABLE sessions (id INTEGER PRIMARY KEY, start_time TEXT NOT NULL, end_time TEXT NOT NULL,
+                                    pomodoro_count INTEGER NOT NULL, completed BOOLEAN NOT NULL)",
+            [],
+        )?;
+        let later = start + Duration::hours(1);
+        for (session_start, completed) in [(start, "1"), (later, "'true'")] {
+            other.execute(
+                &format!("INSERT INTO sessions (start_time, end_time, pomodoro_count, completed) VALUES (?1, ?2, 1, {})", completed),
+                params![session_start.to_rfc3339(), (session_start + Duration::minutes(25)).to_rfc3339()],
+            )?;
+        }
+        drop(other);
+        
+        assert_eq!(db.merge_from(&path)?, MergeCounts { inserted: 1, skipped: 1 });
+        assert_eq!(db.total_sessions()?, 2);
+        assert_eq!(db.lifetime_pomodoros()?, 2);
+        
+        // Merging again adds nothing, and the other file is left as it was
+        assert_eq!(db.merge_from(&path)?, MergeCounts { inserted: 0, skipped: 2 });
+        let other_columns: i64 = Connection::open(&path)?.query_row(
+            "SELECT COUNT(*) FROM pragma_table_info('sessions')",
+            [],
+            |row| row.get(0),
+        )?;
+        assert_eq!(other_columns, 5);
+        
+        std::fs::remove_file(&path).ok();
+        
+        Ok(())
+    }
+    
+    #[test]
+    fn test_search_notes_with_and_without_index() -> Result<()> {
+        let db = Database { conn: Connection::open(":memory:")? };
+        let start = Local::now() - Duration::hours(3);
+        
+        // A note saved before the index existed is picked up by the migration
+        db.conn.execute_batch(
+            "CREATE TABLE sessions (id INTEGER PRIMARY KEY, start_time TEXT NOT NULL, end_time TEXT NOT NULL,
+                                    pomodoro_count INTEGER NOT NULL, completed INTEGER NOT NULL, notes TEXT)",
+        )?;
+        db.conn.execute(
+            "INSERT INTO sessions (start_time, end_time, pomodoro_count, completed, notes) VALUES (?1, ?1, 1, 1, 'old parser notes')",
+            params![start.to_rfc3339()],
+        )?;
+        db.initialize()?;
+        assert!(db.has_notes_index()?);
+        
+        let later = start + Duration::hours(1);
+        db.save_session(&Session { notes: Some("refactoring the parser".to_string()), ..work(later, later, 1, true) })?;
+        let id = db.save_session(&work(later, later, 2, true))?;
+        db.update_note(id, "lunch", false)?;
+        
+        for search in [Database::search_notes_fts, Database::search_notes_like] {
+            let hits = search(&db, "parser", 10)?;
+            assert_eq!(hits.len(), 2);
+            
+            let hits = search(&db, "refac PARSER", 10)?;
+            assert_eq!(hits.len(), 1);
+            assert_eq!(hits[0].notes.as_deref(), Some("refactoring the parser"));
+            
+            // Edited notes are found by their new text only
+            assert_eq!(search(&db, "lunch", 10)?.len(), 1);
+            assert!(search(&db, "  ", 10)?.is_empty());
+            assert!(search(&db, "\"50%", 10)?.is_empty());
+        }
+        
+        db.update_note(id, "dinner", false)?;
+        assert!(db.search_notes("lunch", 10)?.is_empty());
+        
+        Ok(())
+    }
+    
+    #[test]
+    fn test_validate_reports_bad_rows() -> Result<()> {
+        let db = test_db()?;
+        
+        let start = Local::now() - Duration::hours(1);
+        db.save_session(&work(start, start + Duration::minutes(25), 1, true))?;
+        assert!(db.validate()?.is_empty());
+        
+        let start = start.to_rfc3339();
+        let earlier = (Local::now() - Duration::hours(2)).to_rfc3339();
+        for (start_time, end_time, kind) in [
+            (start.as_str(), earlier.as_str(), "work"),
+            ("yesterday", start.as_str(), "work"),
+            (start.as_str(), start.as_str(), "nap"),
+        ] {
+            db.conn.execute(
+                "INSERT INTO sessions (start_time, end_time, pomodoro_count, completed, kind) VALUES (?1, ?2, 0, 0, ?3)",
+                params![start_time, end_time, kind],
+            )?;
+        }
+        
+        assert_eq!(
+            db.validate()?,
+            vec![
+                Issue::EndsBeforeStart { id: 2 },
+                Issue::UnreadableTime { id: 3, column: "start_time", value: "yesterday".to_string() },
+                Issue::UnknownKind { id: 4, kind: "nap".to_string() },
+            ]
+        );
+        
+        // An old table is reported by column, before any rows are read
+        let old = Database { conn: Connection::open(":memory:")? };
+        old.conn.execute_batch("CREATE TABLE sessions (id INTEGER PRIMARY KEY, start_time TEXT, end_time TEXT)")?;
+        assert_eq!(old.validate()?.len(), 7);
+        
+        Ok(())
+    }
+    
+    #[test]
+    fn test_break_summary_counts_skipped_breaks() -> Result<()> {
+        let db = test_db()?;
+        
+        assert_eq!(db.break_summary(None)?, BreakSummary { taken: 0, skipped: 0, rest_minutes: 0 });
+        assert_eq!(db.break_summary(None)?.average_minutes(), None);
+        
+        let start = Local::now() - Duration::hours(3);
+        db.save_session(&work(start, start + Duration::minutes(25), 1, true))?;
+        for (offset, minutes, completed) in [(25, 5, true), (60, 15, true), (90, 1, false)] {
+            let begin = start + Duration::minutes(offset);
+            db.save_session(&Session {
+                kind: TimerType::Break,
+                ..work(begin, begin + Duration::minutes(minutes), 0, completed)
+            })?;
+        }
+        
+        let summary = db.break_summary(None)?;
+        assert_eq!(summary, BreakSummary { taken: 2, skipped: 1, rest_minutes: 21 });
+        assert_eq!(summary.average_minutes(), Some(7.0));
+        assert_eq!(db.break_summary(Some(start + Duration::minutes(50)))?.taken, 1);
+        
+        Ok(())
+    }
+    
+    #[test]
+    fn test_achievements_unlock_once() -> Result<()> {
+        let db = test_db()?;
+        assert!(db.unlocked_achievements()?.is_empty());
+        
+        let first = Local::now() - Duration::days(3);
+        assert!(db.unlock_achievement("first_pomodoro", first)?);
+        assert!(!db.unlock_achievement("first_pomodoro", Local::now())?);
+        
+        let unlocked = db.unlocked_achievements()?;
+        assert_eq!(unlocked.len(), 1);
+        assert_eq!(unlocked["first_pomodoro"].timestamp(), first.timestamp());
+        
+        // Checking after a session unlocks what its totals reach, just once
+        db.save_session(&work(first, first + Duration::minutes(25), 1, true))?;
+        assert!(crate::achievements::check(&db)?.is_empty());
+        
+        db.conn.execute("DELETE FROM achievements", [])?;
+        let ids: Vec<&str> = crate::achievements::check(&db)?.iter().map(|a| a.id).collect();
+        assert_eq!(ids, vec!["first_pomodoro"]);
+        assert!(crate::achievements::check(&db)?.is_empty());
+        
+        Ok(())
+    }
+    
+    #[test]
+    fn test_completion_counts_by_window() -> Result<()> {
+        let db = test_db()?;
+        
+        let today = Local::now().date_naive();
+        assert_eq!(db.completion_counts(today - Duration::days(6), 7)?, (0, 0));
+        
+        let at = |days_ago: i64| {
+            (today - Duration::days(days_ago)).and_hms_opt(12, 0, 0).unwrap().and_local_timezone(Local).unwrap()
+        };
+        for (days_ago, completed) in [(0, true), (6, false), (7, true), (13, true), (14, false)] {
+            db.save_session(&work(at(days_ago), at(days_ago) + Duration::minutes(25), 1, completed))?;
+        }
+        
+        assert_eq!(db.completion_counts(today - Duration::days(6), 7)?, (1, 2));
+        assert_eq!(db.completion_counts(today - Duration::days(13), 7)?, (2, 2));
+        
+        Ok(())
+    }
+    
+    #[test]
+    fn test_sessions_on_day_in_start_order() -> Result<()> {
+        let db = test_db()?;
+        
+        let today = Local::now().date_naive();
+        let at = |day: NaiveDate, hour| day.and_hms_opt(hour, 0, 0).unwrap().and_local_timezone(Local).unwrap();
+        db.save_session(&work(at(today, 14), at(today, 14) + Duration::minutes(25), 2, true))?;
+        db.save_session(&work(at(today, 9), at(today, 9) + Duration::minutes(25), 1, true))?;
+        let yesterday = today - Duration::days(1);
+        db.save_session(&work(at(yesterday, 9), at(yesterday, 9) + Duration::minutes(25), 1, true))?;
+        
+        let ids: Vec<Option<i64>> = db.sessions_on(today)?.iter().map(|s| s.id).collect();
+        assert_eq!(ids, vec![Some(2), Some(1)]);
+        assert!(db.sessions_on(today + Duration::days(1))?.is_empty());
+        
+        Ok(())
+    }
+    
+    #[test]
+    fn test_list_tags_with_counts() -> Result<()> {
+        let db = test_db()?;
+        assert!(db.list_tags()?.is_empty());
+        
+        let start = Local::now() - Duration::hours(2);
+        for tag in [Some("writing"), Some("Writing"), Some("writing "), Some("api"), None, Some("  ")] {
+            db.save_session(&Session {
+                tag: tag.map(str::to_string),
+                ..work(start, start + Duration::minutes(25), 1, true)
+            })?;
+        }
+        db.save_session(&Session { kind: TimerType::Break, ..work(start, start, 0, true) })?;
+        
+        assert_eq!(
+            db.list_tags()?,
+            vec![
+                (Some("api".to_string()), 1),
+                (Some("Writing".to_string()), 1),
+                (Some("writing".to_string()), 2),
+                (None, 2),
+            ]
+        );
+        
+        Ok(())
+    }
+    
+    #[test]
+    fn test_query_sessions_filters() -> Result<()> {
+        use chrono::{Datelike, TimeZone};
+        
+        let db = test_db()?;
+        
+        let day = |d: u32| Local.with_ymd_and_hms(2024, 3, d, 9, 0, 0).unwrap();
+        for (d, tag) in [(11, Some("api")), (12, Some("writing")), (13, Some(" api ")), (14, None), (15, Some("api"))] {
+            db.save_session(&Session {
+                tag: tag.map(str::to_string),
+                ..work(day(d), day(d) + Duration::minutes(25), 1, true)
+            })?;
+        }
+        db.save_session(&Session { kind: TimerType::Break, ..work(day(13), day(13), 0, true) })?;
+        
+        let days = |filter: SessionFilter| -> Result<Vec<u32>> {
+            Ok(db.query_sessions(&filter)?.iter().map(|s| s.start.day()).collect())
+        };
+        assert_eq!(days(SessionFilter::default())?, vec![15, 14, 13, 12, 11]);
+        assert_eq!(days(SessionFilter { breaks: true, ..Default::default() })?, vec![15, 14, 13, 13, 12, 11]);
+        assert_eq!(days(SessionFilter { limit: Some(2), ..Default::default() })?, vec![15, 14]);
+        assert_eq!(days(SessionFilter { tag: Some("api".to_string()), ..Default::default() })?, vec![15, 13, 11]);
+        
+        // Both ends are whole days and inclusive
+        let from = Some(day(12).date_naive());
+        let to = Some(day(14).date_naive());
+        assert_eq!(days(SessionFilter { from, to, ..Default::default() })?, vec![14, 13, 12]);
+        assert_eq!(
+            days(SessionFilter { from, tag: Some("api".to_string()), limit: Some(1), ..Default::default() })?,
+            vec![15]
+        );
+        
+        Ok(())
+    }
+    
+    #[test]
+    fn test_reclassify_session() -> Result<()> {
+        let db = test_db()?;
+        
+        use chrono::TimeZone;
+        
+        let start = Local.with_ymd_and_hms(2024, 3, 14, 9, 0, 0).unwrap();
+        let id = db.save_session(&Session { energy: Some(3), ..work(start, start + Duration::minutes(25), 1, true) })?;
+        assert_eq!(db.lifetime_pomodoros()?, 1);
+        
+        // A pomodoro recorded by mistake stops counting as focus once it's a break
+        assert!(db.reclassify_session(id, TimerType::Break)?);
+        let rest = db.session(id)?.unwrap();
+        assert_eq!((rest.kind, rest.completed, rest.energy), (TimerType::Break, true, None));
+        assert_eq!(db.day_summary(start.date_naive())?, DaySummary::default());
+        
+        // Turned back into work it's kept as focus time, but not as a pomodoro
+        assert!(db.reclassify_session(id, TimerType::Work)?);
+        let session = db.session(id)?.unwrap();
+        assert_eq!((session.kind, session.completed), (TimerType::Work, false));
+        assert_eq!(db.day_summary(start.date_naive())?, DaySummary { pomodoros: 0, focus_minutes: 25 });
+        
+        // Reclassifying as the same kind changes nothing
+        assert!(db.reclassify_session(id, TimerType::Work)?);
+        assert_eq!(db.session(id)?, Some(session));
+        
+        assert!(!db.reclassify_session(id + 1, TimerType::Break)?);
+        assert_eq!(db.session(id + 1)?, None);
+        
+        Ok(())
+    }
+    
+    #[test]
+    fn test_focus_minutes_by_day() -> Result<()> {
+        use chrono::TimeZone;
+        
+        let db = test_db()?;
+        
+        let at = |day: u32, hour: u32, min: u32| Local.with_ymd_and_hms(2024, 3, day, hour, min, 0).unwrap();
+        db.save_session(&work(at(12, 9, 0), at(12, 9, 25), 1, true))?;
+        db.save_session(&work(at(13, 9, 0), at(13, 9, 25), 1, true))?;
+        db.save_session(&work(at(13, 10, 0), at(13, 10, 10), 1, false))?;
+        db.save_session(&Session { kind: TimerType::Break, ..Session::new(at(13, 9, 25), at(13, 9, 30)) })?;
+        
+        let days = db.focus_minutes_by_day(at(13, 0, 0))?;
+        assert_eq!(days.into_iter().collect::<Vec<_>>(), vec![(NaiveDate::from_ymd_opt(2024, 3, 13).unwrap(), 35)]);
+        
+        Ok(())
+    }
+    
+    #[test]
+    fn test_intention_met_is_stored() -> Result<()> {
+        let db = test_db()?;
+        
+        let start = Local::now() - Duration::hours(1);
+        let id = db.save_session(&Session {
+            notes: Some("intention: Finish the API docs".to_string()),
+            intention_met: Some(true),
+            ..work(start, start + Duration::minutes(25), 1, true)
+        })?;
+        let plain = db.save_session(&work(start + Duration::minutes(30), start + Duration::minutes(55), 2, true))?;
+        
+        assert_eq!(db.session(id)?.and_then(|s| s.intention_met), Some(true));
+        assert_eq!(db.session(plain)?.and_then(|s| s.intention_met), None);
+        
+        // Breaks have no intention to meet
+        db.reclassify_session(id, TimerType::Break)?;
+        assert_eq!(db.session(id)?.and_then(|s| s.intention_met), None);
+        
+        Ok(())
+    }
 }
\ No newline at end of file
@@ -0,0 +1,126 @@
+use chrono::{DateTime, Duration, Local};
+
+use crate::day;
+use crate::models::Session;
+use crate::timer::TimerType;
+
+/// Tags given to demo work sessions; `None` leaves one untagged
+const TAGS: [Option<&str>; 5] = [Some("writing"), Some("api"), Some("review"), Some("learning"), None];
+
+/// Made-up but plausible history for `demo-data`: up to `count` work
+/// sessions over the `days` days up to `now`, each followed by a break.
+///
+/// Each day's sessions run back to back from a morning start, mostly 25
+/// minutes with a long break after every fourth, and most complete. Ones
+/// that would end after `now` or run into the next day are left out. The
+/// same `seed` always gives the same sessions.
+pub fn sessions(count: usize, days: u32, seed: u64, now: DateTime<Local>) -> Vec<Session> {
+    let mut rng = fastrand::Rng::with_seed(seed);
+    let days = days.max(1) as usize;
+    let today = day::of(&now);
+    
+    let mut per_day = vec![0; days];
+    for _ in 0..count {
+        per_day[rng.usize(..days)] += 1;
+    }
+    
+    let mut sessions = Vec::new();
+    for (ago, &work_sessions) in per_day.iter().enumerate().rev() {
+        let day = today - Duration::days(ago as i64);
+        let start = day.and_hms_opt(rng.u32(8..11), 0, 0);
+        let Some(mut at) = start.and_then(|time| time.and_local_timezone(Local).earliest()) else {
+            continue;
+        };
+        at += Duration::minutes(rng.i64(0..60));
+        let until = day::bounds(day).1.min(now);
+        
+        let mut pomodoros = 0;
+        for i in 0..work_sessions {
+            let minutes = match rng.u8(..10) {
+                0 => 15,
+                1 => 50,
+                _ => 25,
+            };
+            let completed = rng.u8(..10) < 8;
+            let end = at + Duration::minutes(minutes);
+            let rest = if (i + 1) % 4 == 0 { 15 } else { 5 };
+            let back = end + Duration::minutes(rest);
+            if back > until {
+                break;
+            }
+            
+            pomodoros += u64::from(completed);
+            sessions.push(Session {
+                pomodoro_count: pomodoros,
+                completed,
+                energy: rng.bool().then(|| rng.u8(1..=5)),
+                tag: TAGS[rng.usize(..TAGS.len())].map(str::to_string),
+                ..Session::new(at, end)
+            });
+            sessions.push(Session {
+                kind: TimerType::Break,
+                completed: true,
+                pomodoro_count: pomodoros,
+                ..Session::new(end, back)
+            });
+            at = back + Duration::minutes(rng.i64(0..20));
+        }
+    }
+    
+    sessions
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+    
+    #[test]
+    fn test_demo_sessions_are_reproducible() {
+        let now = Local.with_ymd_and_hms(2024, 3, 14, 23, 0, 0).unwrap();
+        let today = now.date_naive();
+        let sessions = sessions(40, 10, 7, now);
+        
+        assert_eq!(sessions, super::sessions(40, 10, 7, now));
+        assert_ne!(sessions, super::sessions(40, 10, 8, now));
+        
+        let work: Vec<&Session> = sessions.iter().filter(|s| s.kind == TimerType::Work).collect();
+        assert_eq!(work.len(), 40);
+        assert_eq!(sessions.len(), 80);
+        
+        // In order, without overlaps, and within the last 10 days
+        let first_day = today - Duration::days(9);
+        for pair in sessions.windows(2) {
+            assert!(pair[0].end <= pair[1].start);
+        }
+        for session in &sessions {
+            assert!(session.start < session.end);
+            let day = session.start.date_naive();
+            assert!(day >= first_day && day <= today);
+        }
+    }
+    
+    #[test]
+    fn test_demo_sessions_end_by_now() {
+        let now = Local.with_ymd_and_hms(2024, 3, 14, 14, 0, 0).unwrap();
+        let sessions = sessions(40, 1, 7, now);
+        
+        assert!(!sessions.is_empty() && sessions.len() < 80);
+        assert!(sessions.iter().all(|session| session.end <= now));
+    }
+    
+    #[test]
+    fn test_dense_demo_sessions_stay_within_their_day() {
+        let now = Local.with_ymd_and_hms(2024, 3, 14, 23, 0, 0).unwrap();
+        let sessions = sessions(500, 3, 7, now);
+        
+        assert!(!sessions.is_empty() && sessions.len() < 1000);
+        for pair in sessions.windows(2) {
+            assert!(pair[0].end <= pair[1].start);
+        }
+        for session in &sessions {
+            let (_, next_day) = day::bounds(day::of(&session.start));
+            assert!(session.end <= next_day);
+        }
+    }
+}
\ No newline at end of file
@@ -0,0 +1,136 @@
+use std::{
+    error::Error,
+    fs::File,
+    io::{self, BufRead, IsTerminal, Read},
+    path::Path,
+};
+
+use crate::config::Config;
+
+/// Environment variable holding the database passphrase
+pub const PASSPHRASE_ENV: &str = "RUSTY_POMODORO_PASSPHRASE";
+
+/// First bytes of every unencrypted SQLite file. SQLCipher encrypts the
+/// header too, so an encrypted file starts with random-looking bytes.
+const SQLITE_HEADER: &[u8; 16] = b"SQLite format 3\0";
+
+/// The passphrase to open the database with, or `None` when
+/// `encrypt_database` is off.
+///
+/// Taken from the first line of stdin with `from_stdin`, else from
+/// `database_passphrase` in the config, then the `RUSTY_POMODORO_PASSPHRASE`
+/// environment variable, and otherwise asked for on the terminal, so it's
+/// needed on every command.
+pub fn passphrase(config: &Config, from_stdin: bool) -> Result<Option<String>, Box<dyn Error>> {
+    if !config.encrypt_database {
+        return Ok(None);
+    }
+    if !cfg!(feature = "encryption") {
+        return Err("encrypt_database is set, but this build has no encryption support; \
+                    reinstall with `--features encryption`"
+            .into());
+    }
+    
+    let given = if from_stdin {
+        let mut line = String::new();
+        io::stdin().lock().read_line(&mut line)?;
+        Some(line.trim_end_matches(['\r', '\n']).to_string())
+    } else {
+        config
+            .database_passphrase
+            .clone()
+            .or_else(|| std::env::var(PASSPHRASE_ENV).ok())
+    };
+    let passphrase = match given {
+        Some(passphrase) => passphrase,
+        None if io::stdin().is_terminal() => prompt()?,
+        None => {
+            return Err(format!(
+                "the session database is encrypted; set {} to its passphrase to use it without a terminal",
+                PASSPHRASE_ENV
+            )
+            .into())
+        }
+    };
+    if passphrase.is_empty() {
+        return Err("the database passphrase can't be empty".into());
+    }
+    
+    Ok(Some(passphrase))
+}
+
+#[cfg(feature = "encryption")]
+fn prompt() -> io::Result<String> {
+    rpassword::prompt_password("Database passphrase: ")
+}
+
+#[cfg(not(feature = "encryption"))]
+fn prompt() -> io::Result<String> {
+    Err(io::Error::new(io::ErrorKind::Unsupported, "this build has no encryption support"))
+}
+
+/// Whether `path` holds a database that isn't plain SQLite, which for a
+/// sessions database means SQLCipher. Missing and empty files aren't.
+pub fn is_encrypted(path: &Path) -> io::Result<bool> {
+    let mut file = match File::open(path) {
+        Ok(file) => file,
+        Err(e) if e.kind() == io::ErrorKind::NotFound => return Ok(false),
+        Err(e) => return Err(e),
+    };
+    
+    let mut header = Vec::with_capacity(SQLITE_HEADER.len());
+    file.by_ref().take(SQLITE_HEADER.len() as u64).read_to_end(&mut header)?;
+    Ok(!header.is_empty() && header != SQLITE_HEADER)
+}
+
+/// Rewrites the plain database at `path` encrypted with `passphrase`,
+/// replacing the original only once the encrypted copy is complete.
+#[cfg(feature = "encryption")]
+pub fn encrypt_in_place(path: &Path, passphrase: &str) -> rusqlite::Result<()> {
+    let encrypted = path.with_extension("db.encrypting");
+    let _ = std::fs::remove_file(&encrypted);
+    
+    let plain = rusqlite::Connection::open(path)?;
+    plain.execute(
+        "ATTACH DATABASE ?1 AS encrypted KEY ?2",
+        rusqlite::params![encrypted.to_string_lossy(), passphrase],
+    )?;
+    plain.query_row("SELECT sqlcipher_export('encrypted')", [], |_| Ok(()))?;
+    plain.execute("DETACH DATABASE encrypted", [])?;
+    drop(plain);
+    
+    std::fs::rename(&encrypted, path).map_err(|e| rusqlite::Error::ToSqlConversionFailure(Box::new(e)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::TempDir;
+    
+    #[test]
+    fn test_is_encrypted_reads_the_header() {
+        let dir = TempDir::new("header");
+        
+        let plain = dir.join("plain.db");
+        rusqlite::Connection::open(&plain)
+            .and_then(|conn| conn.execute("CREATE TABLE t (x)", []))
+            .unwrap();
+        assert!(!is_encrypted(&plain).unwrap());
+        
+        let scrambled = dir.join("scrambled.db");
+        std::fs::write(&scrambled, [0x5a; 4096]).unwrap();
+        assert!(is_encrypted(&scrambled).unwrap());
+        
+        // New databases start out as missing or empty files
+        let empty = dir.join("empty.db");
+        std::fs::write(&empty, []).unwrap();
+        assert!(!is_encrypted(&empty).unwrap());
+        assert!(!is_encrypted(&dir.join("missing.db")).unwrap());
+    }
+    
+    #[test]
+    fn test_no_passphrase_unless_enabled() {
+        let config = Config { database_passphrase: Some("hunter2".to_string()), ..Config::default() };
+        assert_eq!(passphrase(&config, false).unwrap(), None);
+    }
+}
\ No newline at end of file
@@ -0,0 +1,267 @@
+use chrono::NaiveDate;
+use serde::Serialize;
+use std::collections::BTreeMap;
+
+use crate::models::Session;
+
+/// Output formats supported by `export`.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, clap::ValueEnum)]
+pub enum ExportFormat {
+    #[default]
+    Json,
+    Csv,
+    /// Tab-separated, for pasting into spreadsheets
+    Tsv,
+}
+
+/// How `export --rollup` totals up sessions instead of listing them.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, clap::ValueEnum)]
+pub enum Rollup {
+    /// One record per day
+    Daily,
+}
+
+/// A session as exported.
+///
+/// Field names are part of the export format, so renaming them breaks
+/// anything that consumes the export.
+#[derive(Debug, Serialize)]
+pub struct ExportedSession {
+    pub id: i64,
+    pub start_time: String,
+    pub end_time: String,
+    pub pomodoro_count: u64,
+    pub completed: bool,
+    pub duration_minutes: i64,
+    pub notes: Option<String>,
+}
+
+impl From<&Session> for ExportedSession {
+    fn from(session: &Session) -> Self {
+        ExportedSession {
+            id: session.id.unwrap_or_default(),
+            start_time: session.start.to_rfc3339(),
+            end_time: session.end.to_rfc3339(),
+            pomodoro_count: session.pomodoro_count,
+            completed: session.completed,
+            duration_minutes: session.minutes(),
+            notes: session.notes.clone(),
+        }
+    }
+}
+
+/// Column names shared by the delimited formats
+const HEADER: [&str; 7] = [
+    "id",
+    "start_time",
+    "end_time",
+    "pomodoro_count",
+    "completed",
+    "duration_minutes",
+    "notes",
+];
+
+/// A day's work sessions totalled up, as exported by `export --rollup daily`.
+///
+/// Like `ExportedSession`, the field names are part of the export format.
+#[derive(Debug, PartialEq, Serialize)]
+pub struct DayRollup {
+    pub date: NaiveDate,
+    /// Completed pomodoros
+    pub completed: i64,
+    pub focus_minutes: i64,
+    /// Whether `completed` reached the daily goal
+    pub goal_met: bool,
+}
+
+/// Column names of a delimited rollup
+const ROLLUP_HEADER: [&str; 4] = ["date", "completed", "focus_minutes", "goal_met"];
+
+/// Totals per day, oldest first, for the days with work sessions.
+pub fn daily_rollup(sessions: &[Session], goal: u32) -> Vec<DayRollup> {
+    let mut days: BTreeMap<NaiveDate, (i64, i64)> = BTreeMap::new();
+    for session in sessions {
+        let (completed, minutes) = days.entry(crate::day::of(&session.start)).or_default();
+        *completed += i64::from(session.completed);
+        *minutes += session.minutes();
+    }
+    
+    days.into_iter()
+        .map(|(date, (completed, focus_minutes))| DayRollup {
+            date,
+            completed,
+            focus_minutes,
+            goal_met: completed >= i64::from(goal),
+        })
+        .collect()
+}
+
+/// Renders daily rollups in `format`.
+pub fn rollup_to(days: &[DayRollup], format: ExportFormat) -> serde_json::Result<String> {
+    let rows = days.iter().map(|day| {
+        vec![
+            day.date.to_string(),
+            day.completed.to_string(),
+            day.focus_minutes.to_string(),
+            day.goal_met.to_string(),
+        ]
+    });
+    
+    Ok(match format {
+        ExportFormat::Json => serde_json::to_string_pretty(days)?,
+        ExportFormat::Csv => delimited(&ROLLUP_HEADER, rows, ",", escape_csv),
+        ExportFormat::Tsv => delimited(&ROLLUP_HEADER, rows, "\t", escape_tsv),
+    })
+}
+
+/// Renders sessions as a pretty-printed JSON array.
+pub fn to_json(sessions: &[Session]) -> serde_json::Result<String> {
+    let records: Vec<ExportedSession> = sessions.iter().map(ExportedSession::from).collect();
+    serde_json::to_string_pretty(&records)
+}
+
+/// Renders sessions as CSV, quoting fields that contain delimiters.
+pub fn to_csv(sessions: &[Session]) -> String {
+    to_delimited(sessions, ",", escape_csv)
+}
+
+/// Renders sessions as TSV, escaping tabs and line breaks so every session
+/// stays on one row.
+pub fn to_tsv(sessions: &[Session]) -> String {
+    to_delimited(sessions, "\t", escape_tsv)
+}
+
+fn to_delimited(sessions: &[Session], delimiter: &str, escape: fn(&str) -> String) -> String {
+    let rows = sessions.iter().map(ExportedSession::from).map(|session| {
+        vec![
+            session.id.to_string(),
+            session.start_time,
+            session.end_time,
+            session.pomodoro_count.to_string(),
+            session.completed.to_string(),
+            session.duration_minutes.to_string(),
+            session.notes.unwrap_or_default(),
+        ]
+    });
+    
+    delimited(&HEADER, rows, delimiter, escape)
+}
+
+/// A header line, then a line per row with each field escaped.
+fn delimited(
+    header: &[&str],
+    rows: impl Iterator<Item = Vec<String>>,
+    delimiter: &str,
+    escape: fn(&str) -> String,
+) -> String {
+    let mut out = header.join(delimiter);
+    out.push('\n');
+    
+    for fields in rows {
+        let row: Vec<String> = fields.iter().map(|field| escape(field)).collect();
+        out.push_str(&row.join(delimiter));
+        out.push('\n');
+    }
+    
+    out
+}
+
+fn escape_csv(field: &str) -> String {
+    if field.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", field.replace('"', "\"\""))
+    } else {
+        field.to_string()
+    }
+}
+
+fn escape_tsv(field: &str) -> String {
+    field
+        .replace('\\', "\\\\")
+        .replace('\t', "\\t")
+        .replace('\n', "\\n")
+        .replace('\r', "\\r")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::{Duration, FixedOffset, Local, TimeZone};
+    
+    fn session(notes: Option<&str>) -> Session {
+        let start = Local.with_ymd_and_hms(2024, 3, 14, 9, 0, 0).unwrap();
+        Session {
+            id: Some(7),
+            pomodoro_count: 1,
+            completed: true,
+            notes: notes.map(str::to_string),
+            ..Session::new(start, start + Duration::minutes(25))
+        }
+    }
+    
+    #[test]
+    fn test_tsv_escapes_tabs_and_newlines() {
+        let tsv = to_tsv(&[session(Some("api\tdocs\nand tests")), session(None)]);
+        let lines: Vec<&str> = tsv.lines().collect();
+        
+        assert_eq!(lines.len(), 3);
+        assert_eq!(lines[0], "id\tstart_time\tend_time\tpomodoro_count\tcompleted\tduration_minutes\tnotes");
+        assert!(lines[1].starts_with("7\t2024-03-14T09:00:00"));
+        assert!(lines[1].ends_with("\t1\ttrue\t25\tapi\\tdocs\\nand tests"));
+        assert!(lines[2].ends_with("\t25\t"));
+    }
+    
+    #[test]
+    fn test_csv_quotes_special_fields() {
+        let csv = to_csv(&[session(Some("said \"done\", finally"))]);
+        
+        assert!(csv.lines().nth(1).unwrap().ends_with(",25,\"said \"\"done\"\", finally\""));
+    }
+    
+    #[test]
+    fn test_export_keeps_recorded_offset() {
+        // Recorded in Tokyo, across midnight there; exported as it was stored
+        // rather than rewritten into whatever zone the export runs in
+        let tokyo = FixedOffset::east_opt(9 * 3600).unwrap();
+        let start = tokyo.with_ymd_and_hms(2024, 3, 14, 23, 40, 0).unwrap();
+        let exported = ExportedSession::from(&Session::new(start, start + Duration::minutes(35)));
+        
+        assert_eq!(exported.start_time, "2024-03-14T23:40:00+09:00");
+        assert_eq!(exported.end_time, "2024-03-15T00:15:00+09:00");
+        assert_eq!(exported.duration_minutes, 35);
+        
+        // Ending in another offset (e.g. after a DST change) still counts real time
+        let new_york = FixedOffset::west_opt(5 * 3600).unwrap();
+        let session = Session {
+            end: (start + Duration::minutes(25)).with_timezone(&new_york),
+            ..Session::new(start, start)
+        };
+        let exported = ExportedSession::from(&session);
+        assert_eq!(exported.end_time, "2024-03-14T10:05:00-05:00");
+        assert_eq!(exported.duration_minutes, 25);
+    }
+    
+    #[test]
+    fn test_daily_rollup() {
+        let day = |date: u32, hour: u32, completed: bool| {
+            let start = Local.with_ymd_and_hms(2024, 3, date, hour, 0, 0).unwrap();
+            Session { completed, ..Session::new(start, start + Duration::minutes(25)) }
+        };
+        // Newest first, as queried
+        let sessions = [day(15, 10, true), day(14, 11, false), day(14, 10, true), day(14, 9, true)];
+        let days = daily_rollup(&sessions, 2);
+        
+        assert_eq!(days.len(), 2);
+        assert_eq!(days[0], DayRollup {
+            date: NaiveDate::from_ymd_opt(2024, 3, 14).unwrap(),
+            completed: 2,
+            focus_minutes: 75,
+            goal_met: true,
+        });
+        assert_eq!((days[1].completed, days[1].focus_minutes, days[1].goal_met), (1, 25, false));
+        
+        let csv = rollup_to(&days, ExportFormat::Csv).unwrap();
+        assert_eq!(csv, "date,completed,focus_minutes,goal_met\n2024-03-14,2,75,true\n2024-03-15,1,25,false\n");
+        let json = rollup_to(&days, ExportFormat::Json).unwrap();
+        assert!(json.contains("\"date\": \"2024-03-14\""));
+    }
+}
\ No newline at end of file
@@ -0,0 +1,118 @@
+use std::{
+    io,
+    process::{Command, Stdio},
+};
+
+/// Values available to the `on_session_end` command template.
+pub struct SessionEnd<'a> {
+    pub minutes: i64,
+    pub completed: bool,
+    pub tag: Option<&'a str>,
+    pub pomodoros: u64,
+}
+
+/// Splits a command template into arguments and fills in `{minutes}`,
+/// `{completed}`, `{tag}` and `{pomodoros}`.
+///
+/// Arguments are split on whitespace, with single or double quotes grouping
+/// words. Substitution happens per argument and no shell is involved, so a
+/// tag containing spaces or shell syntax stays a single literal argument.
+pub fn render_args(template: &str, event: &SessionEnd) -> Vec<String> {
+    split_args(template)
+        .into_iter()
+        .map(|arg| {
+            arg.replace("{minutes}", &event.minutes.to_string())
+                .replace("{completed}", &event.completed.to_string())
+                .replace("{tag}", event.tag.unwrap_or(""))
+                .replace("{pomodoros}", &event.pomodoros.to_string())
+        })
+        .collect()
+}
+
+/// Starts the hook command without waiting for it.
+///
+/// Output is discarded so nothing interferes with the TUI, and a background
+/// thread reaps the child once it exits.
+pub fn spawn(template: &str, event: &SessionEnd) -> io::Result<()> {
+    let args = render_args(template, event);
+    let Some((program, rest)) = args.split_first() else {
+        return Ok(());
+    };
+    
+    let mut child = Command::new(program)
+        .args(rest)
+        .stdin(Stdio::null())
+        .stdout(Stdio::null())
+        .stderr(Stdio::null())
+        .spawn()?;
+    
+    std::thread::spawn(move || child.wait());
+    
+    Ok(())
+}
+
+fn split_args(template: &str) -> Vec<String> {
+    let mut args = Vec::new();
+    let mut current = String::new();
+    let mut in_arg = false;
+    let mut quote: Option<char> = None;
+    
+    for c in template.chars() {
+        match (quote, c) {
+            (Some(q), c) if c == q => quote = None,
+            (Some(_), c) => current.push(c),
+            (None, '"' | '\'') => {
+                quote = Some(c);
+                in_arg = true;
+            }
+            (None, c) if c.is_whitespace() => {
+                if in_arg {
+                    args.push(std::mem::take(&mut current));
+                    in_arg = false;
+                }
+            }
+            (None, c) => {
+                current.push(c);
+                in_arg = true;
+            }
+        }
+    }
+    
+    if in_arg {
+        args.push(current);
+    }
+    
+    args
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    
+    #[test]
+    fn test_render_args_substitutes_fields() {
+        let event = SessionEnd {
+            minutes: 25,
+            completed: true,
+            tag: Some("client work; rm -rf"),
+            pomodoros: 3,
+        };
+        
+        assert_eq!(
+            render_args("notify-send 'Done: {tag}' \"{minutes} min\" #{pomodoros} {completed}", &event),
+            vec!["notify-send", "Done: client work; rm -rf", "25 min", "#3", "true"]
+        );
+    }
+    
+    #[test]
+    fn test_render_args_without_tag() {
+        let event = SessionEnd {
+            minutes: 12,
+            completed: false,
+            tag: None,
+            pomodoros: 0,
+        };
+        
+        assert_eq!(render_args("echo {tag} ''  {minutes}", &event), vec!["echo", "", "", "12"]);
+    }
+}
\ No newline at end of file
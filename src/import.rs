@@ -0,0 +1,329 @@
+use chrono::{DateTime, Local, NaiveDate, NaiveDateTime, NaiveTime};
+use serde::Deserialize;
+use std::{
+    error::Error,
+    fs::File,
+    io::Read,
+    path::{Path, PathBuf},
+};
+
+use crate::models::Session;
+
+/// Column layouts of the CSV exports that `import` understands.
+#[derive(Debug, Clone, Copy, PartialEq, clap::ValueEnum)]
+pub enum CsvMapping {
+    /// Toggl Track detailed report
+    Toggl,
+    /// Clockify detailed report
+    Clockify,
+}
+
+/// Where the fields we need live in an export, by header name.
+struct Columns {
+    start_date: &'static str,
+    start_time: &'static str,
+    end_date: &'static str,
+    end_time: &'static str,
+    description: &'static str,
+    project: &'static str,
+}
+
+impl CsvMapping {
+    fn columns(self) -> Columns {
+        match self {
+            CsvMapping::Toggl => Columns {
+                start_date: "Start date",
+                start_time: "Start time",
+                end_date: "End date",
+                end_time: "End time",
+                description: "Description",
+                project: "Project",
+            },
+            CsvMapping::Clockify => Columns {
+                start_date: "Start Date",
+                start_time: "Start Time",
+                end_date: "End Date",
+                end_time: "End Time",
+                description: "Description",
+                project: "Project",
+            },
+        }
+    }
+}
+
+/// Date formats tried in order; Toggl writes ISO dates, Clockify US ones by default
+const DATE_FORMATS: [&str; 2] = ["%Y-%m-%d", "%m/%d/%Y"];
+
+/// Time formats tried in order, covering 24-hour and AM/PM clocks
+const TIME_FORMATS: [&str; 4] = ["%H:%M:%S", "%H:%M", "%I:%M:%S %p", "%I:%M %p"];
+
+/// Sessions read from an export, plus the rows that couldn't be used.
+pub struct Parsed {
+    pub sessions: Vec<Session>,
+    /// Line number and reason for each skipped row
+    pub skipped: Vec<(u64, String)>,
+}
+
+/// Reads time entries from a CSV export.
+///
+/// Entries become sessions with the description as the note and the project
+/// as the tag. They aren't pomodoros, so they count towards focus time but
+/// not towards pomodoro totals. Malformed rows are skipped and reported.
+pub fn parse_csv<R: Read>(input: R, mapping: CsvMapping) -> Result<Parsed, Box<dyn Error>> {
+    let columns = mapping.columns();
+    let mut reader = csv::ReaderBuilder::new().flexible(true).from_reader(input);
+    
+    let headers = reader.headers()?.clone();
+    let index = |name: &str| {
+        headers
+            .iter()
+            .position(|header| header.trim().trim_start_matches('\u{feff}') == name)
+            .ok_or_else(|| format!("missing column '{}' for the {:?} mapping", name, mapping))
+    };
+    let start_date = index(columns.start_date)?;
+    let start_time = index(columns.start_time)?;
+    let end_date = index(columns.end_date)?;
+    let end_time = index(columns.end_time)?;
+    let description = index(columns.description).ok();
+    let project = index(columns.project).ok();
+    
+    let mut parsed = Parsed { sessions: Vec::new(), skipped: Vec::new() };
+    for record in reader.records() {
+        let record = match record {
+            Ok(record) => record,
+            Err(e) => {
+                let line = e.position().map(|p| p.line()).unwrap_or(0);
+                parsed.skipped.push((line, e.to_string()));
+                continue;
+            }
+        };
+        let line = record.position().map(|p| p.line()).unwrap_or(0);
+        let field = |i: usize| record.get(i).unwrap_or("").trim();
+        let text = |i: Option<usize>| i.map(field).filter(|s| !s.is_empty()).map(str::to_string);
+        
+        let times = parse_local(field(start_date), field(start_time))
+            .and_then(|start| parse_local(field(end_date), field(end_time)).map(|end| (start, end)));
+        match times {
+            Ok((start, end)) if end > start => parsed.sessions.push(Session {
+                notes: text(description),
+                tag: text(project),
+                ..Session::new(start, end)
+            }),
+            Ok(_) => parsed.skipped.push((line, "ends before it starts".to_string())),
+            Err(reason) => parsed.skipped.push((line, reason)),
+        }
+    }
+    
+    Ok(parsed)
+}
+
+/// Flat files that earlier versions kept sessions in, in the data
+/// directory, in the layout `export` still writes
+pub const LEGACY_FILES: [&str; 2] = ["sessions.json", "sessions.csv"];
+
+/// A session in the `export` layout. Only the times are required, so
+/// hand-written files and older exports without some columns still load.
+#[derive(Deserialize)]
+struct ExportedRecord {
+    start_time: String,
+    end_time: String,
+    #[serde(default)]
+    pomodoro_count: u64,
+    #[serde(default)]
+    completed: bool,
+    #[serde(default)]
+    notes: Option<String>,
+    #[serde(default)]
+    tag: Option<String>,
+}
+
+impl ExportedRecord {
+    fn into_session(self) -> Result<Session, String> {
+        let time = |value: &str| {
+            DateTime::parse_from_rfc3339(value).map_err(|_| format!("unrecognized timestamp '{}'", value))
+        };
+        let (start, end) = (time(&self.start_time)?, time(&self.end_time)?);
+        if end <= start {
+            return Err("ends before it starts".to_string());
+        }
+        
+        Ok(Session {
+            pomodoro_count: self.pomodoro_count,
+            completed: self.completed,
+            notes: self.notes.filter(|notes| !notes.is_empty()),
+            tag: self.tag.filter(|tag| !tag.is_empty()),
+            ..Session::new(start, end)
+        })
+    }
+}
+
+/// The first legacy session file found in `data_dir`, if any.
+pub fn find_legacy(data_dir: &Path) -> Option<PathBuf> {
+    LEGACY_FILES.iter().map(|name| data_dir.join(name)).find(|path| path.is_file())
+}
+
+/// Reads a legacy session file, as JSON or CSV depending on its extension.
+/// Unusable records are skipped and reported, numbered from 1 for JSON and
+/// by line for CSV.
+pub fn parse_legacy(path: &Path) -> Result<Parsed, Box<dyn Error>> {
+    let file = File::open(path)?;
+    let mut parsed = Parsed { sessions: Vec::new(), skipped: Vec::new() };
+    
+    if path.extension().is_some_and(|ext| ext == "json") {
+        let records: Vec<serde_json::Value> = serde_json::from_reader(file)?;
+        for (i, record) in records.into_iter().enumerate() {
+            let session = serde_json::from_value::<ExportedRecord>(record)
+                .map_err(|e| e.to_string())
+                .and_then(ExportedRecord::into_session);
+            match session {
+                Ok(session) => parsed.sessions.push(session),
+                Err(reason) => parsed.skipped.push((i as u64 + 1, reason)),
+            }
+        }
+    } else {
+        let mut reader = csv::Reader::from_reader(file);
+        let headers = reader.headers()?.clone();
+        for record in reader.records() {
+            let record = match record {
+                Ok(record) => record,
+                Err(e) => {
+                    let line = e.position().map(|p| p.line()).unwrap_or(0);
+                    parsed.skipped.push((line, e.to_string()));
+                    continue;
+                }
+            };
+            let line = record.position().map(|p| p.line()).unwrap_or(0);
+            let session = record
+                .deserialize::<ExportedRecord>(Some(&headers))
+                .map_err(|e| e.to_string())
+                .and_then(ExportedRecord::into_session);
+            match session {
+                Ok(session) => parsed.sessions.push(session),
+                Err(reason) => parsed.skipped.push((line, reason)),
+            }
+        }
+    }
+    
+    Ok(parsed)
+}
+
+/// Combines separate date and time fields into a local timestamp.
+fn parse_local(date: &str, time: &str) -> Result<DateTime<Local>, String> {
+    let date = DATE_FORMATS
+        .iter()
+        .find_map(|format| NaiveDate::parse_from_str(date, format).ok())
+        .ok_or_else(|| format!("unrecognized date '{}'", date))?;
+    let time = TIME_FORMATS
+        .iter()
+        .find_map(|format| NaiveTime::parse_from_str(time, format).ok())
+        .ok_or_else(|| format!("unrecognized time '{}'", time))?;
+    
+    NaiveDateTime::new(date, time)
+        .and_local_timezone(Local)
+        .earliest()
+        .ok_or_else(|| format!("{} {} doesn't exist in the local timezone", date, time))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::TempDir;
+    
+    #[test]
+    fn test_parse_toggl_export() {
+        let csv = "\
+User,Email,Client,Project,Task,Description,Billable,Start date,Start time,End date,End time,Duration,Tags
+Ana,ana@example.com,,Website,,Landing page,No,2024-03-14,09:00:00,2024-03-14,09:50:00,00:50:00,
+Ana,ana@example.com,,,,,No,2024-03-14,13:00:00,2024-03-14,13:30:00,00:30:00,
+Ana,ana@example.com,,Website,,Broken,No,14/03/2024,10:00:00,2024-03-14,11:00:00,01:00:00,
+Ana,ana@example.com,,Website,,Backwards,No,2024-03-14,12:00:00,2024-03-14,11:00:00,01:00:00,
+";
+        let parsed = parse_csv(csv.as_bytes(), CsvMapping::Toggl).unwrap();
+        
+        assert_eq!(parsed.sessions.len(), 2);
+        assert_eq!(parsed.sessions[0].minutes(), 50);
+        assert_eq!(parsed.sessions[0].tag.as_deref(), Some("Website"));
+        assert_eq!(parsed.sessions[0].notes.as_deref(), Some("Landing page"));
+        assert!(!parsed.sessions[0].completed);
+        assert_eq!(parsed.sessions[1].tag, None);
+        
+        let lines: Vec<u64> = parsed.skipped.iter().map(|(line, _)| *line).collect();
+        assert_eq!(lines, [4, 5]);
+        assert!(parsed.skipped[0].1.contains("14/03/2024"));
+    }
+    
+    #[test]
+    fn test_parse_clockify_export() {
+        let csv = "\
+Project,Client,Description,Task,User,Group,Email,Tags,Billable,Start Date,Start Time,End Date,End Time,Duration (h)
+Docs,,API reference,,Ana,,ana@example.com,,No,03/14/2024,11:45:00 PM,03/15/2024,12:10:00 AM,00:25:00
+";
+        let parsed = parse_csv(csv.as_bytes(), CsvMapping::Clockify).unwrap();
+        
+        assert_eq!(parsed.sessions.len(), 1);
+        assert_eq!(parsed.sessions[0].minutes(), 25);
+        assert_eq!(parsed.sessions[0].start.date_naive(), NaiveDate::from_ymd_opt(2024, 3, 14).unwrap());
+    }
+    
+    #[test]
+    fn test_missing_columns_are_an_error() {
+        let err = parse_csv("Start,End\n".as_bytes(), CsvMapping::Toggl).err().unwrap();
+        
+        assert!(err.to_string().contains("Start date"));
+    }
+    
+    #[test]
+    fn test_legacy_files_round_trip_exports() {
+        let dir = TempDir::new("legacy");
+        assert_eq!(find_legacy(&dir), None);
+        
+        let start = Local::now() - chrono::Duration::hours(3);
+        let sessions = vec![
+            Session { id: Some(1), pomodoro_count: 1, completed: true, ..Session::new(start, start + chrono::Duration::minutes(25)) },
+            Session {
+                id: Some(2),
+                notes: Some("wrote, then \"tested\"".to_string()),
+                ..Session::new(start + chrono::Duration::hours(1), start + chrono::Duration::minutes(70))
+            },
+        ];
+        
+        let json = dir.join("sessions.json");
+        std::fs::write(&json, crate::export::to_json(&sessions).unwrap()).unwrap();
+        std::fs::write(dir.join("sessions.csv"), crate::export::to_csv(&sessions)).unwrap();
+        // JSON is preferred when both are there
+        assert_eq!(find_legacy(&dir), Some(json));
+        
+        for name in LEGACY_FILES {
+            let parsed = parse_legacy(&dir.join(name)).unwrap();
+            assert!(parsed.skipped.is_empty(), "{}: {:?}", name, parsed.skipped);
+            let imported = parsed.sessions;
+            assert_eq!(imported.len(), 2);
+            assert_eq!(imported[0].start, sessions[0].start);
+            assert_eq!((imported[0].pomodoro_count, imported[0].completed), (1, true));
+            assert_eq!(imported[1].notes, sessions[1].notes);
+            assert_eq!(imported[1].minutes(), 10);
+        }
+    }
+    
+    #[test]
+    fn test_legacy_records_without_times_are_skipped() {
+        let dir = TempDir::new("legacy_bad");
+        let path = dir.join("sessions.json");
+        std::fs::write(
+            &path,
+            r#"[
+                {"start_time": "2024-03-14T09:00:00+01:00", "end_time": "2024-03-14T09:25:00+01:00"},
+                {"start_time": "yesterday", "end_time": "2024-03-14T09:25:00+01:00"},
+                {"end_time": "2024-03-14T09:25:00+01:00"},
+                {"start_time": "2024-03-14T10:00:00+01:00", "end_time": "2024-03-14T09:25:00+01:00"}
+            ]"#,
+        )
+        .unwrap();
+        
+        let parsed = parse_legacy(&path).unwrap();
+        assert_eq!(parsed.sessions.len(), 1);
+        assert_eq!(parsed.sessions[0].start.to_rfc3339(), "2024-03-14T09:00:00+01:00");
+        let numbers: Vec<u64> = parsed.skipped.iter().map(|(n, _)| *n).collect();
+        assert_eq!(numbers, [2, 3, 4]);
+    }
+}
\ No newline at end of file
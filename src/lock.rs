@@ -0,0 +1,83 @@
+/// Tells whether the user's screen is currently locked.
+pub trait LockMonitor {
+    fn is_locked(&mut self) -> bool;
+}
+
+/// Fallback for platforms without lock detection; never reports a lock.
+pub struct NoLockMonitor;
+
+impl LockMonitor for NoLockMonitor {
+    fn is_locked(&mut self) -> bool {
+        false
+    }
+}
+
+/// Returns the lock monitor for the current platform.
+pub fn monitor() -> Box<dyn LockMonitor> {
+    #[cfg(target_os = "linux")]
+    {
+        Box::new(linux::LogindLockMonitor::new())
+    }
+    
+    #[cfg(not(target_os = "linux"))]
+    {
+        Box::new(NoLockMonitor)
+    }
+}
+
+#[cfg(target_os = "linux")]
+mod linux {
+    use super::LockMonitor;
+    use std::{
+        process::Command,
+        time::{Duration, Instant},
+    };
+    
+    /// How often to ask logind, since each check spawns a process
+    const POLL_INTERVAL: Duration = Duration::from_secs(2);
+    
+    /// Reads systemd-logind's `LockedHint` for the current session over D-Bus.
+    pub struct LogindLockMonitor {
+        last_check: Option<Instant>,
+        locked: bool,
+    }
+    
+    impl LogindLockMonitor {
+        pub fn new() -> Self {
+            Self {
+                last_check: None,
+                locked: false,
+            }
+        }
+        
+        fn query() -> Option<bool> {
+            let session = std::env::var("XDG_SESSION_ID").unwrap_or_else(|_| "self".to_string());
+            let output = Command::new("loginctl")
+                .args(["show-session", &session, "--property=LockedHint", "--value"])
+                .output()
+                .ok()?;
+            
+            if !output.status.success() {
+                return None;
+            }
+            
+            Some(String::from_utf8_lossy(&output.stdout).trim() == "yes")
+        }
+    }
+    
+    impl LockMonitor for LogindLockMonitor {
+        fn is_locked(&mut self) -> bool {
+            let due = match self.last_check {
+                Some(checked) => checked.elapsed() >= POLL_INTERVAL,
+                None => true,
+            };
+            if due {
+                // Without logind (or outside a session) we can't tell, so assume unlocked
+                self.locked = Self::query().unwrap_or(false);
+                self.last_check = Some(Instant::now());
+            }
+            
+            self.locked
+        }
+    }
+}
\ No newline at end of file
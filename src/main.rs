@@ -1,406 +1,2293 @@
-use chrono::{DateTime, Local};
+use chrono::{Datelike, Local};
 use clap::{Parser, Subcommand};
 use crossterm::{
-    event::{self, DisableMouseCapture, EnableMouseCapture, Event, KeyCode},
-    execute,
-    terminal::{disable_raw_mode, enable_raw_mode, EnterAlternateScreen, LeaveAlternateScreen},
+    event::{self, Event, KeyCode, KeyModifiers},
+    style::Color as TextColor,
 };
-use notify_rust::Notification;
 use rusqlite::{params, Connection, Result as SqlResult};
 use std::{
-    io,
+    io::{self, IsTerminal, Write},
+    sync::atomic::{AtomicBool, Ordering},
     time::{Duration, Instant},
 };
-use tui::{
-    backend::CrosstermBackend,
-    layout::{Constraint, Direction, Layout},
-    style::{Color, Modifier, Style},
-    text::{Span, Spans},
-    widgets::{Block, Borders, Gauge, Paragraph},
-    Terminal,
-};
 
+mod config;
+mod day;
 mod db;
+mod demo;
+mod encryption;
+mod export;
+mod hook;
+mod import;
+mod lock;
+mod models;
+mod notify;
+mod onboarding;
+mod profile;
+mod report;
+mod sound;
+mod state;
+mod status;
+mod suggest;
+mod technique;
 mod timer;
 mod ui;
+mod window;
 mod analytics;
+mod achievements;
+#[cfg(test)]
+mod testing;
+
+use timer::{
+    counts_as_pomodoro, format_remaining, micro_breaks_due, remaining_secs, worth_recording, PomodoroTimer, TimerState,
+    TimerType,
+};
+use config::{Config, Source, Sourced};
+use db::{Database, SessionFilter};
+use analytics::{Align, Cell, Period, RestBalance, Trend};
+use export::{ExportFormat, Rollup};
+use lock::{LockMonitor, NoLockMonitor};
+use models::Session;
+use state::{State, Unfinished};
+use technique::{Technique, TechniqueKind};
+use window::WindowInfo;
+use ui::{Dashboard, HistoryPanel};
+
+/// Number of past sessions loaded into the history panel
+const HISTORY_LIMIT: usize = 50;
+
+/// How far into a work phase to look at the foreground window for
+/// `auto_tag`, so it sees the app you switched to rather than this terminal
+const AUTO_TAG_DELAY: Duration = Duration::from_secs(30);
+
+/// Tick length used by `--low-power`
+const LOW_POWER_TICK_MS: u64 = 500;
+
+/// How often `start --headless` prints the time left
+const PROGRESS_LINE_INTERVAL: Duration = Duration::from_secs(60);
+
+/// The same control key pressed again within this is ignored, so held
+/// keys and key repeat don't toggle pause or reset over and over
+const KEY_DEBOUNCE: Duration = Duration::from_millis(150);
 
-use timer::{TimerState, TimerType, PomodoroTimer};
-use db::Database;
+/// How much `+` and `-` change the current work phase by
+const ADJUST_STEP: Duration = Duration::from_secs(60);
+
+/// How long a micro-break reminder stays in the status block
+const MICRO_BREAK_FLASH: Duration = Duration::from_secs(10);
+
+/// Length of the two windows whose completion rates `stats` compares
+const TREND_DAYS: i64 = 7;
+
+/// Days of history `plan` averages for the recent pace
+const PLAN_PACE_DAYS: i64 = 14;
+
+/// Share of a day's focus time that `reclassify` warns about moving
+const RECLASSIFY_WARN_SHARE: f64 = 0.25;
+
+/// How long `stop` waits for the timer to exit, and `pause` and `resume`
+/// for it to report the change
+const STOP_TIMEOUT: Duration = Duration::from_secs(3);
+
+/// How often `stats --watch` reloads its figures
+const DASHBOARD_REFRESH: Duration = Duration::from_secs(5);
 
 #[derive(Parser)]
 #[command(author, version, about, long_about = None)]
 struct Cli {
     #[command(subcommand)]
     command: Option<Commands>,
+    
+    /// Use plain ASCII instead of emoji in text output
+    #[arg(long, global = true)]
+    no_emoji: bool,
+    
+    /// Don't run the first-launch setup questions
+    #[arg(long, global = true)]
+    no_onboarding: bool,
+    
+    /// Plain output without colors; setting NO_COLOR does the same
+    #[arg(long, global = true)]
+    no_color: bool,
+    
+    /// Stop with an error if the database has inconsistent rows, instead
+    /// of warning about them. Commands that control a running timer skip
+    /// the check
+    #[arg(long, global = true)]
+    strict: bool,
+    
+    /// Keep sessions, settings and state separate under this name
+    #[arg(long, global = true, value_name = "NAME")]
+    profile: Option<String>,
+    
+    /// Read the database passphrase from the first line of stdin; how
+    /// `start --detach` hands it to the background timer
+    #[arg(long, global = true, hide = true)]
+    passphrase_stdin: bool,
 }
 
 #[derive(Subcommand)]
 enum Commands {
     /// Start a new Pomodoro session
     Start {
-        /// Work duration in minutes
-        #[arg(short, long, default_value_t = 25)]
-        work: u64,
+        /// Work duration in minutes [default: 25, or from the configured preset]
+        #[arg(short, long)]
+        work: Option<u64>,
+        
+        /// Break duration in minutes [default: 5, or from the configured preset]
+        #[arg(short, long)]
+        break_time: Option<u64>,
+        
+        /// Ask for a 1-5 focus rating after each work session
+        #[arg(long)]
+        rate: bool,
+        
+        /// Only notify when a break ends, not when work ends
+        #[arg(long)]
+        break_only_notifications: bool,
+        
+        /// Use a named preset instead of --work/--break-time
+        #[arg(long, conflicts_with_all = ["work", "break_time"])]
+        preset: Option<String>,
+        
+        /// Pomodoro variant to run
+        #[arg(long, value_enum)]
+        technique: Option<TechniqueKind>,
+        
+        /// Label recorded with each session, e.g. a project name
+        #[arg(short, long)]
+        tag: Option<String>,
+        
+        /// What you mean to get done, e.g. "Finish the API docs"; shown while
+        /// you work and saved to each work session's notes
+        #[arg(long, value_name = "TEXT")]
+        intention: Option<String>,
+        
+        /// Ask for an intention before each work session, and afterwards
+        /// whether you achieved it
+        #[arg(long)]
+        ask_intention: bool,
+        
+        /// Carry on with the work phase last quit part-way through today,
+        /// as resume_on_start does
+        #[arg(long)]
+        resume_on_start: bool,
         
-        /// Break duration in minutes
-        #[arg(short, long, default_value_t = 5)]
-        break_time: u64,
+        /// Tick less often to save battery, at the cost of slower key response
+        #[arg(long)]
+        low_power: bool,
+        
+        /// Drop notifications sent within this many seconds of the previous one
+        #[arg(long, value_name = "SECONDS")]
+        compact_notifications: Option<u64>,
+        
+        /// Use bright, bold colors that are easier to tell apart
+        #[arg(long)]
+        high_contrast: bool,
+        
+        /// Announce every phase change in a descriptive notification
+        #[arg(long)]
+        announce: bool,
+        
+        /// Start without asking, even with unusually long durations
+        #[arg(short, long)]
+        yes: bool,
+        
+        /// Run the timer in the background and return to the shell
+        #[arg(long, conflicts_with = "rate")]
+        detach: bool,
+        
+        /// Run without the timer display, e.g. from scripts; end it with `stop`
+        #[arg(long)]
+        headless: bool,
+        
+        /// Cycle through work and breaks until this much focus time is in,
+        /// e.g. 2h or 1h30m, then stop. Pauses and breaks don't count
+        #[arg(long, value_name = "DURATION", value_parser = timer::parse_span)]
+        sprint: Option<Duration>,
     },
     
     /// Show productivity statistics
-    Stats,
+    Stats {
+        /// Open a live dashboard that refreshes every few seconds
+        #[arg(long)]
+        watch: bool,
+        
+        /// List the N tags with the most focus time
+        #[arg(long, value_name = "N", conflicts_with = "watch")]
+        top_tags: Option<usize>,
+        
+        /// Time window for every statistic shown, including --top-tags
+        #[arg(long, value_enum, default_value_t = Period::All)]
+        period: Period,
+        
+        /// Show the last 12 weeks as a grid of days shaded by focus time
+        #[arg(long, conflicts_with_all = ["watch", "top_tags"])]
+        calendar: bool,
+    },
+    
+    /// Show progress towards today's goals
+    Today,
+    
+    /// Forecast the weekly goal from your recent daily pace
+    Plan,
+    
+    /// List achievements, unlocked or not, with your progress towards each
+    Achievements,
+    
+    /// List every tag in use with its number of sessions
+    Tags,
+    
+    /// Rank your profiles by focus time, e.g. to compare "work" with "learning"
+    Leaderboard {
+        /// Time window to add up
+        #[arg(long, value_enum, default_value_t = Period::Month)]
+        period: Period,
+    },
+    
+    /// Fill the database with made-up sessions, to try out stats and reports
+    #[command(hide = true)]
+    DemoData {
+        /// Work sessions to add, each followed by a break; any that would
+        /// end in the future or run into the next day are left out
+        #[arg(long, default_value_t = 200)]
+        sessions: usize,
+        
+        /// Spread them over this many days, up to today
+        #[arg(long, default_value_t = 60)]
+        days: u32,
+        
+        /// Seed for the random sessions; the same seed on the same day adds
+        /// the same ones
+        #[arg(long, default_value_t = 42)]
+        seed: u64,
+        
+        /// Really add them to this profile's database
+        #[arg(long)]
+        confirm: bool,
+    },
+    
+    /// List audio output devices and play a test tone, to track down
+    /// missing sound (needs the `sound` feature)
+    SoundCheck {
+        /// Only list the devices, without playing anything
+        #[arg(long)]
+        list_devices: bool,
+    },
+    
+    /// Summarize a day against the daily goal
+    Report {
+        /// Day to report on, as YYYY-MM-DD (default: today)
+        #[arg(long)]
+        date: Option<chrono::NaiveDate>,
+        
+        /// Add a digest of the day's sessions, so a partner you send the
+        /// report to can tell if they were changed afterwards
+        #[arg(long)]
+        accountability: bool,
+    },
+    
+    /// Attach a note to the most recent session
+    Note {
+        /// Text of the note
+        text: String,
+        
+        /// Add to the existing note instead of replacing it
+        #[arg(short, long)]
+        append: bool,
+    },
     
-    /// Export session data to JSON
+    /// Find sessions by the words in their notes, best match first
+    Search {
+        /// Words to look for; each must appear, and may be the start of a longer word
+        #[arg(required = true)]
+        query: Vec<String>,
+        
+        /// Show at most this many sessions
+        #[arg(short, long, default_value_t = 20)]
+        limit: usize,
+    },
+    
+    /// List past work sessions, newest first
+    History {
+        /// Show at most this many sessions
+        #[arg(short, long, default_value_t = 20)]
+        limit: usize,
+        
+        /// Only sessions with this tag
+        #[arg(short, long)]
+        tag: Option<String>,
+        
+        /// Only sessions from this day on, as YYYY-MM-DD
+        #[arg(long)]
+        from: Option<chrono::NaiveDate>,
+        
+        /// Only sessions up to and including this day, as YYYY-MM-DD
+        #[arg(long)]
+        to: Option<chrono::NaiveDate>,
+        
+        /// Print the sessions as JSON instead of a table
+        #[arg(long)]
+        json: bool,
+        
+        /// List breaks too, e.g. to find one to reclassify
+        #[arg(long)]
+        all: bool,
+    },
+    
+    /// Record a session as work or as a break, e.g. after skipping at the
+    /// wrong moment; `history --all` shows session ids, breaks included
+    Reclassify {
+        /// Id of the session to change
+        id: i64,
+        
+        /// What the session should be recorded as
+        #[arg(long, value_enum)]
+        to: TimerType,
+    },
+    
+    /// Print the running timer's state as JSON; exits with 1 if none is running
+    Status,
+    
+    /// Follow a timer running in the background until you press q
+    Attach,
+    
+    /// Stop the running timer, saving the current session
+    Stop,
+    
+    /// Pause the running timer, e.g. from a script when a meeting starts
+    Pause,
+    
+    /// Resume the running timer after `pause`
+    Resume,
+    
+    /// Send the first-session reminder if it's due, then exit (for cron)
+    Check,
+    
+    /// Import time entries exported from another tracker
+    Import {
+        /// CSV file to read
+        #[arg(long)]
+        from_csv: std::path::PathBuf,
+        
+        /// Which tracker produced the file
+        #[arg(long, value_enum, default_value_t = import::CsvMapping::Toggl)]
+        mapping: import::CsvMapping,
+    },
+    
+    /// Add the sessions from another rusty_pomodoro database, e.g. from a
+    /// second machine; sessions already present are skipped
+    Merge {
+        /// Database file to read sessions from
+        path: std::path::PathBuf,
+    },
+    
+    /// Inspect the configuration
+    Config {
+        #[command(subcommand)]
+        action: ConfigAction,
+    },
+    
+    /// Back up the session database to a file
+    Backup {
+        /// Destination file path
+        path: String,
+    },
+    
+    /// Export session data to JSON, CSV or TSV
     Export {
         /// Output file path
         #[arg(short, long)]
         output: Option<String>,
+        
+        /// Output format
+        #[arg(short, long, value_enum, default_value_t = ExportFormat::Json, conflicts_with = "blocks")]
+        format: ExportFormat,
+        
+        /// Merge back-to-back work sessions into focus blocks
+        #[arg(long)]
+        blocks: bool,
+        
+        /// Join sessions separated by less than this many minutes
+        #[arg(long, default_value_t = 10, requires = "blocks")]
+        gap: u64,
+        
+        /// Export totals instead of sessions: one record per day with the
+        /// completed count, focus minutes and whether the goal was met
+        #[arg(long, value_enum, conflicts_with = "blocks")]
+        rollup: Option<Rollup>,
+        
+        /// Only sessions from this day on, as YYYY-MM-DD
+        #[arg(long)]
+        from: Option<chrono::NaiveDate>,
+        
+        /// Only sessions up to and including this day, as YYYY-MM-DD
+        #[arg(long)]
+        to: Option<chrono::NaiveDate>,
     },
 }
 
+#[derive(Subcommand)]
+enum ConfigAction {
+    /// Print every setting's effective value and where it came from
+    Show,
+}
+
 fn main() -> Result<(), Box<dyn std::error::Error>> {
     let cli = Cli::parse();
     
-    let db = Database::new()?;
-    db.initialize()?;
+    // Pick the profile before anything reads files; the shared config may name a default
+    let profile_name = match &cli.profile {
+        Some(name) => Some(name.clone()),
+        None => Config::load()?.profile,
+    };
+    profile::activate(profile::Profile::new(profile_name.as_deref())?);
     
-    match &cli.command {
-        Some(Commands::Start { work, break_time }) => {
-            run_pomodoro_timer(*work, *break_time, &db)?;
-        }
-        Some(Commands::Stats) => {
-            show_stats(&db)?;
-        }
-        Some(Commands::Export { output }) => {
-            export_data(&db, output)?;
-        }
-        None => {
-            // Default to starting with standard 25/5 settings
-            run_pomodoro_timer(25, 5, &db)?;
+    // Commands that only talk to a running timer use hardly any settings,
+    // so a bad value mustn't break scripts polling `status`
+    let remote = matches!(
+        cli.command,
+        Some(Commands::Status | Commands::Attach | Commands::Pause | Commands::Resume | Commands::Stop)
+    );
+    let mut config = Config::load()?;
+    if let Err(e) = config.validate() {
+        if !remote {
+            return Err(e);
         }
+        eprintln!("warning: {}", e);
     }
+    day::set_start_hour(config.day_start_hour);
+    let color = should_colorize(cli.no_color);
     
-    Ok(())
-}
-
-fn run_pomodoro_timer(work_mins: u64, break_mins: u64, db: &Database) -> Result<(), Box<dyn std::error::Error>> {
-    // Setup terminal
-    enable_raw_mode()?;
-    let mut stdout = io::stdout();
-    execute!(stdout, EnterAlternateScreen, EnableMouseCapture)?;
-    let backend = CrosstermBackend::new(stdout);
-    let mut terminal = Terminal::new(backend)?;
+    // First launch: ask a few questions before starting the timer
+    let starts_timer = matches!(cli.command, None | Some(Commands::Start { .. }));
+    if starts_timer && !cli.no_onboarding && !Config::exists() && io::stdin().is_terminal() {
+        config = onboarding::run(config)?;
+    }
     
-    // Create timer
-    let work_duration = Duration::from_secs(work_mins * 60);
-    let break_duration = Duration::from_secs(break_mins * 60);
-    let mut timer = PomodoroTimer::new(work_duration, break_duration);
+    let passphrase = encryption::passphrase(&config, cli.passphrase_stdin)?;
+    let db = Database::new(passphrase.as_deref())?;
+    db.initialize()?;
     
-    // Start timer
-    timer.start();
-    let start_time = Local::now();
-    let mut completed_pomodoros = 0;
+    let issues = if remote { Vec::new() } else { db.validate()? };
+    if !issues.is_empty() {
+        for issue in &issues {
+            eprintln!("{}: {}", if cli.strict { "error" } else { "warning" }, issue);
+        }
+        if cli.strict {
+            return Err(format!("found {} problems in the session database", issues.len()).into());
+        }
+    }
     
-    let mut last_update = Instant::now();
+    // One-time upgrade from flat-file storage. It asks first, so only when
+    // starting a timer from a terminal, never in the middle of piped output
+    if starts_timer && io::stdin().is_terminal() && io::stdout().is_terminal() {
+        offer_legacy_import(&db)?;
+    }
     
-    // Main loop
-    loop {
-        terminal.draw(|f| {
-            let size = f.size();
-            
-            // Create the layout
-            let chunks = Layout::default()
-                .direction(Direction::Vertical)
-                .margin(2)
-                .constraints(
-                    [
-                        Constraint::Length(3),
-                        Constraint::Length(3),
-                        Constraint::Length(3),
-                        Constraint::Length(3),
-                        Constraint::Min(0),
-                    ]
-                    .as_ref(),
-                )
-                .split(size);
-            
-            // Title block
-            let title = match timer.timer_type() {
-                TimerType::Work => format!("🍅 Work Session ({}m)", work_mins),
-                TimerType::Break => format!("☕ Break ({}m)", break_mins),
-            };
-            
-            let title_block = Block::default()
-                .title(title)
-                .borders(Borders::ALL);
-            
-            f.render_widget(title_block, chunks[0]);
-            
-            // Timer gauge
-            let elapsed = timer.elapsed().as_secs_f64();
-            let total = timer.total_time().as_secs_f64();
-            let percent = (elapsed / total * 100.0).min(100.0);
-            
-            let mins_left = ((total - elapsed) / 60.0).ceil() as u64;
-            let secs_left = ((total - elapsed) % 60.0).ceil() as u64;
+    // On stderr, so it never ends up in `status` JSON or an export on stdout
+    if let Some(days) = config.retention_days {
+        let pruned = db.prune_older_than(days)?;
+        if pruned > 0 {
+            eprintln!("Removed {} sessions older than {} days", pruned, days);
+        }
+    }
+    
+    match &cli.command {
+        Some(Commands::Start { work, break_time, rate, break_only_notifications, preset, technique, tag, intention, ask_intention, resume_on_start, low_power, compact_notifications, high_contrast, announce, yes, detach, headless, sprint }) => {
+            if *break_only_notifications {
+                config.notify_on_work_complete = false;
+            }
+            if let Some(technique) = technique {
+                config.technique = *technique;
+            }
+            if *low_power {
+                config.tick_ms = config.tick_ms.max(LOW_POWER_TICK_MS);
+            }
+            if let Some(secs) = compact_notifications {
+                config.notification_min_interval = *secs;
+            }
+            config.high_contrast |= *high_contrast;
+            config.announce |= *announce;
+            // Lengths given for this run win over the configured rhythm and
+            // over carrying on from an unfinished session
+            config.resume_on_start |= *resume_on_start;
+            if work.is_some() || break_time.is_some() || preset.is_some() {
+                config.sequence = None;
+                config.resume_on_start = false;
+            }
             
-            let gauge_label = format!("{:02}:{:02}", mins_left, secs_left);
+            let mut state = State::load();
+            let (work, break_time) = start_durations(*work, *break_time, preset.as_deref(), &config, &state)?;
+            let (work, break_time) = (work.value, break_time.value);
             
-            let gauge = Gauge::default()
-                .block(Block::default().borders(Borders::ALL))
-                .gauge_style(match timer.timer_type() {
-                    TimerType::Work => Style::default().fg(Color::Red),
-                    TimerType::Break => Style::default().fg(Color::Green),
-                })
-                .percent(percent as u16)
-                .label(gauge_label);
+            // Catch typos like --work 600 before the terminal is taken over
+            if !*yes && io::stdin().is_terminal() {
+                let confirmed = onboarding::confirm_durations(work, break_time, !cli.no_emoji, &mut io::stdin().lock(), &mut io::stdout())?;
+                if !confirmed {
+                    println!("Not started. Pass --yes to skip this check.");
+                    return Ok(());
+                }
+            }
             
-            f.render_widget(gauge, chunks[1]);
+            if *detach {
+                if let Some(running) = status::read()? {
+                    println!("A timer is already running (pid {})", running.pid);
+                    std::process::exit(1);
+                }
+                
+                let pid = spawn_detached(passphrase.as_deref())?;
+                println!("Timer started in the background (pid {})", pid);
+                println!("Use 'rusty_pomodoro status' or 'attach' to check on it, and 'stop' to end it");
+                return Ok(());
+            }
             
-            // Status
-            let status = match timer.state() {
-                TimerState::Running => "⏱️  Running",
-                TimerState::Paused => "⏸️  Paused",
-                TimerState::Stopped => "⏹️  Stopped",
+            let options = TimerOptions {
+                work_mins: work,
+                break_mins: break_time,
+                rate: *rate,
+                tag: tag.as_deref(),
+                intention: intention.as_deref(),
+                ask_intention: *ask_intention,
+                emoji: !cli.no_emoji,
+                color,
+                headless: *headless,
+                sprint: *sprint,
+                resume: resume_left(&config, &state),
             };
+            state.unfinished = run_pomodoro_timer(&options, &config, &db)?.map(Unfinished::new);
             
-            let status_para = Paragraph::new(status)
-                .block(Block::default().title("Status").borders(Borders::ALL));
-            
-            f.render_widget(status_para, chunks[2]);
-            
-            // Stats
-            let stats = format!("🍅 Completed: {}", completed_pomodoros);
-            let stats_para = Paragraph::new(stats)
-                .block(Block::default().title("Statistics").borders(Borders::ALL));
-            
-            f.render_widget(stats_para, chunks[3]);
-            
-            // Help
-            let help = vec![
-                Spans::from(vec![
-                    Span::raw("Press "),
-                    Span::styled("p", Style::default().add_modifier(Modifier::BOLD)),
-                    Span::raw(" to pause/resume, "),
-                    Span::styled("r", Style::default().add_modifier(Modifier::BOLD)),
-                    Span::raw(" to reset, "),
-                    Span::styled("q", Style::default().add_modifier(Modifier::BOLD)),
-                    Span::raw(" to quit"),
-                ]),
-            ];
-            
-            let help_para = Paragraph::new(help)
-                .block(Block::default().title("Help").borders(Borders::ALL));
+            if config.remember_last {
+                state.last_work = Some(work);
+                state.last_break = Some(break_time);
+            }
+            state.save()?;
+        }
+        Some(Commands::Stats { watch, top_tags, period, calendar }) => {
+            if *calendar {
+                show_calendar(&db, color, cli.no_emoji)?;
+            } else if let Some(limit) = top_tags {
+                show_top_tags(&db, *limit, *period, cli.no_emoji)?;
+            } else if *watch {
+                watch_stats(&db, &config, color)?;
+            } else {
+                show_stats(&db, &config, *period, color)?;
+            }
+        }
+        Some(Commands::Today) => {
+            show_today(&db, &config, cli.no_emoji)?;
+        }
+        Some(Commands::Leaderboard { period }) => {
+            show_profile_leaderboard(&db, *period, cli.no_emoji)?;
+        }
+        Some(Commands::DemoData { sessions, days, seed, confirm }) => {
+            if !*confirm {
+                return Err(format!(
+                    "this adds {} made-up sessions to the '{}' profile's history; pass --confirm to go ahead, \
+                     ideally with --profile demo to keep them out of your real one",
+                    sessions,
+                    profile::active().name()
+                )
+                .into());
+            }
+            let demo = demo::sessions(*sessions, *days, *seed, Local::now());
+            let added = db.import_sessions(&demo)?;
+            println!("Added {} demo sessions ({} already present)", added, demo.len() - added);
+        }
+        Some(Commands::Plan) => {
+            show_plan(&db, &config, cli.no_emoji)?;
+        }
+        Some(Commands::Achievements) => {
+            show_achievements(&db, &config, cli.no_emoji)?;
+        }
+        Some(Commands::SoundCheck { list_devices }) => {
+            sound::diagnose(*list_devices)?;
+        }
+        Some(Commands::Tags) => {
+            let tags = db.list_tags()?;
+            if tags.is_empty() {
+                println!("No sessions yet — run 'rusty_pomodoro start' to begin!");
+            } else {
+                let mut table = analytics::Table::new()
+                    .header(&["Tag", "Sessions"])
+                    .align(1, Align::Right)
+                    .color(color);
+                for (tag, count) in tags {
+                    table.row(vec![tag.unwrap_or_else(|| "(untagged)".to_string()).into(), count.to_string().into()]);
+                }
+                println!("{}", table.render());
+            }
+        }
+        Some(Commands::Report { date, accountability }) => {
+            let day = date.unwrap_or_else(day::today);
+            let summary = db.day_summary(day)?;
+            let report = report::DayReport {
+                day,
+                goal: config.daily_goal,
+                pomodoros: summary.pomodoros,
+                focus_minutes: summary.focus_minutes,
+                sessions: db.sessions_on(day)?,
+            };
             
-            f.render_widget(help_para, chunks[4]);
-        })?;
-        
-        // Handle elapsed timer
-        if timer.state() == TimerState::Running && timer.is_complete() {
-            if timer.timer_type() == TimerType::Work {
-                // Work session completed
-                completed_pomodoros += 1;
-                
-                // Record completed session in database
-                db.save_session(start_time, Local::now(), completed_pomodoros, true)?;
-                
-                // Show notification
-                Notification::new()
-                    .summary("Work Session Complete!")
-                    .body("Time for a break!")
-                    .show()?;
-                
-                timer.switch_to_break();
+            let label = config.format_date(report.day);
+            if *accountability {
+                println!("{}", report.render_accountability(&label));
             } else {
-                // Break session completed
-                Notification::new()
-                    .summary("Break Complete!")
-                    .body("Time to get back to work!")
-                    .show()?;
+                println!("{}", report.render(&label));
+            }
+        }
+        Some(Commands::Note { text, append }) => {
+            match db.latest_session_id()? {
+                Some(id) => {
+                    db.update_note(id, text, *append)?;
+                    println!("Note saved to session {}", id);
+                }
+                None => println!("No sessions yet — run 'rusty_pomodoro start' first"),
+            }
+        }
+        Some(Commands::Check) => {
+            if let Some(remind_at) = config.first_session_reminder_time()? {
+                let now = Local::now();
+                let sessions_today = db.session_count_on(day::of(&now))?;
                 
-                timer.switch_to_work();
+                // A timer that's running counts, even before its first session is saved
+                let running = status::read()?.is_some();
+                if !running && analytics::needs_first_session_nudge(now.naive_local(), remind_at, sessions_today) {
+                    notify::send("Ready to start your first pomodoro?", "Run 'rusty_pomodoro start' to begin.")?;
+                }
             }
         }
-        
-        // Check for events with small timeout for responsiveness
-        if event::poll(Duration::from_millis(100))? {
-            if let Event::Key(key) = event::read()? {
-                match key.code {
-                    KeyCode::Char('q') => {
-                        // Save any partial session before quitting
-                        if timer.timer_type() == TimerType::Work && timer.elapsed().as_secs() > 60 {
-                            db.save_session(
-                                start_time,
-                                Local::now(),
-                                completed_pomodoros,
-                                false,
-                            )?;
-                        }
-                        break;
-                    }
-                    KeyCode::Char('p') => {
-                        if timer.state() == TimerState::Running {
-                            timer.pause();
-                        } else if timer.state() == TimerState::Paused {
-                            timer.resume();
-                        }
-                    }
-                    KeyCode::Char('r') => {
-                        timer.reset();
-                    }
-                    _ => {}
+        Some(Commands::Search { query, limit }) => {
+            let results = db.search_notes(&query.join(" "), *limit)?;
+            if results.is_empty() {
+                println!("No notes match '{}'", query.join(" "));
+            }
+            for session in results {
+                let note = session.notes.as_deref().unwrap_or_default().replace('\n', " / ");
+                println!("{}  {:>3}m  {}", config.format_time(&session.start.with_timezone(&Local)), session.minutes(), note);
+            }
+        }
+        Some(Commands::History { limit, tag, from, to, json, all }) => {
+            if let (Some(from), Some(to)) = (from, to) {
+                if from > to {
+                    return Err(format!("--from {} is after --to {}", from, to).into());
                 }
             }
+            let filter = SessionFilter { breaks: *all, limit: Some(*limit), tag: tag.clone(), from: *from, to: *to };
+            show_history(&db.query_sessions(&filter)?, *json, &config, color)?;
         }
-        
-        // Update every second
-        if last_update.elapsed() >= Duration::from_secs(1) {
-            timer.update();
-            last_update = Instant::now();
+        Some(Commands::Reclassify { id, to }) => reclassify(&db, *id, *to)?,
+        Some(Commands::Status) => match status::read()? {
+            Some(current) => {
+                println!("{}", serde_json::to_string_pretty(&current.at(Local::now()))?);
+            }
+            None => {
+                println!("No session is running");
+                std::process::exit(1);
+            }
+        },
+        Some(Commands::Attach) => {
+            if status::read()?.is_none() {
+                println!("No session is running");
+                std::process::exit(1);
+            }
+            attach_timer(&ui::Theme::new(config.high_contrast, color))?;
+        }
+        Some(Commands::Pause) => remote_toggle(status::Control::Pause, "paused")?,
+        Some(Commands::Resume) => remote_toggle(status::Control::Resume, "running")?,
+        Some(Commands::Stop) => {
+            if !status::send(status::Control::Stop)? {
+                println!("No session is running");
+                std::process::exit(1);
+            }
+            
+            // Give the timer a few ticks to save its session and exit
+            let deadline = Instant::now() + STOP_TIMEOUT;
+            while status::read()?.is_some() && Instant::now() < deadline {
+                std::thread::sleep(Duration::from_millis(100));
+            }
+            match status::read()? {
+                Some(running) => println!("Asked the timer (pid {}) to stop; it hasn't exited yet", running.pid),
+                None => println!("Timer stopped"),
+            }
+        }
+        Some(Commands::Import { from_csv, mapping }) => {
+            let parsed = import::parse_csv(std::fs::File::open(from_csv)?, *mapping)?;
+            for (line, reason) in &parsed.skipped {
+                eprintln!("Skipped line {}: {}", line, reason);
+            }
+            
+            let added = db.import_sessions(&parsed.sessions)?;
+            println!(
+                "Imported {} sessions ({} already present, {} skipped)",
+                added,
+                parsed.sessions.len() - added,
+                parsed.skipped.len()
+            );
+        }
+        Some(Commands::Merge { path }) => {
+            // SQLite's own error for this only shows the URI it was given
+            if !path.is_file() {
+                return Err(format!("no database file at {}", path.display()).into());
+            }
+            if passphrase.is_some() || encryption::is_encrypted(path)? {
+                return Err("merge only works between unencrypted databases".into());
+            }
+            let counts = db.merge_from(path)?;
+            println!(
+                "Merged {} sessions from {} ({} already present)",
+                counts.inserted,
+                path.display(),
+                counts.skipped
+            );
+        }
+        Some(Commands::Config { action: ConfigAction::Show }) => {
+            show_config(&cli, &config, color)?;
+        }
+        Some(Commands::Backup { path }) => {
+            // SQLite's backup can't copy encrypted pages into a plain file
+            if passphrase.is_some() {
+                return Err("backup doesn't work on an encrypted database; \
+                            copy sessions.db itself instead, which stays encrypted"
+                    .into());
+            }
+            db.backup(std::path::Path::new(path))?;
+            println!("Database backed up to {}", path);
+        }
+        Some(Commands::Export { output, format, blocks, gap, rollup, from, to }) => {
+            if let (Some(from), Some(to)) = (from, to) {
+                if from > to {
+                    return Err(format!("--from {} is after --to {}", from, to).into());
+                }
+            }
+            let filter = SessionFilter { from: *from, to: *to, ..SessionFilter::default() };
+            if *blocks {
+                export_blocks(&db, output, *gap, &filter)?;
+            } else {
+                export_data(&db, output, *format, *rollup, &filter, config.daily_goal)?;
+            }
+        }
+        None => {
+            // Same lengths as a plain `start`: remembered, env, or the configured preset
+            let mut state = State::load();
+            let (work, break_time) = start_durations(None, None, None, &config, &state)?;
+            let options = TimerOptions {
+                work_mins: work.value,
+                break_mins: break_time.value,
+                rate: false,
+                tag: None,
+                intention: None,
+                ask_intention: false,
+                emoji: !cli.no_emoji,
+                color,
+                headless: false,
+                sprint: None,
+                resume: resume_left(&config, &state),
+            };
+            state.unfinished = run_pomodoro_timer(&options, &config, &db)?.map(Unfinished::new);
+            state.save()?;
         }
     }
     
-    // Restore terminal
-    disable_raw_mode()?;
-    execute!(
-        terminal.backend_mut(),
-        LeaveAlternateScreen,
-        DisableMouseCapture
-    )?;
-    terminal.show_cursor()?;
-    
     Ok(())
 }
 
-fn show_stats(db: &Database) -> Result<(), Box<dyn std::error::Error>> {
-    println!("📊 Productivity Statistics");
-    println!("==========================");
+/// Work and break minutes for `start`, and where each came from.
+///
+/// Explicit durations win over --preset, then the environment, then the
+/// last run's durations, then the configured preset.
+fn start_durations(
+    work: Option<u64>,
+    break_time: Option<u64>,
+    preset: Option<&str>,
+    config: &Config,
+    state: &State,
+) -> Result<(Sourced<u64>, Sourced<u64>), Box<dyn std::error::Error>> {
+    if let Some(name) = preset {
+        let preset = config.preset(name)?;
+        return Ok((
+            Sourced { value: preset.work, source: Source::Cli },
+            Sourced { value: preset.break_time, source: Source::Cli },
+        ));
+    }
     
-    // Get total number of sessions
-    let total_sessions: i64 = db.conn.query_row(
-        "SELECT COUNT(*) FROM sessions", 
-        [], 
-        |row| row.get(0)
-    )?;
-    
-    // Get completed sessions
-    let completed_sessions: i64 = db.conn.query_row(
-        "SELECT COUNT(*) FROM sessions WHERE completed = 1", 
-        [], 
-        |row| row.get(0)
-    )?;
-    
-    // Get total focus time
-    let total_minutes: i64 = db.conn.query_row(
-        "SELECT SUM(CAST((julianday(end_time) - julianday(start_time)) * 24 * 60 As Integer)) 
-         FROM sessions", 
-        [], 
-        |row| row.get(0)
-    ).unwrap_or(0);
-    
-    println!("Total Sessions: {}", total_sessions);
-    println!("Completed Sessions: {}", completed_sessions);
-    println!("Completion Rate: {}%", 
-             if total_sessions > 0 {
-                 (completed_sessions as f64 / total_sessions as f64 * 100.0).round()
-             } else {
-                 0.0
-             });
-    println!("Total Focus Time: {} hours {} minutes", total_minutes / 60, total_minutes % 60);
+    let name = config.preset.as_deref().unwrap_or("classic");
+    let defaults = config.preset(name)?;
+    let file_keys = Config::keys_in_file()?;
+    let preset_source = if file_keys.contains("preset") || config.presets.contains_key(name) {
+        Source::File
+    } else {
+        Source::Default
+    };
+    let remembered = |last: Option<u64>| last.filter(|_| config.remember_last);
     
-    // Show most productive days
-    println!("\nMost Productive Days:");
-    println!("--------------------");
+    Ok((
+        Sourced::first_of(
+            [
+                (work, Source::Cli),
+                (config::env_minutes(config::WORK_ENV)?, Source::Env),
+                (remembered(state.last_work), Source::LastRun),
+            ],
+            Sourced { value: defaults.work, source: preset_source },
+        ),
+        Sourced::first_of(
+            [
+                (break_time, Source::Cli),
+                (config::env_minutes(config::BREAK_ENV)?, Source::Env),
+                (remembered(state.last_break), Source::LastRun),
+            ],
+            Sourced { value: defaults.break_time, source: preset_source },
+        ),
+    ))
+}
+
+/// Prints every setting's effective value and where it came from.
+fn show_config(cli: &Cli, config: &Config, color: bool) -> Result<(), Box<dyn std::error::Error>> {
+    match Config::file_path() {
+        Some(path) => println!("Config file: {}", path.display()),
+        None => println!("Config file: none, using built-in defaults"),
+    }
     
-    let mut stmt = db.conn.prepare(
-        "SELECT date(start_time) as day, 
-                COUNT(*) as sessions,
-                SUM(CAST((julianday(end_time) - julianday(start_time)) * 24 * 60 As Integer)) as minutes
-         FROM sessions 
-         GROUP BY day 
-         ORDER BY minutes DESC
-         LIMIT 5"
-    )?;
+    let mut table = analytics::Table::new().header(&["Setting", "Value", "Source"]).color(color);
+    let mut row = |key: &str, setting: Sourced<String>| {
+        table.row(vec![key.into(), setting.value.into(), setting.source.to_string().into()]);
+    };
     
-    let days = stmt.query_map([], |row| {
-        let day: String = row.get(0)?;
-        let sessions: i64 = row.get(1)?;
-        let minutes: i64 = row.get(2)?;
-        
-        Ok((day, sessions, minutes))
-    })?;
+    let profile = Sourced::first_of(
+        [(cli.profile.clone(), Source::Cli), (config.profile.clone(), Source::File)],
+        Sourced { value: "default".to_string(), source: Source::Default },
+    );
+    row("profile", profile);
     
-    for day_result in days {
-        let (day, sessions, minutes) = day_result?;
-        println!("{}: {} sessions, {} hours {} minutes", 
-                 day, sessions, minutes / 60, minutes % 60);
-    }
+    let color_off = Sourced::first_of(
+        [
+            (cli.no_color.then_some("off"), Source::Cli),
+            (std::env::var_os("NO_COLOR").map(|_| "off"), Source::Env),
+        ],
+        Sourced { value: "auto", source: Source::Default },
+    );
+    row("color", Sourced { value: color_off.value.to_string(), source: color_off.source });
     
-    println!("\nTip: Run 'rusty_pomodoro export' to get detailed session data");
+    // What a plain `start` would run with
+    let (work, break_time) = start_durations(None, None, None, config, &State::load())?;
+    row("start work minutes", Sourced { value: work.value.to_string(), source: work.source });
+    row("start break minutes", Sourced { value: break_time.value.to_string(), source: break_time.source });
+    
+    for (key, mut setting) in config.describe(&Config::keys_in_file()?)? {
+        // The passphrase may come from the environment instead of the file
+        let passphrase_from_env = key == "database_passphrase"
+            && setting.source == Source::Default
+            && std::env::var_os(encryption::PASSPHRASE_ENV).is_some();
+        if passphrase_from_env {
+            setting = Sourced { value: "(hidden)".to_string(), source: Source::Env };
+        }
+        row(&key, setting);
+    }
     
+    println!("{}", table.render());
+    // Flags such as --low-power or --technique aren't settings of their own
+    println!("\nFlags given to `start` override the settings above for that run only.");
     Ok(())
 }
 
-fn export_data(db: &Database, output_path: &Option<String>) -> Result<(), Box<dyn std::error::Error>> {
-    let mut stmt = db.conn.prepare(
-        "SELECT id, start_time, end_time, pomodoro_count, completed
-         FROM sessions 
-         ORDER BY start_time DESC"
-    )?;
-    
-    #[derive(serde::Serialize)]
-    struct Session {
-        id: i64,
-        start_time: String,
-        end_time: String,
-        pomodoro_count: i64,
-        completed: bool,
-        duration_minutes: i64,
-    }
-    
-    let sessions = stmt.query_map([], |row| {
-        let id: i64 = row.get(0)?;
-        let start_time: String = row.get(1)?;
-        let end_time: String = row.get(2)?;
-        let pomodoro_count: i64 = row.get(3)?;
-        let completed: bool = row.get(4)?;
-        
-        // Calculate duration in minutes
-        let start = DateTime::parse_from_rfc3339(&start_time)
-            .unwrap()
-            .with_timezone(&Local);
-        let end = DateTime::parse_from_rfc3339(&end_time)
-            .unwrap()
-            .with_timezone(&Local);
-        
-        let duration = end.signed_duration_since(start);
-        let duration_minutes = duration.num_minutes();
-        
-        Ok(Session {
-            id,
-            start_time,
-            end_time,
-            pomodoro_count,
-            completed,
-            duration_minutes,
-        })
-    })?;
+/// How a timer run was asked for on the command line.
+#[derive(Clone, Copy)]
+struct TimerOptions<'a> {
+    work_mins: u64,
+    break_mins: u64,
+    /// Ask for a focus rating after each work session
+    rate: bool,
+    tag: Option<&'a str>,
+    /// Focus intention for the work sessions
+    intention: Option<&'a str>,
+    /// Ask for an intention before each work session
+    ask_intention: bool,
+    emoji: bool,
+    color: bool,
+    /// Run without a terminal, as the background process of `start --detach`
+    headless: bool,
+    /// Stop once this much work time has been logged
+    sprint: Option<Duration>,
+    /// Work left from an unfinished session, to run as the first work phase
+    resume: Option<Duration>,
+}
+
+/// The work left from the phase last quit part-way through today, when
+/// `resume_on_start` is on.
+fn resume_left(config: &Config, state: &State) -> Option<Duration> {
+    config.resume_on_start.then(|| state.unfinished?.left_on(day::today())).flatten()
+}
+
+/// Runs the timer until it's quit or a sprint is done, returning the work
+/// that was left if it was quit during a work phase.
+fn run_pomodoro_timer(options: &TimerOptions, config: &Config, db: &Database) -> Result<Option<Duration>, Box<dyn std::error::Error>> {
+    let TimerOptions { work_mins, break_mins, rate, tag, intention, ask_intention, emoji, color, headless, sprint, resume } = *options;
     
-    let mut all_sessions = Vec::new();
-    for session in sessions {
-        all_sessions.push(session?);
+    // Raw mode fails with an unhelpful error when piped or run from CI
+    if !headless && (!io::stdin().is_terminal() || !io::stdout().is_terminal()) {
+        return Err("the timer needs an interactive terminal; use 'start --headless' to run it without one, \
+                    or 'start --detach' to run it in the background"
+            .into());
     }
     
-    let json = serde_json::to_string_pretty(&all_sessions)?;
+    // Both guards tear down on every way out of here: q, Ctrl-C, `stop`,
+    // a finished sprint, an error or a panic. The terminal is restored
+    // first, then the status files are removed
+    let _status_files = status::Guard::install();
+    let mut terminal = if headless { None } else { Some(ui::Screen::enter(true)?) };
+    install_interrupt_handler();
     
-    match output_path {
-        Some(path) => {
-            std::fs::write(path, json)?;
-            println!("Data exported to {}", path);
+    // A custom sequence sets each phase's length in turn, looping at the end
+    let sequence = config.sequence.as_deref().map(timer::parse_sequence).transpose()?;
+    let mut step = 0;
+    
+    // Create timer
+    let (mut work_duration, mut break_duration) = match &sequence {
+        Some(steps) => (steps[0].1, steps[1].1),
+        None => (Duration::from_secs(work_mins * 60), Duration::from_secs(break_mins * 60)),
+    };
+    let mut timer = PomodoroTimer::new(work_duration, break_duration);
+    if config.bank_skipped_breaks {
+        timer.enable_banking(Duration::from_secs(config.max_banked_minutes * 60));
+    }
+    timer.set_break_scaling(config.break_scaling);
+    
+    // Work time so far in a sprint, pauses excluded. Each work phase is cut
+    // short to what's left, so the sprint ends exactly on target
+    let mut focused = Duration::ZERO;
+    
+    // Carrying on from an unfinished session, the first work phase is just
+    // what it had left, and counts as a full pomodoro when done
+    let mut resuming = resume.is_some();
+    let first_work = resume.map_or(work_duration, |left| left.min(work_duration));
+    timer.set_work_duration(first_work);
+    if let Some(target) = sprint {
+        timer.set_work_duration(first_work.min(target));
+    }
+    
+    // What each work session is for; with --ask-intention it's asked for
+    // before every work session, starting from the last one
+    let mut intention = intention.map(str::to_string);
+    let ask_for_intention = |terminal: &mut Option<ui::Screen>, current: Option<String>| match terminal {
+        Some(terminal) if ask_intention => ui::prompt_intention(terminal, current.as_deref()),
+        _ => Ok(current),
+    };
+    intention = ask_for_intention(&mut terminal, intention)?;
+    
+    if headless && resuming {
+        println!("Carrying on from where you left off: {} of work left", format_remaining(first_work.as_secs()));
+    }
+    
+    // Start timer
+    timer.start();
+    let mut phase_start = Local::now();
+    let mut completed_pomodoros = 0;
+    let mut today_pomodoros = db.day_summary(day::today())?.pomodoros.max(0) as u64;
+    let (filled_symbol, empty_symbol) = config.tally_symbols(emoji);
+    
+    let mut history = HistoryPanel::new(history_entries(db, config)?);
+    
+    let mut lock_monitor: Box<dyn LockMonitor> = if config.pause_on_lock {
+        lock::monitor()
+    } else {
+        Box::new(NoLockMonitor)
+    };
+    let mut paused_by_lock: Option<Instant> = None;
+    let mut auto_paused = Duration::ZERO;
+    
+    // An explicit --tag always wins over window detection
+    let window_info: Option<Box<dyn WindowInfo>> = (config.auto_tag && tag.is_none()).then(window::detector);
+    let mut detected_tag: Option<String> = None;
+    let mut tag_sampled = false;
+    
+    // The end-of-day summary bypasses this, since it's sent once and ends the run
+    let mut notifier = notify::Throttle::new(Duration::from_secs(config.notification_min_interval));
+    
+    let theme = ui::Theme::new(config.high_contrast, color);
+    let (workday_start, workday_end) = config.workday()?;
+    // Already checked when the config was loaded
+    let gauge_label = ui::GaugeLabel::parse(&config.gauge_label)?;
+    
+    // Today's goals shown over the timer by the overview key, fetched when opened
+    let mut overview: Option<String> = None;
+    
+    // Set by the skip key; ends the current phase on the next tick
+    let mut skip_requested = false;
+    let mut debounce = ui::KeyDebounce::new(KEY_DEBOUNCE);
+    
+    // Time the timer has run across all phases so far, excluding pauses
+    // like `elapsed()`. Time before a reset still counts; it was spent
+    let mut cycle_elapsed = Duration::ZERO;
+    
+    // Set by the invalidate key; the work phase is still saved, but not as a pomodoro
+    let mut invalidated = false;
+    // Pomodoros since the last break taken in full, for max_pomodoros_before_forced_rest.
+    // Counted from today's sessions so quitting and restarting doesn't reset it
+    let mut since_rest = db.pomodoros_since_rest(day::today())?;
+    let mut quit_requested = false;
+    // Work left in the phase the timer was quit during
+    let mut unfinished = None;
+    
+    // Only fires when the clock passes end_of_day during this run, so
+    // starting a session late in the evening doesn't end it immediately
+    let mut end_of_day = config
+        .end_of_day_time()?
+        .filter(|&time| Local::now().time() < time);
+    
+    let mut last_status_write = Instant::now();
+    
+    // When the headless progress line was last printed; cleared at each
+    // phase change so the new phase is announced straight away
+    let mut last_progress_line: Option<Instant> = None;
+    
+    // Micro-break reminders sent in this work phase, and when the last one
+    // started showing in the status block
+    let micro_break_interval = config.micro_break_interval.map(|mins| Duration::from_secs(mins * 60));
+    let mut micro_breaks_sent = 0;
+    let mut micro_break_shown: Option<Instant> = None;
+    
+    // Whether this phase has been flagged as running unusually long
+    let mut long_run_warned = false;
+    
+    // With auto_start_work off, when the next work phase was left waiting
+    // after a break, until the reminder about it goes out; and whether p or
+    // `resume` asked to start it
+    let overrun_grace = config.break_overrun_reminder.map(|mins| Duration::from_secs(mins * 60));
+    let mut waiting_since: Option<Instant> = None;
+    let mut start_requested = false;
+    
+    // A request left over from an earlier run isn't meant for this one
+    let _ = status::take_control();
+    
+    // When the timer was stopped with no key pressed since, for auto_quit_minutes
+    let mut idle_since: Option<Instant> = None;
+    
+    // Flowtime work phases run until the user ends them
+    let flow = config.technique.work_duration(work_duration).is_none();
+    let is_open_ended = |timer: &PomodoroTimer| timer.timer_type() == TimerType::Work && flow;
+    // Phase length for announcements, none when the user decides when it ends
+    let phase_length = |timer: &PomodoroTimer| (!is_open_ended(timer)).then(|| timer.total_time());
+    
+    notify_phase_start(&mut notifier, config, TimerType::Work, phase_length(&timer))?;
+    
+    // Main loop
+    loop {
+        if let Some(info) = &window_info {
+            if !tag_sampled && timer.timer_type() == TimerType::Work && timer.elapsed() >= AUTO_TAG_DELAY {
+                detected_tag = info.active_window();
+                tag_sampled = true;
+            }
+        }
+        let phase_tag = tag.or(detected_tag.as_deref());
+        
+        // Open-ended work has no length to cut, so end it once the sprint is done
+        if sprint.is_some_and(|target| is_open_ended(&timer) && focused + timer.elapsed() >= target) {
+            skip_requested = true;
+        }
+        
+        // Handle elapsed timer before drawing, so a finished phase is never
+        // shown at 00:00 with the next phase's title and colors
+        let ran_out = !is_open_ended(&timer) && timer.phase_due(config.paused_at_end);
+        let phase_over = skip_requested || ran_out;
+        let skipped = std::mem::take(&mut skip_requested);
+        
+        // A phase that ran out while paused (with paused_at_end = "complete")
+        // is wrapped up as if running, and the next phase starts paused
+        let finish_paused = ran_out && timer.state() == TimerState::Paused;
+        if finish_paused {
+            timer.resume();
+        }
+        if timer.state() == TimerState::Running && phase_over {
+            if timer.timer_type() == TimerType::Work {
+                // Work session completed; long automatic pauses mean it was
+                // interrupted, and a flow session ended very early isn't kept
+                let recorded = !skipped || worth_recording(timer.elapsed(), config.min_session_seconds);
+                // The last phase of a sprint, cut to what was left, isn't a full pomodoro
+                let resumed = std::mem::take(&mut resuming);
+                let cut_short = timer.total_time() < work_duration && !resumed;
+                let counted = recorded
+                    && !invalidated
+                    && !cut_short
+                    && counts_as_pomodoro(timer.total_time(), auto_paused, config.max_auto_pause);
+                if counted {
+                    completed_pomodoros += 1;
+                    since_rest += 1;
+                }
+                let end_time = Local::now();
+                focused += timer.elapsed();
+                let sprint_done = sprint.is_some_and(|target| focused >= target);
+                
+                // Show notification; announcements go out once the break is set up
+                if sprint_done {
+                    if config.notify_on_work_complete || config.announce {
+                        notifier.send("Sprint complete!", &format!("{} of focus — well done.", format_minutes(focused.as_secs() as i64 / 60)))?;
+                    }
+                } else if config.notify_on_work_complete && !config.announce {
+                    notifier.send("Work Session Complete!", "Time for a break!")?;
+                }
+                if config.sound && !skipped {
+                    sound::chime();
+                }
+                
+                if recorded {
+                    // Optionally ask how focused the session felt
+                    let energy = match &mut terminal {
+                        Some(terminal) if rate => ui::prompt_energy(terminal)?,
+                        _ => None,
+                    };
+                    // ...and, with --ask-intention, whether it did what it was meant to
+                    let intention_met = match (&mut terminal, &intention) {
+                        (Some(terminal), Some(intention)) if ask_intention => ui::prompt_intention_met(terminal, intention)?,
+                        _ => None,
+                    };
+                    
+                    // Record the session in the database
+                    let session = Session {
+                        pomodoro_count: completed_pomodoros,
+                        completed: counted,
+                        energy,
+                        tag: phase_tag.map(str::to_string),
+                        notes: intention_note(intention.as_deref()),
+                        intention_met,
+                        ..Session::new(phase_start, end_time)
+                    };
+                    db.save_session(&session)?;
+                    run_session_hook(config, &session);
+                    celebrate_achievements(db)?;
+                    history.set_items(history_entries(db, config)?);
+                    today_pomodoros = db.day_summary(day::today())?.pomodoros.max(0) as u64;
+                }
+                if sprint_done {
+                    break;
+                }
+                
+                if let Some(steps) = &sequence {
+                    step = (step + 1) % steps.len();
+                    break_duration = steps[step].1;
+                }
+                let break_length = config.technique.break_duration(break_duration, timer.elapsed());
+                timer.set_break_duration(break_length);
+                cycle_elapsed += timer.elapsed();
+                timer.switch_to_break();
+                last_progress_line = None;
+                notify_phase_start(&mut notifier, config, TimerType::Break, phase_length(&timer))?;
+                phase_start = Local::now();
+                auto_paused = Duration::ZERO;
+                detected_tag = None;
+                tag_sampled = false;
+                micro_breaks_sent = 0;
+                invalidated = false;
+            } else {
+                // Break session completed; skipped breaks are recorded as unfinished
+                let rest = Session {
+                    kind: TimerType::Break,
+                    completed: !skipped,
+                    pomodoro_count: completed_pomodoros,
+                    ..Session::new(phase_start, Local::now())
+                };
+                db.save_session(&rest)?;
+                
+                if config.notify_on_break_complete && !skipped && !config.announce {
+                    notifier.send("Break Complete!", "Time to get back to work!")?;
+                }
+                if config.sound && !skipped {
+                    sound::chime();
+                }
+                if skipped {
+                    timer.bank_remaining_break();
+                } else {
+                    since_rest = 0;
+                }
+                
+                cycle_elapsed += timer.elapsed();
+                intention = ask_for_intention(&mut terminal, intention)?;
+                if let Some(steps) = &sequence {
+                    step = (step + 1) % steps.len();
+                    work_duration = steps[step].1;
+                }
+                timer.set_work_duration(work_duration);
+                if let Some(target) = sprint {
+                    timer.set_work_duration(work_duration.min(target.saturating_sub(focused)));
+                }
+                timer.switch_to_work();
+                last_progress_line = None;
+                if config.auto_start_work {
+                    notify_phase_start(&mut notifier, config, TimerType::Work, phase_length(&timer))?;
+                } else {
+                    timer.stop();
+                    waiting_since = Some(Instant::now());
+                }
+                phase_start = Local::now();
+                auto_paused = Duration::ZERO;
+            }
+        }
+        if finish_paused {
+            timer.pause();
+        }
+        
+        let open_ended = is_open_ended(&timer);
+        let rest_required = timer::rest_required(timer.timer_type(), since_rest, config.max_pomodoros_before_forced_rest);
+        
+        // Nudge to stretch partway through long work phases, without pausing.
+        // Counting reminders per phase means each interval fires only once
+        if let Some(interval) = micro_break_interval {
+            if timer.timer_type() == TimerType::Work && timer.state() == TimerState::Running {
+                let due = micro_breaks_due(timer.elapsed(), interval);
+                if due > micro_breaks_sent {
+                    micro_breaks_sent = due;
+                    micro_break_shown = Some(Instant::now());
+                    notifier.send("Stretch!", "Look away from the screen and stretch; the timer keeps running.")?;
+                }
+            }
+        }
+        // The break is over but work hasn't started; nudge once, like a micro-break
+        if timer.state() != TimerState::Stopped {
+            waiting_since = None;
+        } else if let (Some(since), Some(grace)) = (waiting_since, overrun_grace) {
+            if since.elapsed() >= grace {
+                waiting_since = None;
+                notifier.send("Break's over — ready to focus?", "Start the next work session when you're ready.")?;
+            }
+        }
+        // A phase past a day was almost certainly left on by mistake
+        if timer.running_long() != long_run_warned {
+            long_run_warned = !long_run_warned;
+            if long_run_warned {
+                notifier.send("Still there?", "This session has been running unusually long, over 24 hours.")?;
+                if headless {
+                    println!("{}  warning: session running unusually long", Local::now().format("%H:%M"));
+                }
+            }
+        }
+        // Without a display, print where the timer is once a minute
+        let progress_due = match last_progress_line {
+            Some(printed) => printed.elapsed() >= PROGRESS_LINE_INTERVAL,
+            None => true,
+        };
+        if headless && progress_due {
+            last_progress_line = Some(Instant::now());
+            let phase = match timer.timer_type() {
+                TimerType::Work => "work",
+                TimerType::Break => "break",
+            };
+            if open_ended {
+                println!("{}  {}  {} so far", Local::now().format("%H:%M"), phase, format_remaining(timer.elapsed().as_secs()));
+            } else {
+                println!("{}  {}  {} left", Local::now().format("%H:%M"), phase, format_remaining(remaining_secs(&timer)));
+            }
+        }
+        
+        let flash = micro_break_shown
+            .filter(|shown| shown.elapsed() < MICRO_BREAK_FLASH)
+            .map(|_| if emoji { "🤸 Stretch and blink!" } else { "Stretch and blink!" })
+            .or(long_run_warned.then_some(if emoji { "⚠ Running unusually long" } else { "Running unusually long" }))
+            .or((timer.state() == TimerState::Stopped).then_some("Break's over, press p to start work"))
+            .or(resuming.then_some(if emoji { "↻ Carrying on from where you left off" } else { "Carrying on from where you left off" }));
+        
+        if let Some(terminal) = &mut terminal {
+            let modes: Vec<&str> = [
+                (config.high_contrast, "high contrast"),
+                (config.announce, "announcing phase changes"),
+            ]
+            .iter()
+            .filter(|(on, _)| *on)
+            .map(|(_, name)| *name)
+            .collect();
+            let view = ui::TimerView {
+                timer: &timer,
+                work_mins: work_duration.as_secs() / 60,
+                intention: intention.as_deref(),
+                open_ended,
+                gauge_label: &gauge_label,
+                gauge_direction: config.gauge_direction,
+                locked: paused_by_lock.is_some(),
+                invalidated,
+                rest_required,
+                flash,
+                cycle: config.show_cycle_time.then(|| cycle_elapsed + timer.elapsed()),
+                clock: Local::now().time(),
+                today_pomodoros,
+                daily_goal: config.daily_goal as u64,
+                pace: (config.daily_goal > 0).then(|| {
+                    // Measured from the start of the day, which may not be midnight
+                    analytics::daily_pace(
+                        today_pomodoros,
+                        config.daily_goal as u64,
+                        day::clock(Local::now().time()),
+                        day::clock(workday_start),
+                        day::clock(workday_end),
+                    )
+                }),
+                tally_symbols: (filled_symbol, empty_symbol),
+                modes,
+                overview: overview.as_deref(),
+                theme: &theme,
+            };
+            terminal.draw(|f| ui::draw_timer(f, &view, &mut history))?;
+        }
+        
+        // Wrap up the day once end_of_day has passed
+        if let Some(time) = end_of_day {
+            if Local::now().time() >= time {
+                end_of_day = None;
+                
+                let summary = db.day_summary(day::today())?;
+                let goal = config.daily_goal as i64;
+                let verdict = if summary.pomodoros >= goal {
+                    "Daily goal met — nice work!".to_string()
+                } else {
+                    format!("{} short of your daily goal.", goal - summary.pomodoros)
+                };
+                
+                notify::send(
+                    "That's a wrap for today!",
+                    &format!(
+                        "{} pomodoros, {} minutes of focus. {}",
+                        summary.pomodoros, summary.focus_minutes, verdict
+                    ),
+                )?;
+                quit_requested = true;
+            }
+        }
+        
+        // Locking the screen shouldn't count as focus time
+        let locked = lock_monitor.is_locked();
+        if locked && timer.timer_type() == TimerType::Work && timer.state() == TimerState::Running {
+            timer.pause();
+            paused_by_lock = Some(Instant::now());
+        } else if !locked {
+            if let Some(since) = paused_by_lock.take() {
+                auto_paused += since.elapsed();
+                timer.resume();
+            }
+        }
+        
+        // Quit once the timer has been left stopped, untouched, for too long
+        if timer.state() != TimerState::Stopped {
+            idle_since = None;
+        } else {
+            let since = *idle_since.get_or_insert_with(Instant::now);
+            quit_requested |= timer::auto_quit_due(timer.state(), since.elapsed(), config.auto_quit_minutes);
+        }
+        
+        // Requests from other processes, e.g. `rusty_pomodoro stop`, and
+        // Ctrl-C or SIGTERM when running headless
+        match status::take_control() {
+            Some(status::Control::Stop) => quit_requested = true,
+            Some(status::Control::Pause) => timer.pause(),
+            // Stays paused while the screen is locked; unlocking resumes it
+            Some(status::Control::Resume) if paused_by_lock.is_none() => {
+                start_requested = timer.state() == TimerState::Stopped;
+                timer.resume();
+            }
+            _ => {}
+        }
+        if INTERRUPTED.load(Ordering::SeqCst) {
+            quit_requested = true;
+        }
+        
+        // Wait up to one tick for input; this also paces redraws and completion checks.
+        // Without a terminal there's no input to wait for, so just sleep
+        if terminal.is_none() {
+            std::thread::sleep(Duration::from_millis(config.tick_ms));
+        } else if event::poll(Duration::from_millis(config.tick_ms))? {
+            if let Event::Key(key) = event::read()? {
+                idle_since = None;
+                
+                // While the overview is open, any key just closes it
+                let key_code = match key.code {
+                    _ if overview.take().is_some() => KeyCode::Null,
+                    // Keys that change the timer act once per press
+                    KeyCode::Char(c @ ('p' | 'r' | 's' | 'x' | '+' | '-')) if !debounce.accept(c, Instant::now()) => KeyCode::Null,
+                    code => code,
+                };
+                match key_code {
+                    KeyCode::Char('q') => quit_requested = true,
+                    // Raw mode turns Ctrl-C into a key press rather than a signal
+                    KeyCode::Char('c') if key.modifiers.contains(KeyModifiers::CONTROL) => quit_requested = true,
+                    KeyCode::Char('o') => {
+                        overview = Some(analytics::render_goals(&today_goals(db, config)?, emoji));
+                    }
+                    KeyCode::Char('p') => {
+                        if timer.state() == TimerState::Running {
+                            timer.pause();
+                        } else if timer.state() == TimerState::Paused {
+                            timer.resume();
+                        } else {
+                            start_requested = true;
+                        }
+                    }
+                    KeyCode::Char('r') => {
+                        cycle_elapsed += timer.elapsed();
+                        timer.reset();
+                        phase_start = Local::now();
+                        auto_paused = Duration::ZERO;
+                        micro_breaks_sent = 0;
+                        invalidated = false;
+                    }
+                    KeyCode::Char('s') => {
+                        // Breaks can be skipped unless rest is due; work only ends early when open-ended
+                        skip_requested = (timer.timer_type() == TimerType::Break && !rest_required) || open_ended;
+                    }
+                    KeyCode::Char('x') if timer.timer_type() == TimerType::Work => {
+                        // Pressing it again takes it back
+                        invalidated = !invalidated;
+                    }
+                    // Flow sessions have no set length to adjust
+                    KeyCode::Char('+') if !open_ended => timer.extend_work(ADJUST_STEP),
+                    KeyCode::Char('-') if !open_ended => timer.shorten_work(ADJUST_STEP),
+                    KeyCode::Up => history.scroll(-1),
+                    KeyCode::Down => history.scroll(1),
+                    KeyCode::PageUp => history.page_up(),
+                    KeyCode::PageDown => history.page_down(),
+                    _ => {}
+                }
+            }
+        }
+        
+        // Work left waiting after a break starts now
+        if std::mem::take(&mut start_requested) && timer.state() == TimerState::Stopped {
+            timer.start();
+            phase_start = Local::now();
+            notify_phase_start(&mut notifier, config, TimerType::Work, phase_length(&timer))?;
+        }
+        
+        if quit_requested {
+            // Save any partial session before quitting
+            if timer.timer_type() == TimerType::Work && worth_recording(timer.elapsed(), config.min_session_seconds) {
+                let session = Session {
+                    pomodoro_count: completed_pomodoros,
+                    tag: tag.or(detected_tag.as_deref()).map(str::to_string),
+                    notes: intention_note(intention.as_deref()),
+                    ..Session::new(phase_start, Local::now())
+                };
+                db.save_session(&session)?;
+                run_session_hook(config, &session);
+                celebrate_achievements(db)?;
+            }
+            if timer.timer_type() == TimerType::Work && !is_open_ended(&timer) {
+                unfinished = Some(timer.total_time().saturating_sub(timer.elapsed())).filter(|left| !left.is_zero());
+            }
+            break;
+        }
+        
+        // Refresh the status file every second. Best effort: a status file we
+        // can't write shouldn't stop the timer
+        if last_status_write.elapsed() >= Duration::from_secs(1) {
+            last_status_write = Instant::now();
+            let current = status::Status::capture(
+                &timer,
+                open_ended,
+                completed_pomodoros,
+                tag.or(detected_tag.as_deref()),
+            );
+            let _ = status::write(&current);
+        }
+    }
+    
+    // Leave the full screen before printing the summary
+    drop(terminal);
+    
+    if sprint.is_some_and(|target| focused >= target) {
+        println!(
+            "Sprint complete: {} of focus, {} pomodoros.",
+            format_minutes(focused.as_secs() as i64 / 60),
+            completed_pomodoros,
+        );
+    }
+    
+    Ok(unfinished)
+}
+
+/// Set by Ctrl-C or SIGTERM; the timer loop sees it on its next tick and
+/// exits the same way as for q
+static INTERRUPTED: AtomicBool = AtomicBool::new(false);
+
+fn install_interrupt_handler() {
+    // Fails only if a handler is already set, which is just as good
+    let _ = ctrlc::set_handler(|| INTERRUPTED.store(true, Ordering::SeqCst));
+}
+
+/// Re-runs this `start` command as a background process with no terminal,
+/// returning its pid.
+fn spawn_detached(passphrase: Option<&str>) -> io::Result<u32> {
+    let args = std::env::args().skip(1).filter(|arg| arg != "--detach");
+    let mut command = std::process::Command::new(std::env::current_exe()?);
+    command
+        .args(args)
+        .arg("--headless")
+        .stdin(std::process::Stdio::null())
+        .stdout(std::process::Stdio::null())
+        .stderr(std::process::Stdio::null());
+    // It has no terminal to ask for the passphrase on; a pipe keeps it out
+    // of the environment, where other processes could read it
+    if passphrase.is_some() {
+        command.arg("--passphrase-stdin").stdin(std::process::Stdio::piped());
+    }
+    
+    // A process group of its own, so closing the shell doesn't end the timer
+    #[cfg(unix)]
+    {
+        use std::os::unix::process::CommandExt;
+        command.process_group(0);
+    }
+    
+    let mut child = command.spawn()?;
+    if let (Some(passphrase), Some(mut stdin)) = (passphrase, child.stdin.take()) {
+        writeln!(stdin, "{}", passphrase)?;
+    }
+    
+    Ok(child.id())
+}
+
+/// Imports sessions from a flat file left by an earlier version, if there
+/// is one and the user agrees, then renames the file to `.bak` so it's
+/// only offered once. Everything goes to stderr, like other notices.
+fn offer_legacy_import(db: &Database) -> Result<(), Box<dyn std::error::Error>> {
+    let Some(path) = import::find_legacy(&profile::active().data_dir()) else {
+        return Ok(());
+    };
+    let mut state = State::load();
+    if state.legacy_import_declined {
+        return Ok(());
+    }
+    
+    // A file we can't read may not be ours at all; don't let it block every command
+    let file = path.display().to_string();
+    let parsed = match import::parse_legacy(&path) {
+        Ok(parsed) => parsed,
+        Err(e) => {
+            eprintln!("warning: not offering to import {}: {}", file, e);
+            return Ok(());
+        }
+    };
+    if !onboarding::confirm_legacy_import(&file, parsed.sessions.len(), &mut io::stdin().lock(), &mut io::stderr())? {
+        state.legacy_import_declined = true;
+        state.save()?;
+        eprintln!("Left {} as it is, and won't ask again.", file);
+        return Ok(());
+    }
+    
+    for (record, reason) in &parsed.skipped {
+        eprintln!("Skipped record {}: {}", record, reason);
+    }
+    let added = db.import_sessions(&parsed.sessions)?;
+    
+    let mut backup = path.clone().into_os_string();
+    backup.push(".bak");
+    std::fs::rename(&path, &backup)?;
+    eprintln!(
+        "Imported {} sessions ({} already present, {} skipped); the old file is now {}",
+        added,
+        parsed.sessions.len() - added,
+        parsed.skipped.len(),
+        std::path::Path::new(&backup).display()
+    );
+    
+    Ok(())
+}
+
+/// Sends `pause` or `resume` to the running timer and waits for its status
+/// to show `expected`; exits with 1 if no timer is running.
+fn remote_toggle(control: status::Control, expected: &str) -> Result<(), Box<dyn std::error::Error>> {
+    if !status::send(control)? {
+        println!("No session is running");
+        std::process::exit(1);
+    }
+    
+    // The status file is only rewritten once a second
+    let deadline = Instant::now() + STOP_TIMEOUT;
+    loop {
+        match status::read()? {
+            Some(current) if current.state == expected => {
+                println!("Timer {} ({} phase)", expected, current.phase);
+                return Ok(());
+            }
+            Some(current) if Instant::now() >= deadline => {
+                println!("Asked the timer (pid {}) to {}; it's still {}", current.pid, control.as_str(), current.state);
+                return Ok(());
+            }
+            Some(_) => std::thread::sleep(Duration::from_millis(100)),
+            None => {
+                println!("The timer exited");
+                return Ok(());
+            }
+        }
+    }
+}
+
+/// Shows a timer running in another process, read from its status file,
+/// until it exits or the user presses q.
+fn attach_timer(theme: &ui::Theme) -> Result<(), Box<dyn std::error::Error>> {
+    let mut terminal = ui::Screen::enter(false)?;
+    // In the timer itself x only marks a session as not counting, so
+    // stopping from here asks for a second press first
+    let mut confirm_stop = false;
+    
+    while let Some(current) = status::read()? {
+        terminal.draw(|f| ui::draw_attached(f, &current.at(Local::now()), confirm_stop, theme))?;
+        
+        if event::poll(Duration::from_millis(500))? {
+            if let Event::Key(key) = event::read()? {
+                match key.code {
+                    KeyCode::Char('q') | KeyCode::Esc => break,
+                    KeyCode::Char('x') if confirm_stop => {
+                        status::send(status::Control::Stop)?;
+                        confirm_stop = false;
+                    }
+                    KeyCode::Char('x') => confirm_stop = true,
+                    _ => confirm_stop = false,
+                }
+            }
+        }
+    }
+    
+    Ok(())
+}
+
+/// Notifies that a phase has begun: a full announcement with `announce`,
+/// otherwise a short note if `notify_on_work_start` or
+/// `notify_on_break_start` asks for one.
+fn notify_phase_start(
+    notifier: &mut notify::Throttle,
+    config: &Config,
+    timer_type: TimerType,
+    length: Option<Duration>,
+) -> Result<(), Box<dyn std::error::Error>> {
+    match timer_type {
+        TimerType::Work if config.announce => notifier.send("Work session started", &announcement(timer_type, length)),
+        TimerType::Break if config.announce => notifier.send("Break started", &announcement(timer_type, length)),
+        TimerType::Work if config.notify_on_work_start => {
+            notifier.send("Focus session started", "Stick to one task until the timer rings.")
+        }
+        TimerType::Break if config.notify_on_break_start => {
+            notifier.send("Break started", "Step away from the screen for a bit.")
+        }
+        _ => Ok(()),
+    }
+}
+
+/// Describes a phase that just started, for `announce` notifications.
+fn announcement(timer_type: TimerType, length: Option<Duration>) -> String {
+    let Some(length) = length else {
+        return "Focus for as long as it flows, then press s to take a break.".to_string();
+    };
+    
+    let minutes = length.as_secs().div_ceil(60);
+    let ends = Local::now() + chrono::Duration::from_std(length).unwrap_or_else(|_| chrono::Duration::zero());
+    
+    match timer_type {
+        TimerType::Work => format!("Focus for {} minutes, until {}.", minutes, ends.format("%H:%M")),
+        TimerType::Break => format!("Take a {} minute break. Work resumes at {}.", minutes, ends.format("%H:%M")),
+    }
+}
+
+/// Fires the configured `on_session_end` command for a saved session.
+fn run_session_hook(config: &Config, session: &Session) {
+    if let Some(template) = &config.on_session_end {
+        let event = hook::SessionEnd {
+            minutes: session.minutes(),
+            completed: session.completed,
+            tag: session.tag.as_deref(),
+            pomodoros: session.pomodoro_count,
+        };
+        
+        // Fire-and-forget: a broken hook shouldn't interrupt the timer
+        let _ = hook::spawn(template, &event);
+    }
+}
+
+/// Formats recent sessions as lines for the history panel.
+fn history_entries(db: &Database, config: &Config) -> Result<Vec<String>, Box<dyn std::error::Error>> {
+    let entries = db
+        .query_sessions(&SessionFilter { limit: Some(HISTORY_LIMIT), ..Default::default() })?
+        .into_iter()
+        .map(|session| {
+            format!(
+                "{}  {:>3}m  {}",
+                config.format_time(&session.start.with_timezone(&Local)),
+                session.minutes(),
+                if session.completed { "✓" } else { "✗" },
+            )
+        })
+        .collect();
+    
+    Ok(entries)
+}
+
+/// Changes a session between work and break, warning when that moves a
+/// large part of the day's focus time.
+fn reclassify(db: &Database, id: i64, kind: TimerType) -> Result<(), Box<dyn std::error::Error>> {
+    let Some(session) = db.session(id)? else {
+        return Err(format!("no session with id {}", id).into());
+    };
+    let name = |kind: TimerType| match kind {
+        TimerType::Work => "work",
+        TimerType::Break => "a break",
+    };
+    if session.kind == kind {
+        println!("Session {} is already {}", id, name(kind));
+        return Ok(());
+    }
+    
+    let day = day::of(&session.start);
+    let day_focus = db.day_summary(day)?.focus_minutes;
+    let minutes = session.minutes();
+    if minutes as f64 >= day_focus.max(1) as f64 * RECLASSIFY_WARN_SHARE {
+        let change = match kind {
+            TimerType::Work => "adds",
+            TimerType::Break => "removes",
+        };
+        eprintln!(
+            "warning: this {} {} of focus time on {}, which had {} before",
+            change,
+            format_minutes(minutes),
+            day,
+            format_minutes(day_focus)
+        );
+    }
+    
+    db.reclassify_session(id, kind)?;
+    println!("Session {} is now {}", id, name(kind));
+    if session.completed && kind == TimerType::Break {
+        println!("It no longer counts as a pomodoro");
+    }
+    
+    Ok(())
+}
+
+/// Prints sessions for `history`, as an aligned table or as JSON.
+fn show_history(sessions: &[Session], json: bool, config: &Config, color: bool) -> Result<(), Box<dyn std::error::Error>> {
+    if json {
+        println!("{}", serde_json::to_string_pretty(sessions)?);
+        return Ok(());
+    }
+    if sessions.is_empty() {
+        println!("No sessions match");
+        return Ok(());
+    }
+    
+    // Only listings with breaks in them need to tell the two apart
+    let kinds = sessions.iter().any(|session| session.kind == TimerType::Break);
+    let header: &[&str] = if kinds {
+        &["ID", "Started", "Duration", "Tag", "Completed", "Kind"]
+    } else {
+        &["ID", "Started", "Duration", "Tag", "Completed"]
+    };
+    let mut table = analytics::Table::new()
+        .header(header)
+        .align(0, Align::Right)
+        .align(2, Align::Right)
+        .color(color);
+    for session in sessions {
+        let mut row = vec![
+            session.id.unwrap_or_default().to_string().into(),
+            config.format_time(&session.start.with_timezone(&Local)).into(),
+            format!("{}m", session.minutes()).into(),
+            session.tag.clone().unwrap_or_default().into(),
+            if session.completed { "yes" } else { "no" }.into(),
+        ];
+        if kinds {
+            row.push(match session.kind {
+                TimerType::Work => "work",
+                TimerType::Break => "break",
+            }
+            .into());
+        }
+        table.row(row);
+    }
+    println!("{}", table.render());
+    
+    Ok(())
+}
+
+/// The note recording a work session's focus intention, if it had one.
+fn intention_note(intention: Option<&str>) -> Option<String> {
+    intention.map(|text| format!("{}{}", models::INTENTION_PREFIX, text))
+}
+
+fn show_calendar(db: &Database, color: bool, no_emoji: bool) -> Result<(), Box<dyn std::error::Error>> {
+    let today = day::today();
+    let first_day = today - chrono::Duration::weeks(analytics::CALENDAR_WEEKS as i64);
+    let minutes = db.focus_minutes_by_day(day::bounds(first_day).0)?;
+    
+    let heading = if no_emoji { "Focus Calendar" } else { "📅 Focus Calendar" };
+    println!("{}", heading);
+    println!("=================");
+    println!("{}", analytics::render_calendar(&minutes, today, analytics::CALENDAR_WEEKS, color));
+    
+    Ok(())
+}
+
+fn show_top_tags(
+    db: &Database,
+    limit: usize,
+    period: Period,
+    no_emoji: bool,
+) -> Result<(), Box<dyn std::error::Error>> {
+    let since = period.start(day::today());
+    
+    let rows: Vec<(String, i64)> = db
+        .top_tags(since, limit)?
+        .into_iter()
+        .map(|total| (total.tag.unwrap_or_else(|| "(untagged)".to_string()), total.minutes))
+        .collect();
+    
+    if rows.is_empty() {
+        println!("No sessions in this period yet.");
+        return Ok(());
+    }
+    
+    let heading = if no_emoji { "Top Tags by Focus Time" } else { "🏆 Top Tags by Focus Time" };
+    println!("{}", heading);
+    println!("=========================");
+    println!("{}", analytics::render_leaderboard(&rows, !no_emoji));
+    
+    Ok(())
+}
+
+/// Ranks every profile by focus time over `period`. The active profile's
+/// database is already open; the others are opened read-only, except
+/// encrypted ones, whose passphrases are in their own configs.
+fn show_profile_leaderboard(db: &Database, period: Period, no_emoji: bool) -> Result<(), Box<dyn std::error::Error>> {
+    let since = period.start(day::today());
+    let active = profile::active();
+    
+    let focus_minutes = |profile: &profile::Profile| -> Result<i64, Box<dyn std::error::Error>> {
+        if *profile == active {
+            return Ok(db.work_and_break_minutes(since)?.0);
+        }
+        let path = profile.database_path();
+        if !path.exists() {
+            return Ok(0);
+        }
+        if encryption::is_encrypted(&path)? {
+            return Err("encrypted".into());
+        }
+        Ok(Database::open_read_only(&path)?.work_and_break_minutes(since)?.0)
+    };
+    
+    let mut rows = Vec::new();
+    let mut idle = Vec::new();
+    let mut unreadable = Vec::new();
+    for profile in profile::all() {
+        match focus_minutes(&profile) {
+            Ok(0) => idle.push(profile.name().to_string()),
+            Ok(minutes) => rows.push((profile.name().to_string(), minutes)),
+            Err(e) => unreadable.push(format!("{} ({})", profile.name(), e)),
+        }
+    }
+    rows.sort_by(|a, b| b.1.cmp(&a.1).then_with(|| a.0.cmp(&b.0)));
+    
+    let heading = if no_emoji { "Focus Time by Profile" } else { "🏆 Focus Time by Profile" };
+    println!("{}", heading);
+    println!("=========================");
+    if rows.is_empty() {
+        println!("No sessions in this period yet.");
+    } else {
+        println!("{}", analytics::render_leaderboard(&rows, !no_emoji));
+    }
+    if !idle.is_empty() && !rows.is_empty() {
+        println!("\nNo focus time: {}", idle.join(", "));
+    }
+    if !unreadable.is_empty() {
+        println!("Skipped: {}", unreadable.join(", "));
+    }
+    
+    Ok(())
+}
+
+fn show_stats(db: &Database, config: &Config, period: Period, color: bool) -> Result<(), Box<dyn std::error::Error>> {
+    if db.total_sessions()? == 0 {
+        println!("No sessions yet — run 'rusty_pomodoro start' to begin!");
+        return Ok(());
+    }
+    
+    // A session is any recorded work phase, finished or not; only the
+    // finished ones count as pomodoros.
+    let since = period.start(day::today());
+    let totals = db.work_totals(since)?;
+    if totals.sessions == 0 {
+        println!("No sessions in the {} — run 'rusty_pomodoro start' to begin!", period.label());
+        return Ok(());
+    }
+    
+    if period == Period::All {
+        println!("📊 Productivity Statistics");
+        println!("==========================");
+    } else {
+        let heading = format!("📊 Productivity Statistics ({})", period.label());
+        println!("{}", heading);
+        println!("{}", "=".repeat(heading.chars().count() + 1));
+    }
+    
+    let completion_rate = (totals.pomodoros as f64 / totals.sessions as f64 * 100.0).round();
+    let rate_color = match completion_rate as i64 {
+        80.. => TextColor::Green,
+        50..=79 => TextColor::Yellow,
+        _ => TextColor::Red,
+    };
+    
+    let mut summary = analytics::Table::new()
+        .align(1, Align::Right)
+        .color(color);
+    let pomodoros_label = if period == Period::All { "Lifetime Pomodoros:" } else { "Pomodoros:" };
+    summary.row(vec![pomodoros_label.into(), totals.pomodoros.to_string().into()]);
+    summary.row(vec!["Total Sessions:".into(), totals.sessions.to_string().into()]);
+    summary.row(vec!["Completed Sessions:".into(), totals.pomodoros.to_string().into()]);
+    summary.row(vec!["Completion Rate:".into(), Cell::colored(format!("{}%", completion_rate), rate_color)]);
+    summary.row(vec!["Total Focus Time:".into(), format_minutes(totals.minutes).into()]);
+    
+    // Completion rate of the last 7 days against the 7 before them
+    let today = day::today();
+    let (recent_done, recent_total) = db.completion_counts(today - chrono::Duration::days(TREND_DAYS - 1), TREND_DAYS)?;
+    let (previous_done, previous_total) = db.completion_counts(today - chrono::Duration::days(2 * TREND_DAYS - 1), TREND_DAYS)?;
+    let trend = match (
+        analytics::completion_rate(recent_done, recent_total),
+        analytics::completion_rate(previous_done, previous_total),
+    ) {
+        (Some(recent), Some(previous)) => {
+            let text = analytics::format_trend(recent, previous);
+            match analytics::completion_trend(recent, previous) {
+                Trend::Improving => Cell::colored(text, TextColor::Green),
+                Trend::Steady => text.into(),
+                Trend::Declining => Cell::colored(text, TextColor::Red),
+            }
+        }
+        _ => "insufficient data".into(),
+    };
+    summary.row(vec!["Completion Trend (7 days):".into(), trend]);
+    
+    let (work_minutes, break_minutes) = db.work_and_break_minutes(since)?;
+    let ratio = match analytics::focus_ratio(work_minutes, break_minutes) {
+        Some(ratio) => {
+            let (verdict, color) = match analytics::rest_balance(ratio) {
+                RestBalance::TooLittleRest => ("too little rest", TextColor::Red),
+                RestBalance::Balanced => ("balanced", TextColor::Green),
+                RestBalance::MostlyBreaks => ("mostly breaks", TextColor::Yellow),
+            };
+            Cell::colored(format!("{:.1} : 1 ({})", ratio, verdict), color)
+        }
+        None => "no breaks recorded".into(),
+    };
+    summary.row(vec![format!("Focus Ratio ({}):", period.label()).into(), ratio]);
+    
+    let inputs = db.focus_inputs(since)?;
+    let score = match analytics::focus_score(&inputs, config.energy_in_focus_score) {
+        Some(score) => format!("{} / 100", score).into(),
+        None => "no sessions".into(),
+    };
+    summary.row(vec![format!("Focus Score ({}):", period.label()).into(), score]);
+    println!("{}", summary.render());
+    
+    // Breaks are their own rows, so rest is measured rather than assumed
+    let breaks = db.break_summary(since)?;
+    if breaks.taken + breaks.skipped > 0 {
+        println!("\nBreaks ({}):", period.label());
+        
+        let mut rest = analytics::Table::new()
+            .align(1, Align::Right)
+            .color(color);
+        rest.row(vec!["Breaks Taken:".into(), breaks.taken.to_string().into()]);
+        rest.row(vec![
+            "Breaks Skipped:".into(),
+            if breaks.skipped > 0 {
+                Cell::colored(breaks.skipped.to_string(), TextColor::Yellow)
+            } else {
+                breaks.skipped.to_string().into()
+            },
+        ]);
+        rest.row(vec!["Total Rest Time:".into(), format_minutes(breaks.rest_minutes).into()]);
+        if let Some(average) = breaks.average_minutes() {
+            rest.row(vec!["Average Break:".into(), format!("{:.1} min", average).into()]);
+        }
+        println!("{}", rest.render());
+    }
+    
+    // Show most productive days
+    println!("\nMost Productive Days:");
+    
+    let mut days = analytics::Table::new()
+        .header(&["Day", "Sessions", "Focus"])
+        .align(1, Align::Right)
+        .align(2, Align::Right)
+        .color(color);
+    for day in db.top_days(5, since)? {
+        days.row(vec![
+            chrono::NaiveDate::parse_from_str(&day.day, "%Y-%m-%d")
+                .map(|date| config.format_date(date))
+                .unwrap_or(day.day)
+                .into(),
+            day.sessions.to_string().into(),
+            Cell::colored(format_minutes(day.minutes), TextColor::Green),
+        ]);
+    }
+    println!("{}", days.render());
+    
+    println!("\nTip: Run 'rusty_pomodoro export' to get detailed session data");
+    
+    Ok(())
+}
+
+/// Formats a number of minutes as "3h 05m".
+fn format_minutes(minutes: i64) -> String {
+    format!("{}h {:02}m", minutes / 60, minutes % 60)
+}
+
+/// Whether output may use colors: only on a terminal, and never with
+/// `--no-color` or when `NO_COLOR` is set. The TUI turns monochrome too.
+fn should_colorize(no_color: bool) -> bool {
+    !no_color && io::stdout().is_terminal() && std::env::var_os("NO_COLOR").is_none()
+}
+
+fn watch_stats(db: &Database, config: &Config, color: bool) -> Result<(), Box<dyn std::error::Error>> {
+    let mut terminal = ui::Screen::enter(true)?;
+    
+    let theme = ui::Theme::new(config.high_contrast, color);
+    let mut dashboard = load_dashboard(db, config)?;
+    let mut last_refresh = Instant::now();
+    
+    loop {
+        terminal.draw(|f| ui::draw_dashboard(f, &dashboard, &theme))?;
+        
+        if event::poll(Duration::from_millis(250))? {
+            if let Event::Key(key) = event::read()? {
+                if matches!(key.code, KeyCode::Char('q') | KeyCode::Esc) {
+                    break;
+                }
+            }
+        }
+        
+        if last_refresh.elapsed() >= DASHBOARD_REFRESH {
+            dashboard = load_dashboard(db, config)?;
+            last_refresh = Instant::now();
+        }
+    }
+    
+    Ok(())
+}
+
+fn load_dashboard(db: &Database, config: &Config) -> Result<Dashboard, Box<dyn std::error::Error>> {
+    let today = day::today();
+    let days_into_week = today.weekday().num_days_from_monday() as i64;
+    
+    let mut today_pomodoros = 0;
+    let mut week_pomodoros = 0;
+    let mut last_seven_days = Vec::new();
+    for offset in (0..7).rev() {
+        let day = today - chrono::Duration::days(offset);
+        let pomodoros = db.day_summary(day)?.pomodoros;
+        
+        if offset == 0 {
+            today_pomodoros = pomodoros;
+        }
+        if offset <= days_into_week {
+            week_pomodoros += pomodoros;
+        }
+        last_seven_days.push((day.format("%a").to_string(), pomodoros as u64));
+    }
+    
+    let monday = today - chrono::Duration::days(days_into_week);
+    let mut this_week = Vec::new();
+    for offset in 0..7 {
+        let day = monday + chrono::Duration::days(offset);
+        let pomodoros = if day <= today { db.day_summary(day)?.pomodoros } else { 0 };
+        this_week.push((day.format("%a").to_string(), pomodoros));
+    }
+    
+    Ok(Dashboard {
+        today_pomodoros,
+        daily_goal: config.daily_goal as i64,
+        week_pomodoros,
+        weekly_goal: config.weekly_goal as i64,
+        streak: analytics::current_streak(&db.completed_days()?, today),
+        last_seven_days,
+        this_week,
+        today_index: days_into_week as usize,
+    })
+}
+
+fn show_today(db: &Database, config: &Config, no_emoji: bool) -> Result<(), Box<dyn std::error::Error>> {
+    let today = day::today();
+    let goals = today_goals(db, config)?;
+    
+    let heading = if no_emoji { "Today" } else { "📅 Today" };
+    println!("{} ({})", heading, today.format("%A, %B %-d"));
+    println!("==========================");
+    println!("{}", analytics::render_goals(&goals, !no_emoji));
+    
+    Ok(())
+}
+
+fn show_plan(db: &Database, config: &Config, no_emoji: bool) -> Result<(), Box<dyn std::error::Error>> {
+    let today = day::today();
+    let days_into_week = today.weekday().num_days_from_monday() as i64;
+    
+    // Pace from whole days only; today is still in progress
+    let mut history = Vec::new();
+    for offset in 1..=PLAN_PACE_DAYS {
+        history.push(db.day_summary(today - chrono::Duration::days(offset))?.pomodoros.max(0) as u64);
+    }
+    let mut done = 0;
+    for offset in 0..=days_into_week {
+        done += db.day_summary(today - chrono::Duration::days(offset))?.pomodoros.max(0) as u64;
+    }
+    
+    let days_left = (7 - days_into_week) as u64;
+    let plan = analytics::forecast(&history, done, config.weekly_goal as u64, days_left);
+    
+    let heading = if no_emoji { "Weekly plan" } else { "🎯 Weekly plan" };
+    println!("{}", heading);
+    println!("==========================");
+    println!("Goal:         {} pomodoros ({} done, {} to go)", config.weekly_goal, done, plan.remaining);
+    println!("Recent pace:  {:.1} a day over the last {} days", plan.pace, PLAN_PACE_DAYS);
+    
+    if plan.remaining == 0 {
+        println!("\nWeekly goal met — anything more is a bonus.");
+        return Ok(());
+    }
+    
+    let days = |n: u64| if n == 1 { "1 day".to_string() } else { format!("{} days", n) };
+    match plan.days_needed {
+        Some(needed) if plan.on_track => println!("At this pace: {} more, on track to finish by Sunday", days(needed)),
+        Some(needed) => println!("At this pace: {} more, {} past Sunday", days(needed), days(needed - days_left)),
+        None => println!("At this pace: no recent pomodoros to project from"),
+    }
+    println!(
+        "To finish by Sunday: {} a day over the {} days left, today included",
+        plan.per_day_needed, days_left
+    );
+    
+    Ok(())
+}
+
+/// Unlocks achievements reached by the session just saved, with a
+/// notification for each. These skip the throttle: they're rare, and
+/// usually follow a completion notice.
+fn celebrate_achievements(db: &Database) -> Result<(), Box<dyn std::error::Error>> {
+    for achievement in achievements::check(db)? {
+        notify::send(&format!("Achievement unlocked: {}", achievement.name), achievement.description)?;
+    }
+    
+    Ok(())
+}
+
+fn show_achievements(db: &Database, config: &Config, no_emoji: bool) -> Result<(), Box<dyn std::error::Error>> {
+    // Catch up on history that was imported or merged since the last session
+    achievements::check(db)?;
+    
+    let totals = achievements::Totals::load(db)?;
+    let unlocked = db.unlocked_achievements()?;
+    let progress: Vec<_> = achievements::ACHIEVEMENTS.iter().map(|a| a.progress(&totals)).collect();
+    
+    let heading = if no_emoji { "Achievements" } else { "🏆 Achievements" };
+    println!("{} ({} of {} unlocked)", heading, unlocked.len(), progress.len());
+    println!("==========================");
+    
+    let bars = analytics::render_goals(&progress, !no_emoji);
+    for (bar, achievement) in bars.lines().zip(&achievements::ACHIEVEMENTS) {
+        match unlocked.get(achievement.id) {
+            Some(at) => println!("{}  {}", bar, config.format_date(at.date_naive())),
+            None => println!("{}", bar),
+        }
+    }
+    
+    Ok(())
+}
+
+/// Today's progress towards the daily and streak goals.
+fn today_goals(db: &Database, config: &Config) -> Result<Vec<analytics::GoalProgress>, Box<dyn std::error::Error>> {
+    let today = day::today();
+    let summary = db.day_summary(today)?;
+    let streak = analytics::current_streak(&db.completed_days()?, today);
+    
+    Ok(vec![
+        analytics::GoalProgress {
+            label: "Pomodoros",
+            current: summary.pomodoros,
+            target: config.daily_goal as i64,
+            unit: "",
+        },
+        analytics::GoalProgress {
+            label: "Focus time",
+            current: summary.focus_minutes,
+            target: config.daily_focus_goal as i64,
+            unit: "min",
+        },
+        analytics::GoalProgress {
+            label: "Streak",
+            current: streak as i64,
+            target: config.streak_goal as i64,
+            unit: "days",
+        },
+    ])
+}
+
+fn export_data(
+    db: &Database,
+    output_path: &Option<String>,
+    format: ExportFormat,
+    rollup: Option<Rollup>,
+    filter: &SessionFilter,
+    daily_goal: u32,
+) -> Result<(), Box<dyn std::error::Error>> {
+    let sessions = db.query_sessions(filter)?;
+    
+    let contents = match (rollup, format) {
+        (Some(Rollup::Daily), format) => export::rollup_to(&export::daily_rollup(&sessions, daily_goal), format)?,
+        (None, ExportFormat::Json) => export::to_json(&sessions)?,
+        (None, ExportFormat::Csv) => export::to_csv(&sessions),
+        (None, ExportFormat::Tsv) => export::to_tsv(&sessions),
+    };
+    write_export(&contents, output_path)
+}
+
+fn export_blocks(
+    db: &Database,
+    output_path: &Option<String>,
+    gap_mins: u64,
+    filter: &SessionFilter,
+) -> Result<(), Box<dyn std::error::Error>> {
+    #[derive(serde::Serialize)]
+    struct Block {
+        start_time: String,
+        end_time: String,
+        sessions: usize,
+        focus_minutes: i64,
+    }
+    
+    let mut sessions = db.session_times()?;
+    sessions.retain(|(start, _)| {
+        let day = day::of(start);
+        filter.from.unwrap_or(chrono::NaiveDate::MIN) <= day && day <= filter.to.unwrap_or(chrono::NaiveDate::MAX)
+    });
+    let max_gap = chrono::Duration::minutes(gap_mins as i64);
+    
+    let blocks: Vec<Block> = analytics::group_into_blocks(&sessions, max_gap)
+        .into_iter()
+        .map(|block| Block {
+            start_time: block.start.to_rfc3339(),
+            end_time: block.end.to_rfc3339(),
+            sessions: block.sessions,
+            focus_minutes: block.focus_minutes,
+        })
+        .collect();
+    
+    let json = serde_json::to_string_pretty(&blocks)?;
+    write_export(&json, output_path)
+}
+
+fn write_export(contents: &str, output_path: &Option<String>) -> Result<(), Box<dyn std::error::Error>> {
+    match output_path {
+        Some(path) => {
+            std::fs::write(path, contents)?;
+            println!("Data exported to {}", path);
         }
         None => {
-            println!("{}", json);
+            print!("{}", contents);
+            if !contents.ends_with('\n') {
+                println!();
+            }
         }
     }
     
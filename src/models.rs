@@ -0,0 +1,72 @@
+use chrono::{DateTime, Duration, FixedOffset, TimeZone};
+use serde::Serialize;
+
+use crate::timer::TimerType;
+
+/// A recorded work session or break.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct Session {
+    /// Row id, `None` until the session is saved
+    pub id: Option<i64>,
+    /// Start and end keep the UTC offset they were recorded with, which
+    /// may differ from today's local one after travel or a DST change
+    pub start: DateTime<FixedOffset>,
+    pub end: DateTime<FixedOffset>,
+    /// Pomodoros completed so far in the run this session belongs to
+    pub pomodoro_count: u64,
+    /// Whether the session counted as a pomodoro
+    pub completed: bool,
+    /// Focus rating from 1 to 5, when one was given
+    pub energy: Option<u8>,
+    pub notes: Option<String>,
+    pub tag: Option<String>,
+    /// Whether this was focus time or a break
+    pub kind: TimerType,
+    /// Whether the session's focus intention was achieved, when asked
+    pub intention_met: Option<bool>,
+}
+
+/// Start of the note that records a session's focus intention
+pub const INTENTION_PREFIX: &str = "intention: ";
+
+impl Session {
+    /// An unsaved, unfinished work session between `start` and `end`.
+    pub fn new<Tz: TimeZone>(start: DateTime<Tz>, end: DateTime<Tz>) -> Self {
+        Session {
+            id: None,
+            start: start.fixed_offset(),
+            end: end.fixed_offset(),
+            pomodoro_count: 0,
+            completed: false,
+            energy: None,
+            notes: None,
+            tag: None,
+            kind: TimerType::Work,
+            intention_met: None,
+        }
+    }
+    
+    pub fn duration(&self) -> Duration {
+        self.end.signed_duration_since(self.start)
+    }
+    
+    /// Length in whole minutes, rounded down.
+    pub fn minutes(&self) -> i64 {
+        self.duration().num_minutes()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::Local;
+    
+    #[test]
+    fn test_session_minutes_round_down() {
+        let start = Local.with_ymd_and_hms(2024, 3, 14, 9, 0, 0).unwrap();
+        let session = Session::new(start, start + Duration::seconds(25 * 60 + 59));
+        
+        assert_eq!(session.minutes(), 25);
+        assert_eq!(session.duration(), Duration::seconds(1559));
+    }
+}
\ No newline at end of file
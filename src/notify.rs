@@ -0,0 +1,83 @@
+use std::{
+    error::Error,
+    time::{Duration, Instant},
+};
+
+/// Drops notifications that come too soon after the previous one, so fast
+/// cycles don't flood the desktop.
+pub struct Throttle {
+    min_interval: Duration,
+    last_sent: Option<Instant>,
+}
+
+impl Throttle {
+    /// A zero interval lets every notification through.
+    pub fn new(min_interval: Duration) -> Self {
+        Self { min_interval, last_sent: None }
+    }
+    
+    /// Sends the notification unless one went out less than the interval ago.
+    pub fn send(&mut self, summary: &str, body: &str) -> Result<(), Box<dyn Error>> {
+        if self.allow(Instant::now()) {
+            send(summary, body)?;
+        }
+        
+        Ok(())
+    }
+    
+    /// Whether a notification may go out at `now`, recording it if so.
+    fn allow(&mut self, now: Instant) -> bool {
+        let too_soon = self
+            .last_sent
+            .is_some_and(|last| now.saturating_duration_since(last) < self.min_interval);
+        if !too_soon {
+            self.last_sent = Some(now);
+        }
+        
+        !too_soon
+    }
+}
+
+/// Shows a desktop notification.
+#[cfg(feature = "notifications")]
+pub fn send(summary: &str, body: &str) -> Result<(), Box<dyn Error>> {
+    notify_rust::Notification::new()
+        .summary(summary)
+        .body(body)
+        .show()?;
+    
+    Ok(())
+}
+
+/// Rings the terminal bell when built without desktop notifications.
+#[cfg(not(feature = "notifications"))]
+pub fn send(_summary: &str, _body: &str) -> Result<(), Box<dyn Error>> {
+    use std::io::Write;
+    
+    let mut stdout = std::io::stdout();
+    stdout.write_all(b"\x07")?;
+    stdout.flush()?;
+    
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    
+    #[test]
+    fn test_throttle_drops_notifications_within_interval() {
+        let start = Instant::now();
+        let mut throttle = Throttle::new(Duration::from_secs(10));
+        
+        assert!(throttle.allow(start));
+        assert!(!throttle.allow(start + Duration::from_secs(9)));
+        
+        // Dropped notifications don't extend the window
+        assert!(throttle.allow(start + Duration::from_secs(10)));
+        
+        let mut off = Throttle::new(Duration::ZERO);
+        assert!(off.allow(start));
+        assert!(off.allow(start));
+    }
+}
\ No newline at end of file
@@ -0,0 +1,210 @@
+use std::{
+    error::Error,
+    io::{self, BufRead, Write},
+};
+
+use crate::config::Config;
+
+/// Walks a new user through the basic settings and saves them.
+///
+/// Runs on the first launch, detected by the absence of a config file.
+pub fn run(config: Config) -> Result<Config, Box<dyn Error>> {
+    let stdin = io::stdin();
+    let mut stdout = io::stdout();
+    
+    let config = ask(config, &mut stdin.lock(), &mut stdout)?;
+    config.save()?;
+    
+    writeln!(stdout, "\nSaved your settings to {}", Config::path().display())?;
+    writeln!(stdout, "Edit that file any time, or pass --no-onboarding to skip this.\n")?;
+    
+    Ok(config)
+}
+
+/// Asks the onboarding questions, keeping the current value on an empty answer.
+fn ask<R: BufRead, W: Write>(mut config: Config, input: &mut R, output: &mut W) -> Result<Config, Box<dyn Error>> {
+    writeln!(output, "🍅 Welcome to Rusty Pomodoro! Let's set a few things up.")?;
+    writeln!(output, "Press Enter to accept the default shown in brackets.\n")?;
+    
+    let names: Vec<String> = config.preset_names().iter().map(|n| n.to_string()).collect();
+    writeln!(output, "Which rhythm suits you?")?;
+    for (i, name) in names.iter().enumerate() {
+        let preset = config.preset(name)?;
+        writeln!(output, "  {}) {:<10} {} min work / {} min break", i + 1, name, preset.work, preset.break_time)?;
+    }
+    
+    loop {
+        let answer = prompt(input, output, "Preset [1]: ")?;
+        if answer.is_empty() {
+            config.preset = names.first().cloned();
+            break;
+        }
+        
+        let by_number = answer.parse::<usize>().ok().and_then(|n| n.checked_sub(1)).and_then(|i| names.get(i));
+        let by_name = names.iter().find(|name| name.eq_ignore_ascii_case(&answer));
+        match by_number.or(by_name) {
+            Some(name) => {
+                config.preset = Some(name.clone());
+                break;
+            }
+            None => writeln!(output, "Please pick a number from 1 to {} or a preset name.", names.len())?,
+        }
+    }
+    
+    loop {
+        let answer = prompt(input, output, &format!("Daily goal in pomodoros [{}]: ", config.daily_goal))?;
+        if answer.is_empty() {
+            break;
+        }
+        match answer.parse::<u32>() {
+            Ok(goal) if goal > 0 => {
+                config.daily_goal = goal;
+                break;
+            }
+            _ => writeln!(output, "Please enter a positive whole number.")?,
+        }
+    }
+    
+    let answer = prompt(input, output, "Desktop notifications when a phase ends? [Y/n]: ")?;
+    let notifications = !matches!(answer.to_lowercase().as_str(), "n" | "no");
+    config.notify_on_work_complete = notifications;
+    config.notify_on_break_complete = notifications;
+    
+    if cfg!(feature = "sound") {
+        let answer = prompt(input, output, "Play a sound when a phase ends? [y/N]: ")?;
+        config.sound = matches!(answer.to_lowercase().as_str(), "y" | "yes");
+    } else {
+        writeln!(output, "Sounds stay off: this build has no sound support (reinstall with `--features sound`).")?;
+    }
+    
+    Ok(config)
+}
+
+/// Work phases longer than this are probably a typo, e.g. `--work 600`
+const LONG_WORK_MINUTES: u64 = 180;
+
+/// Breaks longer than this are probably a typo
+const LONG_BREAK_MINUTES: u64 = 60;
+
+/// Asks before starting a timer with unusually long phases.
+///
+/// Returns `true` straight away for ordinary durations. Anything but a yes
+/// answer cancels, so a stray Enter doesn't start a marathon.
+pub fn confirm_durations<R: BufRead, W: Write>(
+    work: u64,
+    break_time: u64,
+    emoji: bool,
+    input: &mut R,
+    output: &mut W,
+) -> io::Result<bool> {
+    if work <= LONG_WORK_MINUTES && break_time <= LONG_BREAK_MINUTES {
+        return Ok(true);
+    }
+    
+    writeln!(
+        output,
+        "{}That's {} of work and {} of break per round.",
+        if emoji { "⚠️  " } else { "Warning: " },
+        format_duration(work),
+        format_duration(break_time)
+    )?;
+    let answer = prompt(input, output, "Start anyway? [y/N]: ")?;
+    
+    Ok(matches!(answer.to_lowercase().as_str(), "y" | "yes"))
+}
+
+/// Offers to import sessions found in a file left by an earlier version.
+/// Enter accepts, since the sessions are the user's own history.
+pub fn confirm_legacy_import<R: BufRead, W: Write>(file: &str, sessions: usize, input: &mut R, output: &mut W) -> io::Result<bool> {
+    writeln!(output, "Found {} sessions from an earlier version in {}.", sessions, file)?;
+    let answer = prompt(input, output, "Import them into the session database? [Y/n]: ")?;
+    
+    Ok(!matches!(answer.to_lowercase().as_str(), "n" | "no"))
+}
+
+fn format_duration(minutes: u64) -> String {
+    if minutes >= 60 {
+        format!("{}h {}m", minutes / 60, minutes % 60)
+    } else {
+        format!("{}m", minutes)
+    }
+}
+
+fn prompt<R: BufRead, W: Write>(input: &mut R, output: &mut W, question: &str) -> io::Result<String> {
+    write!(output, "{}", question)?;
+    output.flush()?;
+    
+    let mut answer = String::new();
+    input.read_line(&mut answer)?;
+    
+    Ok(answer.trim().to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::Cursor;
+    
+    #[test]
+    fn test_answers_update_config() {
+        let mut input = Cursor::new("deepwork\nzero\n6\nn\n");
+        let mut output = Vec::new();
+        
+        let config = ask(Config::default(), &mut input, &mut output).unwrap();
+        
+        assert_eq!(config.preset.as_deref(), Some("deepwork"));
+        assert_eq!(config.daily_goal, 6);
+        assert!(!config.notify_on_work_complete);
+        assert!(!config.notify_on_break_complete);
+        assert!(!config.sound);
+        
+        // The invalid goal was asked again
+        let output = String::from_utf8(output).unwrap();
+        assert!(output.contains("positive whole number"));
+        // Without sound support there's nothing to ask
+        assert_eq!(output.contains("no sound support"), !cfg!(feature = "sound"));
+    }
+    
+    #[test]
+    fn test_empty_answers_keep_defaults() {
+        let mut input = Cursor::new("\n\n\n");
+        let mut output = Vec::new();
+        
+        let config = ask(Config::default(), &mut input, &mut output).unwrap();
+        
+        assert_eq!(config.preset.as_deref(), Some("classic"));
+        assert_eq!(config.daily_goal, Config::default().daily_goal);
+        assert!(config.notify_on_work_complete);
+    }
+    
+    #[test]
+    fn test_long_durations_need_a_yes() {
+        let mut output = Vec::new();
+        
+        // Ordinary durations don't ask at all
+        let mut input = Cursor::new("");
+        assert!(confirm_durations(50, 10, true, &mut input, &mut output).unwrap());
+        assert!(output.is_empty());
+        
+        let mut input = Cursor::new("\n");
+        assert!(!confirm_durations(600, 5, true, &mut input, &mut output).unwrap());
+        assert!(String::from_utf8(output.clone()).unwrap().contains("10h 0m of work"));
+        
+        let mut input = Cursor::new("Y\n");
+        assert!(confirm_durations(25, 90, true, &mut input, &mut output).unwrap());
+        
+        let mut output = Vec::new();
+        let mut input = Cursor::new("n\n");
+        assert!(!confirm_durations(600, 5, false, &mut input, &mut output).unwrap());
+        assert!(String::from_utf8(output).unwrap().starts_with("Warning: That's 10h 0m"));
+    }
+    
+    #[test]
+    fn test_legacy_import_defaults_to_yes() {
+        let mut output = Vec::new();
+        
+        assert!(confirm_legacy_import("sessions.json", 12, &mut Cursor::new("\n"), &mut output).unwrap());
+        assert!(String::from_utf8(output.clone()).unwrap().contains("Found 12 sessions"));
+        assert!(!confirm_legacy_import("sessions.json", 12, &mut Cursor::new("no\n"), &mut output).unwrap());
+    }
+}
\ No newline at end of file
@@ -0,0 +1,149 @@
+use std::{
+    collections::BTreeSet,
+    error::Error,
+    fs,
+    path::{Path, PathBuf},
+    sync::OnceLock,
+};
+
+/// Name that selects the original, un-namespaced locations
+pub const DEFAULT: &str = "default";
+
+/// A separate set of sessions, settings and state, chosen with `--profile`.
+///
+/// The default profile uses the top-level directories, so data from before
+/// profiles existed stays where it is. Other profiles live in
+/// `profiles/<name>` under the same directories.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Profile {
+    name: Option<String>,
+}
+
+static ACTIVE: OnceLock<Profile> = OnceLock::new();
+
+impl Profile {
+    /// Validates a profile name; `None` or "default" give the default profile.
+    pub fn new(name: Option<&str>) -> Result<Self, Box<dyn Error>> {
+        match name {
+            None | Some(DEFAULT) => Ok(Profile::default()),
+            Some(name) if is_valid_name(name) => Ok(Profile { name: Some(name.to_string()) }),
+            Some(name) => Err(format!(
+                "invalid profile name '{}', use letters, digits, '-' and '_'",
+                name
+            )
+            .into()),
+        }
+    }
+    
+    /// The name given with `--profile`, "default" for the default profile.
+    pub fn name(&self) -> &str {
+        self.name.as_deref().unwrap_or(DEFAULT)
+    }
+    
+    /// Where the profile's database, state and status files live.
+    pub fn data_dir(&self) -> PathBuf {
+        self.namespaced(dirs_next::data_dir())
+    }
+    
+    /// The profile's session database.
+    pub fn database_path(&self) -> PathBuf {
+        self.data_dir().join("sessions.db")
+    }
+    
+    /// Where the profile's config file lives.
+    pub fn config_dir(&self) -> PathBuf {
+        self.namespaced(dirs_next::config_dir())
+    }
+    
+    fn namespaced(&self, base: Option<PathBuf>) -> PathBuf {
+        let mut dir = base.unwrap_or_else(|| PathBuf::from("."));
+        dir.push("rusty_pomodoro");
+        if let Some(name) = &self.name {
+            dir.push("profiles");
+            dir.push(name);
+        }
+        dir
+    }
+}
+
+fn is_valid_name(name: &str) -> bool {
+    !name.is_empty() && name.chars().all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_')
+}
+
+/// Every profile that has a data or config directory, the default profile
+/// first and the rest by name.
+pub fn all() -> Vec<Profile> {
+    let mut names = BTreeSet::new();
+    for base in [dirs_next::data_dir(), dirs_next::config_dir()] {
+        names.extend(names_in(&Profile::default().namespaced(base).join("profiles")));
+    }
+    
+    std::iter::once(Profile::default())
+        .chain(names.into_iter().map(|name| Profile { name: Some(name) }))
+        .collect()
+}
+
+/// Names of the profile directories in `dir`, skipping anything that
+/// `--profile` wouldn't accept.
+fn names_in(dir: &Path) -> Vec<String> {
+    let Ok(entries) = fs::read_dir(dir) else {
+        return Vec::new();
+    };
+    
+    entries
+        .filter_map(|entry| entry.ok())
+        .filter(|entry| entry.path().is_dir())
+        .filter_map(|entry| entry.file_name().into_string().ok())
+        .filter(|name| name != DEFAULT && is_valid_name(name))
+        .collect()
+}
+
+/// Makes `profile` the one every file path resolves against. Only the
+/// first call has any effect, so choose the profile before touching files.
+pub fn activate(profile: Profile) {
+    let _ = ACTIVE.set(profile);
+}
+
+/// The profile chosen at startup, or the default one.
+pub fn active() -> Profile {
+    ACTIVE.get().cloned().unwrap_or_default()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::TempDir;
+    
+    #[test]
+    fn test_default_profile_keeps_original_paths() {
+        let default = Profile::new(None).unwrap();
+        assert_eq!(Profile::new(Some("default")).unwrap(), default);
+        assert!(default.data_dir().ends_with("rusty_pomodoro"));
+        
+        let work = Profile::new(Some("work")).unwrap();
+        assert!(work.data_dir().ends_with("rusty_pomodoro/profiles/work"));
+        assert!(work.config_dir().ends_with("rusty_pomodoro/profiles/work"));
+    }
+    
+    #[test]
+    fn test_profile_names_cannot_escape_the_directory() {
+        assert!(Profile::new(Some("../etc")).is_err());
+        assert!(Profile::new(Some("a/b")).is_err());
+        assert!(Profile::new(Some("")).is_err());
+        assert!(Profile::new(Some("side_project-2")).is_ok());
+    }
+    
+    #[test]
+    fn test_names_in_lists_profile_directories() {
+        let dir = TempDir::new("profiles");
+        for name in ["work", "learning", "not a profile", "default"] {
+            fs::create_dir_all(dir.join(name)).unwrap();
+        }
+        fs::write(dir.join("notes.txt"), "").unwrap();
+        
+        let mut names = names_in(&dir);
+        names.sort();
+        assert_eq!(names, vec!["learning", "work"]);
+        assert!(names_in(&dir.join("missing")).is_empty());
+    }
+}
\ No newline at end of file
@@ -0,0 +1,123 @@
+use chrono::NaiveDate;
+use sha2::{Digest, Sha256};
+
+use crate::models::Session;
+
+/// A day's results against the daily goal, for `report`.
+pub struct DayReport {
+    pub day: NaiveDate,
+    pub goal: u32,
+    pub pomodoros: i64,
+    pub focus_minutes: i64,
+    /// Work sessions the figures come from, by start time
+    pub sessions: Vec<Session>,
+}
+
+impl DayReport {
+    /// The plain summary: day, goal and what was actually done.
+    pub fn render(&self, day_label: &str) -> String {
+        let goal = self.goal as i64;
+        let verdict = if self.pomodoros >= goal {
+            "goal met".to_string()
+        } else {
+            format!("missed by {}", goal - self.pomodoros)
+        };
+        
+        format!(
+            "Pomodoro report for {}\nGoal:     {} pomodoros\nActual:   {} pomodoros, {} min of focus\nResult:   {}",
+            day_label, self.goal, self.pomodoros, self.focus_minutes, verdict,
+        )
+    }
+    
+    /// The summary plus a digest of the sessions behind it, to send to an
+    /// accountability partner. Rerunning the report later prints a
+    /// different digest if any of the day's sessions were edited, added or
+    /// removed in the meantime.
+    pub fn render_accountability(&self, day_label: &str) -> String {
+        format!(
+            "{}\nSessions: {}\nSHA-256:  {}",
+            self.render(day_label),
+            self.sessions.len(),
+            digest(&self.sessions),
+        )
+    }
+}
+
+/// SHA-256 of the sessions as hex.
+///
+/// Each session contributes the line
+/// `id|start_time|end_time|pomodoro_count|completed\n`, with the values as
+/// `export` writes them, so the digest can be recomputed from an export.
+pub fn digest(sessions: &[Session]) -> String {
+    let mut hasher = Sha256::new();
+    for session in sessions {
+        hasher.update(format!(
+            "{}|{}|{}|{}|{}\n",
+            session.id.unwrap_or_default(),
+            session.start.to_rfc3339(),
+            session.end.to_rfc3339(),
+            session.pomodoro_count,
+            session.completed,
+        ));
+    }
+    
+    format!("{:x}", hasher.finalize())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::{Duration, Local, TimeZone};
+    
+    fn report(pomodoros: i64) -> DayReport {
+        let start = Local.with_ymd_and_hms(2024, 3, 14, 9, 0, 0).unwrap();
+        let sessions = (0..pomodoros)
+            .map(|i| {
+                let begin = start + Duration::minutes(30 * i);
+                Session {
+                    id: Some(i + 1),
+                    completed: true,
+                    pomodoro_count: i as u64 + 1,
+                    ..Session::new(begin, begin + Duration::minutes(25))
+                }
+            })
+            .collect();
+        
+        DayReport {
+            day: start.date_naive(),
+            goal: 4,
+            pomodoros,
+            focus_minutes: pomodoros * 25,
+            sessions,
+        }
+    }
+    
+    #[test]
+    fn test_report_verdict() {
+        assert!(report(5).render("2024-03-14").ends_with("Result:   goal met"));
+        assert!(report(3).render("2024-03-14").ends_with("Result:   missed by 1"));
+        
+        let text = report(3).render_accountability("2024-03-14");
+        assert!(text.starts_with("Pomodoro report for 2024-03-14\nGoal:     4 pomodoros\nActual:   3 pomodoros, 75 min of focus"));
+        assert!(text.contains("\nSessions: 3\nSHA-256:  "));
+    }
+    
+    #[test]
+    fn test_digest_detects_changed_rows() {
+        let original = report(3).sessions;
+        assert_eq!(digest(&original), digest(&report(3).sessions));
+        assert_eq!(digest(&original).len(), 64);
+        
+        // Changing whether a session counted changes the digest
+        let mut fudged = original.clone();
+        fudged[0].completed = false;
+        assert_ne!(digest(&fudged), digest(&original));
+        
+        let mut stretched = original.clone();
+        stretched[2].end += Duration::minutes(10);
+        assert_ne!(digest(&stretched), digest(&original));
+        
+        assert_ne!(digest(&original[..2]), digest(&original));
+        assert_eq!(digest(&[]), "e3b0c44298fc1c149afbf4c8996fb92427ae41e4649b934ca495991b7852b855");
+    }
+}
\ No newline at end of file
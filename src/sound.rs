@@ -0,0 +1,119 @@
+use std::error::Error;
+
+/// Length of the `sound-check` test tone
+#[cfg(feature = "sound")]
+const TEST_TONE: std::time::Duration = std::time::Duration::from_millis(800);
+
+/// Pitch of the test tone in Hz
+#[cfg(feature = "sound")]
+const TEST_TONE_HZ: f32 = 440.0;
+
+/// Prints the audio backend and its output devices, then unless
+/// `list_only`, plays a short tone on the default device, reporting each
+/// step so a "no sound" report shows where it went wrong.
+#[cfg(feature = "sound")]
+pub fn diagnose(list_only: bool) -> Result<(), Box<dyn Error>> {
+    use cpal::traits::{DeviceTrait, HostTrait};
+    
+    let host = cpal::default_host();
+    println!("Audio backend: {}", host.id().name());
+    
+    let default_name = host.default_output_device().and_then(|device| device.name().ok());
+    let mut found = 0;
+    for device in host.output_devices()? {
+        let name = device.name().unwrap_or_else(|e| format!("(unnamed: {})", e));
+        let marker = if Some(&name) == default_name.as_ref() { "*" } else { " " };
+        println!("  {} {}", marker, name);
+        found += 1;
+    }
+    if found == 0 {
+        println!("  (no output devices found)");
+    }
+    
+    if list_only {
+        return Ok(());
+    }
+    
+    let device = host.default_output_device().ok_or("no default output device")?;
+    let config = device.default_output_config()?;
+    println!(
+        "Playing a test tone on '{}' ({} Hz, {} channels, {:?})...",
+        device.name()?,
+        config.sample_rate().0,
+        config.channels(),
+        config.sample_format(),
+    );
+    
+    match config.sample_format() {
+        cpal::SampleFormat::F32 => play_tone::<f32>(&device, &config.into())?,
+        cpal::SampleFormat::I16 => play_tone::<i16>(&device, &config.into())?,
+        cpal::SampleFormat::U16 => play_tone::<u16>(&device, &config.into())?,
+        format => return Err(format!("unsupported sample format {:?}", format).into()),
+    }
+    println!("Done. If you heard nothing, check the device's volume and that it's the one you expect.");
+    
+    Ok(())
+}
+
+#[cfg(feature = "sound")]
+fn play_tone<T>(device: &cpal::Device, config: &cpal::StreamConfig) -> Result<(), Box<dyn Error>>
+where
+    T: cpal::SizedSample + cpal::FromSample<f32>,
+{
+    use cpal::traits::{DeviceTrait, StreamTrait};
+    
+    let sample_rate = config.sample_rate.0 as f32;
+    let channels = config.channels as usize;
+    let mut clock = 0.0;
+    
+    let stream = device.build_output_stream(
+        config,
+        move |data: &mut [T], _: &cpal::OutputCallbackInfo| {
+            for frame in data.chunks_mut(channels) {
+                clock = (clock + 1.0) % sample_rate;
+                // A quarter of full volume is plenty for a test
+                let value = (clock * TEST_TONE_HZ * 2.0 * std::f32::consts::PI / sample_rate).sin() * 0.25;
+                frame.fill(T::from_sample(value));
+            }
+        },
+        |e| eprintln!("Audio stream error: {}", e),
+        None,
+    )?;
+    stream.play()?;
+    std::thread::sleep(TEST_TONE);
+    
+    Ok(())
+}
+
+/// Plays the test tone on the default device in the background, as the
+/// chime for the `sound` setting. Failures are ignored, since a missing
+/// sound shouldn't stop the timer; `sound-check` shows what went wrong.
+#[cfg(feature = "sound")]
+pub fn chime() {
+    std::thread::spawn(|| {
+        use cpal::traits::{DeviceTrait, HostTrait};
+        
+        let Some(device) = cpal::default_host().default_output_device() else {
+            return;
+        };
+        let Ok(config) = device.default_output_config() else {
+            return;
+        };
+        let _ = match config.sample_format() {
+            cpal::SampleFormat::F32 => play_tone::<f32>(&device, &config.into()),
+            cpal::SampleFormat::I16 => play_tone::<i16>(&device, &config.into()),
+            cpal::SampleFormat::U16 => play_tone::<u16>(&device, &config.into()),
+            _ => Ok(()),
+        };
+    });
+}
+
+/// Builds without the `sound` feature stay quiet.
+#[cfg(not(feature = "sound"))]
+pub fn chime() {}
+
+/// Builds without the `sound` feature have no audio backend to check.
+#[cfg(not(feature = "sound"))]
+pub fn diagnose(_list_only: bool) -> Result<(), Box<dyn Error>> {
+    Err("sound feature not compiled; reinstall with `--features sound` to use audio".into())
+}
\ No newline at end of file
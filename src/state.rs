@@ -0,0 +1,98 @@
+use chrono::{DateTime, Local, NaiveDate};
+use serde::{Deserialize, Serialize};
+use std::{error::Error, fs, path::PathBuf, time::Duration};
+
+/// Things remembered between runs, kept apart from the user's config file
+/// so that saving them never rewrites it.
+#[derive(Debug, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct State {
+    /// Work length of the last timer run, in minutes
+    pub last_work: Option<u64>,
+    
+    /// Break length of the last timer run, in minutes
+    pub last_break: Option<u64>,
+    
+    /// The user turned down importing a legacy sessions file, so don't ask again
+    pub legacy_import_declined: bool,
+    
+    /// The work phase the timer was last quit during, for `resume_on_start`
+    pub unfinished: Option<Unfinished>,
+}
+
+/// A work phase quit before its time was up.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct Unfinished {
+    /// Seconds of work that were still to go
+    pub left_secs: u64,
+    pub quit_at: DateTime<Local>,
+}
+
+impl Unfinished {
+    pub fn new(left: Duration) -> Self {
+        Unfinished { left_secs: left.as_secs(), quit_at: Local::now() }
+    }
+    
+    /// The work still to go, if it was quit on `today`; a session left
+    /// over from an earlier day starts fresh.
+    pub fn left_on(&self, today: NaiveDate) -> Option<Duration> {
+        (crate::day::of(&self.quit_at) == today && self.left_secs > 0).then(|| Duration::from_secs(self.left_secs))
+    }
+}
+
+impl State {
+    /// Location of the state file, next to the session database.
+    pub fn path() -> PathBuf {
+        let mut path = crate::profile::active().data_dir();
+        path.push("state.toml");
+        path
+    }
+    
+    /// Loads the saved state. A missing or unreadable file just means
+    /// nothing is remembered yet.
+    pub fn load() -> Self {
+        fs::read_to_string(Self::path())
+            .ok()
+            .and_then(|contents| toml::from_str(&contents).ok())
+            .unwrap_or_default()
+    }
+    
+    pub fn save(&self) -> Result<(), Box<dyn Error>> {
+        let path = Self::path();
+        if let Some(dir) = path.parent() {
+            fs::create_dir_all(dir)?;
+        }
+        
+        fs::write(&path, toml::to_string_pretty(self)?)?;
+        
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    
+    #[test]
+    fn test_state_round_trip() {
+        let state = State { last_work: Some(50), last_break: Some(10), ..State::default() };
+        let saved = toml::to_string_pretty(&state).unwrap();
+        
+        assert_eq!(toml::from_str::<State>(&saved).unwrap(), state);
+        assert_eq!(toml::from_str::<State>("").unwrap(), State::default());
+    }
+    
+    #[test]
+    fn test_unfinished_only_resumes_the_same_day() {
+        let unfinished = Unfinished::new(Duration::from_secs(600));
+        let today = crate::day::of(&unfinished.quit_at);
+        
+        assert_eq!(unfinished.left_on(today), Some(Duration::from_secs(600)));
+        assert_eq!(unfinished.left_on(today.succ_opt().unwrap()), None);
+        assert_eq!(Unfinished { left_secs: 0, ..unfinished }.left_on(today), None);
+        
+        let state = State { unfinished: Some(unfinished), ..State::default() };
+        let saved = toml::to_string_pretty(&state).unwrap();
+        assert_eq!(toml::from_str::<State>(&saved).unwrap().unfinished.unwrap().left_secs, 600);
+    }
+}
\ No newline at end of file
@@ -0,0 +1,270 @@
+use chrono::{DateTime, Local};
+use serde::{Deserialize, Serialize};
+use std::{error::Error, fs, io, path::PathBuf};
+
+use crate::timer::{remaining_secs, PomodoroTimer, TimerState, TimerType};
+
+/// Snapshot of a running timer, written to the status file every second so
+/// other processes can see what it's doing.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Status {
+    pub pid: u32,
+    /// "work" or "break"
+    pub phase: String,
+    /// "running", "paused" or "stopped"
+    pub state: String,
+    pub elapsed_secs: u64,
+    /// Seconds left in the phase, `None` when the user decides when it ends
+    pub remaining_secs: Option<u64>,
+    pub completed_pomodoros: u64,
+    pub tag: Option<String>,
+    pub updated_at: DateTime<Local>,
+}
+
+impl Status {
+    pub fn capture(timer: &PomodoroTimer, open_ended: bool, completed_pomodoros: u64, tag: Option<&str>) -> Self {
+        let elapsed = timer.elapsed();
+        
+        Status {
+            pid: std::process::id(),
+            phase: match timer.timer_type() {
+                TimerType::Work => "work",
+                TimerType::Break => "break",
+            }
+            .to_string(),
+            state: match timer.state() {
+                TimerState::Running => "running",
+                TimerState::Paused => "paused",
+                TimerState::Stopped => "stopped",
+            }
+            .to_string(),
+            elapsed_secs: elapsed.as_secs(),
+            remaining_secs: (!open_ended).then(|| remaining_secs(timer)),
+            completed_pomodoros,
+            tag: tag.map(str::to_string),
+            updated_at: Local::now(),
+        }
+    }
+    
+    /// Advances a running timer's figures to `now`, since the file can be
+    /// up to a second old when read.
+    pub fn at(mut self, now: DateTime<Local>) -> Self {
+        if self.state == "running" {
+            let since = now.signed_duration_since(self.updated_at).num_seconds().max(0) as u64;
+            self.elapsed_secs += since;
+            self.remaining_secs = self.remaining_secs.map(|secs| secs.saturating_sub(since));
+        }
+        self.updated_at = now;
+        self
+    }
+}
+
+/// Location of the status file, next to the session database.
+pub fn path() -> PathBuf {
+    let mut path = crate::profile::active().data_dir();
+    path.push("status.json");
+    path
+}
+
+/// Replaces the status file, going through a temporary file so readers
+/// never see it half-written.
+pub fn write(status: &Status) -> Result<(), Box<dyn Error>> {
+    let path = path();
+    if let Some(dir) = path.parent() {
+        fs::create_dir_all(dir)?;
+    }
+    
+    let temp = path.with_extension("json.tmp");
+    fs::write(&temp, serde_json::to_string_pretty(status)?)?;
+    fs::rename(&temp, &path)?;
+    
+    Ok(())
+}
+
+/// Status of the running timer, or `None` if no timer is running.
+///
+/// A file left behind by a timer that crashed is ignored, on platforms
+/// where we can tell that its process is gone.
+pub fn read() -> Result<Option<Status>, Box<dyn Error>> {
+    let contents = match fs::read_to_string(path()) {
+        Ok(contents) => contents,
+        Err(e) if e.kind() == io::ErrorKind::NotFound => return Ok(None),
+        Err(e) => return Err(e.into()),
+    };
+    
+    let status: Status = serde_json::from_str(&contents)?;
+    Ok(is_alive(status.pid).then_some(status))
+}
+
+/// Removes the status file, and any request left unread, when dropped.
+///
+/// The timer installs one before writing its first status, so that every
+/// way it can exit, including errors and panics, leaves no stale files.
+/// Files whose status names another process are left alone, so a second
+/// timer that fails early doesn't cut off the one already running.
+pub struct Guard {
+    status: PathBuf,
+    control: PathBuf,
+}
+
+impl Guard {
+    pub fn install() -> Self {
+        Guard { status: path(), control: control_path() }
+    }
+}
+
+impl Drop for Guard {
+    fn drop(&mut self) {
+        let owner = fs::read_to_string(&self.status)
+            .ok()
+            .and_then(|contents| serde_json::from_str::<Status>(&contents).ok())
+            .map(|status| status.pid);
+        if owner.is_some_and(|pid| pid != std::process::id()) {
+            return;
+        }
+        
+        for file in [&self.status, &self.control] {
+            let _ = fs::remove_file(file);
+        }
+    }
+}
+
+/// Requests another process can make of the running timer.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Control {
+    /// Save the current session and exit
+    Stop,
+    Pause,
+    Resume,
+}
+
+impl Control {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Control::Stop => "stop",
+            Control::Pause => "pause",
+            Control::Resume => "resume",
+        }
+    }
+    
+    fn parse(s: &str) -> Option<Self> {
+        match s {
+            "stop" => Some(Control::Stop),
+            "pause" => Some(Control::Pause),
+            "resume" => Some(Control::Resume),
+            _ => None,
+        }
+    }
+}
+
+/// The running timer checks this file on every tick.
+fn control_path() -> PathBuf {
+    path().with_file_name("control")
+}
+
+/// Asks the running timer to act on `control`. Returns `false` if no timer
+/// is running to receive it.
+pub fn send(control: Control) -> Result<bool, Box<dyn Error>> {
+    if read()?.is_none() {
+        return Ok(false);
+    }
+    
+    fs::write(control_path(), control.as_str())?;
+    Ok(true)
+}
+
+/// Takes the pending request, if any, so each is acted on only once.
+pub fn take_control() -> Option<Control> {
+    let contents = fs::read_to_string(control_path()).ok()?;
+    let _ = fs::remove_file(control_path());
+    Control::parse(contents.trim())
+}
+
+#[cfg(target_os = "linux")]
+fn is_alive(pid: u32) -> bool {
+    std::path::Path::new(&format!("/proc/{}", pid)).exists()
+}
+
+#[cfg(not(target_os = "linux"))]
+fn is_alive(_pid: u32) -> bool {
+    true
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::TempDir;
+    use std::time::Duration;
+    
+    #[test]
+    fn test_status_capture_and_catch_up() {
+        let timer = PomodoroTimer::new(Duration::from_secs(25 * 60), Duration::from_secs(5 * 60));
+        let status = Status::capture(&timer, false, 2, Some("docs"));
+        
+        assert_eq!(status.phase, "work");
+        assert_eq!(status.state, "stopped");
+        assert_eq!(status.remaining_secs, Some(25 * 60));
+        assert_eq!(Status::capture(&timer, true, 2, None).remaining_secs, None);
+        
+        // Only a running timer moves on between writes
+        let later = status.updated_at + chrono::Duration::seconds(3);
+        assert_eq!(status.clone().at(later).remaining_secs, Some(25 * 60));
+        
+        let running = Status { state: "running".to_string(), ..status };
+        let caught_up = running.at(later);
+        assert_eq!(caught_up.elapsed_secs, 3);
+        assert_eq!(caught_up.remaining_secs, Some(25 * 60 - 3));
+    }
+    
+    #[test]
+    fn test_control_round_trip() {
+        for control in [Control::Stop, Control::Pause, Control::Resume] {
+            assert_eq!(Control::parse(control.as_str()), Some(control));
+        }
+        assert_eq!(Control::parse("reboot"), None);
+    }
+    
+    #[test]
+    fn test_guard_removes_files_on_every_exit() {
+        let dir = TempDir::new("guard");
+        let files = [dir.join("status.json"), dir.join("control")];
+        let guard = || Guard { status: files[0].clone(), control: files[1].clone() };
+        
+        // Dropped normally, with one file never written
+        fs::write(&files[0], "{}").unwrap();
+        drop(guard());
+        assert!(files.iter().all(|file| !file.exists()));
+        
+        // Dropped while unwinding from a panic
+        for file in &files {
+            fs::write(file, "").unwrap();
+        }
+        let guarded = guard();
+        let result = std::panic::catch_unwind(move || {
+            let _guard = guarded;
+            panic!("timer failed");
+        });
+        assert!(result.is_err());
+        assert!(files.iter().all(|file| !file.exists()));
+    }
+    
+    #[test]
+    fn test_guard_leaves_another_timers_files() {
+        let dir = TempDir::new("guard_other");
+        let (status_file, control_file) = (dir.join("status.json"), dir.join("control"));
+        
+        // A second timer that fails before writing its own status
+        let timer = PomodoroTimer::new(Duration::from_secs(25 * 60), Duration::from_secs(5 * 60));
+        let running = Status { pid: std::process::id() + 1, ..Status::capture(&timer, false, 0, None) };
+        fs::write(&status_file, serde_json::to_string(&running).unwrap()).unwrap();
+        fs::write(&control_file, "pause").unwrap();
+        drop(Guard { status: status_file.clone(), control: control_file.clone() });
+        assert!(status_file.exists() && control_file.exists());
+        
+        // Once the status is its own, the guard cleans up as usual
+        let own = Status { pid: std::process::id(), ..running };
+        fs::write(&status_file, serde_json::to_string(&own).unwrap()).unwrap();
+        drop(Guard { status: status_file.clone(), control: control_file.clone() });
+        assert!(!status_file.exists() && !control_file.exists());
+    }
+}
\ No newline at end of file
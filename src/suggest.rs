@@ -0,0 +1,54 @@
+/// Finds the candidate closest to a mistyped `input`.
+///
+/// Returns `None` when nothing is near enough to be a plausible typo, so
+/// callers can fall back to a plain "unknown" error.
+pub fn suggest<'a>(input: &str, candidates: &[&'a str]) -> Option<&'a str> {
+    let input = input.to_lowercase();
+    let max_distance = (input.chars().count() / 3).max(2);
+    
+    candidates
+        .iter()
+        .map(|&candidate| (levenshtein(&input, &candidate.to_lowercase()), candidate))
+        .filter(|&(distance, _)| distance <= max_distance)
+        .min_by_key(|&(distance, _)| distance)
+        .map(|(_, candidate)| candidate)
+}
+
+/// Number of single-character edits needed to turn `a` into `b`.
+fn levenshtein(a: &str, b: &str) -> usize {
+    let b: Vec<char> = b.chars().collect();
+    let mut previous: Vec<usize> = (0..=b.len()).collect();
+    let mut current = vec![0; b.len() + 1];
+    
+    for (i, ca) in a.chars().enumerate() {
+        current[0] = i + 1;
+        for (j, &cb) in b.iter().enumerate() {
+            let substitution = previous[j] + usize::from(ca != cb);
+            current[j + 1] = substitution.min(previous[j + 1] + 1).min(current[j] + 1);
+        }
+        std::mem::swap(&mut previous, &mut current);
+    }
+    
+    previous[b.len()]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    
+    #[test]
+    fn test_levenshtein() {
+        assert_eq!(levenshtein("", "abc"), 3);
+        assert_eq!(levenshtein("kitten", "sitting"), 3);
+        assert_eq!(levenshtein("deepwork", "deepwork"), 0);
+    }
+    
+    #[test]
+    fn test_suggest_nearest_candidate() {
+        let presets = ["classic", "deepwork", "short"];
+        
+        assert_eq!(suggest("deepwrok", &presets), Some("deepwork"));
+        assert_eq!(suggest("Clasic", &presets), Some("classic"));
+        assert_eq!(suggest("pizza", &presets), None);
+    }
+}
\ No newline at end of file
@@ -0,0 +1,66 @@
+use serde::{Deserialize, Serialize};
+use std::time::Duration;
+
+/// Flowtime breaks last one fifth of the time worked
+const FLOWTIME_BREAK_DIVISOR: u32 = 5;
+
+/// Shapes the work and break phases of a pomodoro variant.
+pub trait Technique {
+    /// Length of a work phase, or `None` when work runs until the user ends it.
+    fn work_duration(&self, configured: Duration) -> Option<Duration>;
+    
+    /// Length of the break that follows `worked` time at work.
+    fn break_duration(&self, configured: Duration, worked: Duration) -> Duration;
+}
+
+/// The pomodoro variants selectable with `--technique`.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize, clap::ValueEnum)]
+#[serde(rename_all = "lowercase")]
+pub enum TechniqueKind {
+    /// Fixed work and break lengths
+    #[default]
+    Classic,
+    /// Open-ended work with a break proportional to the time worked
+    Flowtime,
+}
+
+impl Technique for TechniqueKind {
+    fn work_duration(&self, configured: Duration) -> Option<Duration> {
+        match self {
+            TechniqueKind::Classic => Some(configured),
+            TechniqueKind::Flowtime => None,
+        }
+    }
+    
+    fn break_duration(&self, configured: Duration, worked: Duration) -> Duration {
+        match self {
+            TechniqueKind::Classic => configured,
+            TechniqueKind::Flowtime => worked / FLOWTIME_BREAK_DIVISOR,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    
+    #[test]
+    fn test_classic_uses_configured_durations() {
+        let work = Duration::from_secs(25 * 60);
+        let rest = Duration::from_secs(5 * 60);
+        
+        assert_eq!(TechniqueKind::Classic.work_duration(work), Some(work));
+        assert_eq!(TechniqueKind::Classic.break_duration(rest, Duration::from_secs(90 * 60)), rest);
+    }
+    
+    #[test]
+    fn test_flowtime_break_is_proportional() {
+        let rest = Duration::from_secs(5 * 60);
+        
+        assert_eq!(TechniqueKind::Flowtime.work_duration(Duration::from_secs(25 * 60)), None);
+        assert_eq!(
+            TechniqueKind::Flowtime.break_duration(rest, Duration::from_secs(50 * 60)),
+            Duration::from_secs(10 * 60)
+        );
+    }
+}
\ No newline at end of file
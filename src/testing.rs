@@ -0,0 +1,35 @@
+use std::{
+    fs,
+    ops::Deref,
+    path::{Path, PathBuf},
+};
+
+// Helpers shared by the unit tests of several modules.
+
+/// A fresh directory under the system temp dir, removed with everything in
+/// it when dropped, so a failing test doesn't leave it behind either.
+pub struct TempDir(PathBuf);
+
+impl TempDir {
+    /// `name` keeps tests running at the same time apart.
+    pub fn new(name: &str) -> Self {
+        let path = std::env::temp_dir().join(format!("rusty_pomodoro_{}_{}", name, std::process::id()));
+        let _ = fs::remove_dir_all(&path);
+        fs::create_dir_all(&path).unwrap();
+        TempDir(path)
+    }
+}
+
+impl Deref for TempDir {
+    type Target = Path;
+    
+    fn deref(&self) -> &Path {
+        &self.0
+    }
+}
+
+impl Drop for TempDir {
+    fn drop(&mut self) {
+        let _ = fs::remove_dir_all(&self.0);
+    }
+}
\ No newline at end of file
@@ -1,18 +1,50 @@
+use serde::{Deserialize, Serialize};
 use std::time::{Duration, Instant};
 
-#[derive(PartialEq, Copy, Clone)]
+/// Phase time past which the timer warns that it's been left running
+pub const LONG_RUNNING: Duration = Duration::from_secs(24 * 60 * 60);
+
+/// Most time a phase reports as elapsed. A timer forgotten for weeks reads
+/// as this rather than growing without bound through the display math.
+pub const MAX_ELAPSED: Duration = Duration::from_secs(30 * 24 * 60 * 60);
+
+#[derive(Debug, PartialEq, Copy, Clone)]
 pub enum TimerState {
     Running,
     Paused,
     Stopped,
 }
 
-#[derive(PartialEq, Copy, Clone)]
+#[derive(Debug, PartialEq, Copy, Clone, Serialize, clap::ValueEnum)]
+#[serde(rename_all = "lowercase")]
 pub enum TimerType {
     Work,
     Break,
 }
 
+/// What happens to a phase whose time runs out while the timer is paused,
+/// e.g. when `p` lands in its final tick.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum PausedCompletion {
+    /// Hold the phase at 00:00 and end it once the timer is resumed
+    #[default]
+    Defer,
+    /// End the phase right away and start the next one paused
+    Complete,
+}
+
+/// What extending or shortening a work phase does to the break after it.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum BreakScaling {
+    /// The break keeps its length
+    #[default]
+    Fixed,
+    /// The break grows with the work phase, keeping the work:break ratio
+    Proportional,
+}
+
 pub struct PomodoroTimer {
     work_duration: Duration,
     break_duration: Duration,
@@ -21,6 +53,17 @@ pub struct PomodoroTimer {
     elapsed_before_pause: Duration,
     timer_type: TimerType,
     state: TimerState,
+    /// Most unused break time that can be carried over, `None` when banking is off
+    bank_cap: Option<Duration>,
+    /// Unused break time waiting for the next break
+    banked: Duration,
+    /// Banked time added to the current break
+    break_bonus: Duration,
+    /// Time added to the current work phase by `extend_work`
+    work_extension: Duration,
+    break_scaling: BreakScaling,
+    /// Time added to the current break for an extended work phase
+    break_extension: Duration,
 }
 
 impl PomodoroTimer {
@@ -33,6 +76,12 @@ impl PomodoroTimer {
             elapsed_before_pause: Duration::from_secs(0),
             timer_type: TimerType::Work,
             state: TimerState::Stopped,
+            bank_cap: None,
+            banked: Duration::ZERO,
+            break_bonus: Duration::ZERO,
+            work_extension: Duration::ZERO,
+            break_scaling: BreakScaling::Fixed,
+            break_extension: Duration::ZERO,
         }
     }
     
@@ -42,6 +91,16 @@ impl PomodoroTimer {
         self.state = TimerState::Running;
     }
     
+    /// Stops the timer at the start of the current phase, to wait for `start`.
+    pub fn stop(&mut self) {
+        self.start_time = None;
+        self.pause_time = None;
+        self.elapsed_before_pause = Duration::ZERO;
+        self.state = TimerState::Stopped;
+    }
+    
+    /// Pauses a running timer. This never ends the phase by itself, even
+    /// when its time is already up; `phase_due` decides that.
     pub fn pause(&mut self) {
         if self.state == TimerState::Running {
             self.pause_time = Some(Instant::now());
@@ -52,7 +111,7 @@ impl PomodoroTimer {
     pub fn resume(&mut self) {
         if self.state == TimerState::Paused {
             if let (Some(start), Some(pause)) = (self.start_time, self.pause_time) {
-                self.elapsed_before_pause += pause.duration_since(start);
+                self.elapsed_before_pause = self.elapsed_before_pause.saturating_add(pause.duration_since(start));
                 self.start_time = Some(Instant::now());
                 self.pause_time = None;
             }
@@ -69,43 +128,141 @@ impl PomodoroTimer {
         }
     }
     
+    /// Changes the length of work phases, e.g. to end a sprint on time.
+    pub fn set_work_duration(&mut self, work_duration: Duration) {
+        self.work_duration = work_duration;
+    }
+    
+    /// Changes the break length, e.g. for breaks computed from the time worked.
+    pub fn set_break_duration(&mut self, break_duration: Duration) {
+        self.break_duration = break_duration;
+    }
+    
+    pub fn set_break_scaling(&mut self, break_scaling: BreakScaling) {
+        self.break_scaling = break_scaling;
+    }
+    
+    /// Adds `by` to the current work phase; does nothing during a break.
+    pub fn extend_work(&mut self, by: Duration) {
+        if self.timer_type == TimerType::Work {
+            self.work_extension += by;
+        }
+    }
+    
+    /// Takes back up to `by` of the time added with `extend_work`, so a
+    /// phase is never shorter than planned.
+    pub fn shorten_work(&mut self, by: Duration) {
+        if self.timer_type == TimerType::Work {
+            self.work_extension = self.work_extension.saturating_sub(by);
+        }
+    }
+    
+    /// Time added to the current work phase, zero during a break.
+    pub fn work_extension(&self) -> Duration {
+        self.work_extension
+    }
+    
+    /// Length of the break that follows the current work phase, before any
+    /// banked time: `break_duration`, scaled up with the phase's extension
+    /// under `BreakScaling::Proportional`.
+    pub fn projected_break(&self) -> Duration {
+        self.break_duration + self.scaled_extension()
+    }
+    
+    fn scaled_extension(&self) -> Duration {
+        if self.break_scaling == BreakScaling::Fixed || self.work_duration.is_zero() {
+            return Duration::ZERO;
+        }
+        let ratio = self.break_duration.as_secs_f64() / self.work_duration.as_secs_f64();
+        self.work_extension.mul_f64(ratio)
+    }
+    
+    /// Lets unused break time carry over to the next break, up to `cap` in total.
+    pub fn enable_banking(&mut self, cap: Duration) {
+        self.bank_cap = Some(cap);
+    }
+    
+    /// Banks whatever is left of the current break. Call this before ending
+    /// a break early; it does nothing during work or with banking off.
+    pub fn bank_remaining_break(&mut self) {
+        if let (TimerType::Break, Some(cap)) = (self.timer_type, self.bank_cap) {
+            let unused = self.total_time().saturating_sub(self.elapsed());
+            self.banked = (self.banked + unused).min(cap);
+        }
+    }
+    
+    /// Banked time for the coming break, or the bonus in the current one.
+    pub fn banked(&self) -> Duration {
+        match self.timer_type {
+            TimerType::Work => self.banked,
+            TimerType::Break => self.break_bonus,
+        }
+    }
+    
     pub fn switch_to_work(&mut self) {
         self.timer_type = TimerType::Work;
+        self.break_bonus = Duration::ZERO;
+        self.break_extension = Duration::ZERO;
         self.reset();
     }
     
     pub fn switch_to_break(&mut self) {
+        self.break_extension = self.scaled_extension();
+        self.work_extension = Duration::ZERO;
         self.timer_type = TimerType::Break;
+        self.break_bonus = std::mem::take(&mut self.banked);
         self.reset();
     }
     
-    pub fn update(&mut self) {
-        // Update internal timer state if needed
-    }
-    
+    /// Time run in the current phase, capped at `MAX_ELAPSED`.
     pub fn elapsed(&self) -> Duration {
-        match (self.state, self.start_time, self.pause_time) {
+        let elapsed = match (self.state, self.start_time, self.pause_time) {
             (TimerState::Running, Some(start), _) => {
-                self.elapsed_before_pause + start.elapsed()
+                self.elapsed_before_pause.saturating_add(start.elapsed())
             }
             (TimerState::Paused, Some(start), Some(pause)) => {
-                self.elapsed_before_pause + pause.duration_since(start)
+                self.elapsed_before_pause.saturating_add(pause.duration_since(start))
             }
             _ => self.elapsed_before_pause,
-        }
+        };
+        elapsed.min(MAX_ELAPSED)
     }
     
     pub fn total_time(&self) -> Duration {
         match self.timer_type {
-            TimerType::Work => self.work_duration,
-            TimerType::Break => self.break_duration,
+            TimerType::Work => self.work_duration.saturating_add(self.work_extension),
+            TimerType::Break => self
+                .break_duration
+                .saturating_add(self.break_extension)
+                .saturating_add(self.break_bonus),
         }
     }
     
+    /// Whether the current phase has run for `LONG_RUNNING` or more, most
+    /// likely because the timer was left on.
+    pub fn running_long(&self) -> bool {
+        self.elapsed() >= LONG_RUNNING
+    }
+    
     pub fn is_complete(&self) -> bool {
         self.elapsed() >= self.total_time()
     }
     
+    /// Whether the current phase should end now.
+    ///
+    /// A running timer's phase ends once `is_complete`. A paused one that has
+    /// reached the end is held under `PausedCompletion::Defer`, so it ends on
+    /// the first check after `resume`, and ends at once under
+    /// `PausedCompletion::Complete`. A stopped timer's phase never ends.
+    pub fn phase_due(&self, policy: PausedCompletion) -> bool {
+        let waiting = match self.state {
+            TimerState::Running => false,
+            TimerState::Paused => policy == PausedCompletion::Defer,
+            TimerState::Stopped => true,
+        };
+        !waiting && self.is_complete()
+    }
+    
     pub fn timer_type(&self) -> TimerType {
         self.timer_type
     }
@@ -115,6 +272,139 @@ impl PomodoroTimer {
     }
 }
 
+/// Decides whether a finished work phase counts as a pomodoro.
+///
+/// Automatic pauses (such as a locked screen) may cover at most
+/// `max_auto_pause` of the phase's length.
+pub fn counts_as_pomodoro(total: Duration, auto_paused: Duration, max_auto_pause: f64) -> bool {
+    auto_paused.as_secs_f64() <= total.as_secs_f64() * max_auto_pause
+}
+
+/// Decides whether the timer should exit for `auto_quit_minutes`, having sat
+/// stopped with no key pressed for `untouched`. Pauses never count, so a
+/// long deliberate, remote or lock-screen pause keeps the session.
+pub fn auto_quit_due(state: TimerState, untouched: Duration, limit_minutes: Option<u64>) -> bool {
+    state == TimerState::Stopped && limit_minutes.is_some_and(|limit| untouched >= Duration::from_secs(limit * 60))
+}
+
+/// Decides whether a break has to be taken in full, after `since_rest`
+/// pomodoros without one, under max_pomodoros_before_forced_rest.
+pub fn rest_required(timer_type: TimerType, since_rest: u32, max_before_rest: Option<u32>) -> bool {
+    timer_type == TimerType::Break && max_before_rest.is_some_and(|max| since_rest >= max)
+}
+
+/// Decides whether a work phase that ended early (by quitting, or by
+/// ending a flow session) is long enough to record: more than
+/// `min_session_secs` whole seconds.
+pub fn worth_recording(elapsed: Duration, min_session_secs: u64) -> bool {
+    elapsed.as_secs() > min_session_secs
+}
+
+/// Formats a time left (or, in flow sessions, run so far) as "MM:SS", or
+/// as "H:MM:SS" once it's an hour or more, so long blocks stay readable.
+pub fn format_remaining(secs: u64) -> String {
+    if secs >= 3600 {
+        format!("{}:{:02}:{:02}", secs / 3600, secs / 60 % 60, secs % 60)
+    } else {
+        format!("{:02}:{:02}", secs / 60, secs % 60)
+    }
+}
+
+/// Parses a span of time such as "2h", "90m" or "1h30m"; a bare number is
+/// minutes. Used for `start --sprint`.
+pub fn parse_span(text: &str) -> Result<Duration, String> {
+    let invalid = || format!("'{}' isn't a duration like 2h, 90m or 1h30m", text);
+    
+    let text = text.trim();
+    if let Ok(minutes) = text.parse::<u64>() {
+        return minutes
+            .checked_mul(60)
+            .filter(|_| minutes > 0)
+            .map(Duration::from_secs)
+            .ok_or_else(invalid);
+    }
+    
+    let mut total = 0;
+    let mut rest = text;
+    let mut seen_minutes = false;
+    while !rest.is_empty() {
+        let digits = rest.find(|c: char| !c.is_ascii_digit()).ok_or_else(invalid)?;
+        let amount: u64 = rest[..digits].parse().map_err(|_| invalid())?;
+        let unit = rest[digits..].chars().next().ok_or_else(invalid)?;
+        let unit_secs = match unit {
+            'h' if total == 0 && !seen_minutes => 3600,
+            'm' if !seen_minutes => 60,
+            _ => return Err(invalid()),
+        };
+        seen_minutes |= unit_secs == 60;
+        total = amount
+            .checked_mul(unit_secs)
+            .and_then(|secs| secs.checked_add(total))
+            .ok_or_else(invalid)?;
+        rest = &rest[digits + unit.len_utf8()..];
+    }
+    
+    (total > 0).then(|| Duration::from_secs(total)).ok_or_else(invalid)
+}
+
+/// Parses a custom `sequence` of phases such as `["work:25", "break:5",
+/// "work:25", "longbreak:20"]`, with lengths in minutes. A long break is an
+/// ordinary break; the name only makes the sequence easier to read.
+///
+/// The timer alternates work and breaks, so the sequence has to as well,
+/// starting with work and ending with a break to loop back round.
+pub fn parse_sequence(entries: &[String]) -> Result<Vec<(TimerType, Duration)>, String> {
+    if entries.is_empty() {
+        return Err("it needs at least one work phase and one break".to_string());
+    }
+    
+    let mut steps = Vec::with_capacity(entries.len());
+    for (i, entry) in entries.iter().enumerate() {
+        let invalid = || format!("'{}' isn't a phase like work:25, break:5 or longbreak:20", entry);
+        let (kind, minutes) = entry.split_once(':').ok_or_else(invalid)?;
+        let kind = match kind.trim() {
+            "work" => TimerType::Work,
+            "break" | "longbreak" => TimerType::Break,
+            _ => return Err(invalid()),
+        };
+        let minutes: u64 = minutes.trim().parse().map_err(|_| invalid())?;
+        if minutes == 0 {
+            return Err(format!("'{}' has no length", entry));
+        }
+        
+        let expected = if i % 2 == 0 { TimerType::Work } else { TimerType::Break };
+        if kind != expected {
+            return Err(format!(
+                "'{}' is out of turn; phases must alternate, starting with work",
+                entry
+            ));
+        }
+        let secs = minutes.checked_mul(60).ok_or_else(|| format!("'{}' is too long", entry))?;
+        steps.push((kind, Duration::from_secs(secs)));
+    }
+    if steps.len() % 2 == 1 {
+        return Err("it must end with a break, so it can start over with work".to_string());
+    }
+    
+    Ok(steps)
+}
+
+/// Seconds left in the current phase, with a partial second counting as a
+/// whole one so a countdown shows 25:00 until a full second has passed.
+pub fn remaining_secs(timer: &PomodoroTimer) -> u64 {
+    let remaining = timer.total_time().saturating_sub(timer.elapsed());
+    remaining.as_secs() + u64::from(remaining.subsec_nanos() > 0)
+}
+
+/// How many micro-break reminders a work phase has earned after `elapsed`
+/// of focus, one per `interval`.
+pub fn micro_breaks_due(elapsed: Duration, interval: Duration) -> u64 {
+    if interval.is_zero() {
+        return 0;
+    }
+    elapsed.as_secs() / interval.as_secs().max(1)
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -160,4 +450,286 @@ mod tests {
         assert_eq!(timer.timer_type(), TimerType::Break);
         assert_eq!(timer.total_time(), Duration::from_secs(5 * 60));
     }
+    
+    #[test]
+    fn test_counts_as_pomodoro() {
+        let total = Duration::from_secs(25 * 60);
+        
+        assert!(counts_as_pomodoro(total, Duration::ZERO, 0.5));
+        
+        // Up to and including the threshold still counts
+        let half = Duration::from_secs(25 * 30);
+        assert!(counts_as_pomodoro(total, half, 0.5));
+        assert!(!counts_as_pomodoro(total, half + Duration::from_secs(1), 0.5));
+    }
+    
+    #[test]
+    fn test_is_complete_at_exact_boundary() {
+        // `is_complete` is inclusive: elapsed == total already counts as done.
+        // The main loop relies on this by handling completion before drawing,
+        // so the 00:00 frame is never rendered with the next phase's title.
+        let timer = PomodoroTimer::new(Duration::ZERO, Duration::from_secs(5 * 60));
+        
+        assert_eq!(timer.elapsed(), timer.total_time());
+        assert!(timer.is_complete());
+    }
+    
+    #[test]
+    fn test_banked_break_time_accumulates_up_to_cap() {
+        let mut timer = PomodoroTimer::new(Duration::ZERO, Duration::from_secs(5 * 60));
+        timer.enable_banking(Duration::from_secs(8 * 60));
+        
+        // Left stopped so no time elapses. Skipping a whole break banks all of it for the next one
+        timer.switch_to_break();
+        timer.bank_remaining_break();
+        timer.switch_to_work();
+        assert_eq!(timer.banked(), Duration::from_secs(5 * 60));
+        
+        timer.switch_to_break();
+        assert_eq!(timer.banked(), Duration::from_secs(5 * 60));
+        assert_eq!(timer.total_time(), Duration::from_secs(10 * 60));
+        
+        // The longer break is banked in turn, but only up to the cap
+        timer.bank_remaining_break();
+        timer.switch_to_work();
+        assert_eq!(timer.banked(), Duration::from_secs(8 * 60));
+        
+        // A break that runs out uses up the bank
+        timer.switch_to_break();
+        timer.switch_to_work();
+        assert_eq!(timer.banked(), Duration::ZERO);
+    }
+    
+    #[test]
+    fn test_banking_is_off_by_default() {
+        let mut timer = PomodoroTimer::new(Duration::ZERO, Duration::from_secs(5 * 60));
+        timer.start();
+        timer.switch_to_break();
+        timer.bank_remaining_break();
+        timer.switch_to_work();
+        
+        assert_eq!(timer.banked(), Duration::ZERO);
+    }
+    
+    #[test]
+    fn test_worth_recording_threshold() {
+        // Exactly the minimum, or a fraction over, isn't enough
+        assert!(!worth_recording(Duration::from_secs(59), 60));
+        assert!(!worth_recording(Duration::from_secs(60), 60));
+        assert!(!worth_recording(Duration::from_millis(60_900), 60));
+        assert!(worth_recording(Duration::from_secs(61), 60));
+        
+        // Zero keeps anything that ran a second or more
+        assert!(worth_recording(Duration::from_secs(1), 0));
+        assert!(!worth_recording(Duration::from_millis(500), 0));
+    }
+    
+    #[test]
+    fn test_micro_breaks_due() {
+        let interval = Duration::from_secs(20 * 60);
+        
+        assert_eq!(micro_breaks_due(Duration::from_secs(19 * 60 + 59), interval), 0);
+        assert_eq!(micro_breaks_due(Duration::from_secs(20 * 60), interval), 1);
+        assert_eq!(micro_breaks_due(Duration::from_secs(45 * 60), interval), 2);
+        assert_eq!(micro_breaks_due(Duration::from_secs(45 * 60), Duration::ZERO), 0);
+    }
+    
+    #[test]
+    fn test_format_remaining_switches_to_hours() {
+        assert_eq!(format_remaining(0), "00:00");
+        assert_eq!(format_remaining(25 * 60), "25:00");
+        assert_eq!(format_remaining(59 * 60 + 59), "59:59");
+        assert_eq!(format_remaining(3600), "1:00:00");
+        assert_eq!(format_remaining(2 * 3600 + 5 * 60 + 9), "2:05:09");
+    }
+    
+    #[test]
+    fn test_remaining_secs_rounds_up() {
+        let mut timer = PomodoroTimer::new(Duration::from_secs(25 * 60), Duration::from_secs(5 * 60));
+        assert_eq!(remaining_secs(&timer), 25 * 60);
+        
+        timer.start();
+        sleep(Duration::from_millis(20));
+        assert_eq!(remaining_secs(&timer), 25 * 60);
+    }
+    
+    #[test]
+    fn test_pause_at_boundary() {
+        let mut timer = PomodoroTimer::new(Duration::ZERO, Duration::from_secs(5 * 60));
+        assert!(!timer.phase_due(PausedCompletion::Complete));
+        
+        timer.start();
+        assert!(timer.phase_due(PausedCompletion::Defer));
+        
+        // Paused with no time left: held until resumed, unless completing
+        timer.pause();
+        assert!(timer.is_complete());
+        assert!(!timer.phase_due(PausedCompletion::Defer));
+        assert!(timer.phase_due(PausedCompletion::Complete));
+        
+        timer.resume();
+        assert!(timer.phase_due(PausedCompletion::Defer));
+    }
+    
+    #[test]
+    fn test_parse_span() {
+        let mins = |m: u64| Duration::from_secs(m * 60);
+        
+        assert_eq!(parse_span("2h"), Ok(mins(120)));
+        assert_eq!(parse_span("90m"), Ok(mins(90)));
+        assert_eq!(parse_span("1h30m"), Ok(mins(90)));
+        assert_eq!(parse_span("45"), Ok(mins(45)));
+        
+        for bad in ["", "0", "0h", "h", "2x", "30m1h", "1h2h", "1.5h", "-2h"] {
+            assert!(parse_span(bad).is_err(), "{:?} should be rejected", bad);
+        }
+        
+        // Non-ASCII units and amounts too big to count are errors, not panics
+        for bad in ["2é", "1h5€", "18446744073709551615", "18446744073709551615h", "6000000000000000h"] {
+            assert!(parse_span(bad).is_err(), "{:?} should be rejected", bad);
+        }
+    }
+    
+    #[test]
+    fn test_proportional_break_keeps_ratio_across_adjustments() {
+        let mins = |m: u64| Duration::from_secs(m * 60);
+        let mut timer = PomodoroTimer::new(mins(25), mins(5));
+        timer.set_break_scaling(BreakScaling::Proportional);
+        
+        for _ in 0..3 {
+            timer.extend_work(mins(5));
+        }
+        assert_eq!(timer.total_time(), mins(40));
+        assert_eq!(timer.projected_break(), mins(8));
+        
+        timer.shorten_work(mins(5));
+        assert_eq!(timer.total_time(), mins(35));
+        assert_eq!(timer.projected_break(), mins(7));
+        
+        // Odd minutes keep the 5:1 ratio to the second
+        timer.extend_work(mins(1));
+        assert_eq!(timer.projected_break(), Duration::from_secs(7 * 60 + 12));
+        
+        timer.switch_to_break();
+        assert_eq!(timer.total_time(), Duration::from_secs(7 * 60 + 12));
+        assert_eq!(timer.work_extension(), Duration::ZERO);
+        
+        // Extensions belong to one phase, and don't carry on to the next
+        timer.switch_to_work();
+        assert_eq!(timer.total_time(), mins(25));
+        timer.switch_to_break();
+        assert_eq!(timer.total_time(), mins(5));
+    }
+    
+    #[test]
+    fn test_fixed_break_ignores_extensions() {
+        let mins = |m: u64| Duration::from_secs(m * 60);
+        let mut timer = PomodoroTimer::new(mins(25), mins(5));
+        
+        timer.extend_work(mins(10));
+        assert_eq!(timer.total_time(), mins(35));
+        assert_eq!(timer.projected_break(), mins(5));
+        
+        // Shortening only takes back extensions
+        timer.shorten_work(mins(15));
+        assert_eq!(timer.total_time(), mins(25));
+        
+        timer.extend_work(mins(10));
+        timer.switch_to_break();
+        assert_eq!(timer.total_time(), mins(5));
+        timer.extend_work(mins(10));
+        assert_eq!(timer.total_time(), mins(5));
+    }
+    
+    #[test]
+    fn test_forgotten_timer_is_capped_and_flagged() {
+        let mut timer = PomodoroTimer::new(Duration::from_secs(25 * 60), Duration::from_secs(5 * 60));
+        
+        // Left stopped, so elapsed time is exactly what's set here
+        timer.elapsed_before_pause = LONG_RUNNING - Duration::from_secs(1);
+        assert!(!timer.running_long());
+        timer.elapsed_before_pause = LONG_RUNNING;
+        assert!(timer.running_long());
+        
+        // Far more time than any clock will report doesn't overflow the math
+        timer.start();
+        timer.elapsed_before_pause = Duration::MAX;
+        assert_eq!(timer.elapsed(), MAX_ELAPSED);
+        assert!(timer.is_complete());
+        assert_eq!(remaining_secs(&timer), 0);
+        
+        timer.pause();
+        timer.resume();
+        assert_eq!(timer.elapsed(), MAX_ELAPSED);
+        assert_eq!(format_remaining(timer.elapsed().as_secs()), "720:00:00");
+        
+        timer.switch_to_break();
+        assert!(!timer.running_long());
+    }
+    
+    #[test]
+    fn test_parse_sequence() {
+        let entries = |list: &[&str]| list.iter().map(|s| s.to_string()).collect::<Vec<_>>();
+        let mins = |m: u64| Duration::from_secs(m * 60);
+        
+        let steps = parse_sequence(&entries(&["work:25", "break:5", "work:50", "longbreak:20"])).unwrap();
+        assert_eq!(steps, vec![
+            (TimerType::Work, mins(25)),
+            (TimerType::Break, mins(5)),
+            (TimerType::Work, mins(50)),
+            (TimerType::Break, mins(20)),
+        ]);
+        
+        assert!(parse_sequence(&[]).is_err());
+        assert!(parse_sequence(&entries(&["work:25"])).unwrap_err().contains("end with a break"));
+        assert!(parse_sequence(&entries(&["break:5", "work:25"])).unwrap_err().contains("out of turn"));
+        assert!(parse_sequence(&entries(&["work:25", "work:25"])).unwrap_err().contains("out of turn"));
+        assert!(parse_sequence(&entries(&["work:0", "break:5"])).unwrap_err().contains("no length"));
+        assert!(parse_sequence(&entries(&["nap:25", "break:5"])).is_err());
+        assert!(parse_sequence(&entries(&["work", "break:5"])).is_err());
+        
+        // Lengths too big to count in seconds are errors, not panics
+        let too_long = entries(&["work:18446744073709551615", "break:5"]);
+        assert!(parse_sequence(&too_long).unwrap_err().contains("too long"));
+    }
+    
+    #[test]
+    fn test_stopped_phase_waits_for_start() {
+        let mut timer = PomodoroTimer::new(Duration::ZERO, Duration::from_secs(5 * 60));
+        timer.start();
+        timer.switch_to_break();
+        timer.switch_to_work();
+        
+        // Even a phase with no time left doesn't end before it's started
+        timer.stop();
+        assert_eq!(timer.state(), TimerState::Stopped);
+        assert_eq!(timer.elapsed(), Duration::ZERO);
+        assert!(!timer.phase_due(PausedCompletion::Complete));
+        
+        timer.start();
+        assert!(timer.phase_due(PausedCompletion::Defer));
+    }
+    
+    #[test]
+    fn test_auto_quit_only_when_stopped() {
+        let mins = |m: u64| Duration::from_secs(m * 60);
+        
+        assert!(auto_quit_due(TimerState::Stopped, mins(30), Some(30)));
+        assert!(!auto_quit_due(TimerState::Stopped, mins(29), Some(30)));
+        assert!(!auto_quit_due(TimerState::Stopped, mins(600), None));
+        
+        // A long pause, however it started, keeps the session
+        assert!(!auto_quit_due(TimerState::Paused, mins(600), Some(30)));
+        assert!(!auto_quit_due(TimerState::Running, mins(600), Some(30)));
+    }
+    
+    #[test]
+    fn test_rest_required_after_max_pomodoros() {
+        assert!(!rest_required(TimerType::Break, 3, Some(4)));
+        assert!(rest_required(TimerType::Break, 4, Some(4)));
+        assert!(rest_required(TimerType::Break, 6, Some(4)));
+        assert!(!rest_required(TimerType::Break, 6, None));
+        // Work phases are never held
+        assert!(!rest_required(TimerType::Work, 6, Some(4)));
+    }
 }
\ No newline at end of file
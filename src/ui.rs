@@ -0,0 +1,1128 @@
+use chrono::NaiveTime;
+use crossterm::{
+    event::{self, DisableMouseCapture, EnableMouseCapture, Event, KeyCode},
+    execute,
+    terminal::{disable_raw_mode, enable_raw_mode, EnterAlternateScreen, LeaveAlternateScreen},
+};
+use serde::{Deserialize, Serialize};
+use std::{
+    collections::HashMap,
+    io::{self, Stdout},
+    ops::{Deref, DerefMut},
+    time::{Duration, Instant},
+};
+use tui::{
+    backend::{Backend, CrosstermBackend},
+    layout::{Constraint, Direction, Layout, Rect},
+    style::{Color, Modifier, Style},
+    text::{Line, Span},
+    widgets::{BarChart, Block, Borders, Clear, Gauge, List, ListItem, ListState, Paragraph},
+    Frame, Terminal,
+};
+
+use crate::analytics;
+use crate::status::Status;
+use crate::suggest::suggest;
+use crate::timer::{format_remaining, remaining_secs, PomodoroTimer, TimerState, TimerType};
+
+/// The terminal in raw mode on the alternate screen, for a full-screen
+/// view. Dropping it puts the terminal back, so the shell is left usable
+/// however the view ends: a key, an error returned with `?`, or a panic.
+pub struct Screen {
+    terminal: Terminal<CrosstermBackend<Stdout>>,
+    mouse: bool,
+}
+
+impl Screen {
+    /// `mouse` also captures mouse events, which turns off the terminal's
+    /// own text selection until the screen is dropped.
+    pub fn enter(mouse: bool) -> io::Result<Self> {
+        enable_raw_mode()?;
+        let setup = || {
+            let mut stdout = io::stdout();
+            execute!(stdout, EnterAlternateScreen)?;
+            if mouse {
+                execute!(stdout, EnableMouseCapture)?;
+            }
+            Terminal::new(CrosstermBackend::new(stdout))
+        };
+        
+        match setup() {
+            Ok(terminal) => Ok(Screen { terminal, mouse }),
+            Err(e) => {
+                leave(&mut io::stdout(), mouse);
+                Err(e)
+            }
+        }
+    }
+}
+
+impl Deref for Screen {
+    type Target = Terminal<CrosstermBackend<Stdout>>;
+    
+    fn deref(&self) -> &Self::Target {
+        &self.terminal
+    }
+}
+
+impl DerefMut for Screen {
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        &mut self.terminal
+    }
+}
+
+impl Drop for Screen {
+    fn drop(&mut self) {
+        leave(self.terminal.backend_mut(), self.mouse);
+        let _ = self.terminal.show_cursor();
+    }
+}
+
+/// Undoes `Screen::enter` as far as possible; errors are ignored since
+/// there's nothing better to do with them on the way out.
+fn leave<W: io::Write>(out: &mut W, mouse: bool) {
+    if mouse {
+        let _ = execute!(out, DisableMouseCapture);
+    }
+    let _ = execute!(out, LeaveAlternateScreen);
+    let _ = disable_raw_mode();
+}
+
+/// Styles for the timer view.
+pub struct Theme {
+    pub work: Style,
+    pub rest: Style,
+    /// Text and borders of the panels
+    pub text: Style,
+    /// Selected history entry
+    pub highlight: Style,
+}
+
+impl Theme {
+    /// Without `color` the styles are monochrome, telling phases apart by
+    /// weight alone, whatever `high_contrast` says.
+    pub fn new(high_contrast: bool, color: bool) -> Self {
+        if !color {
+            Theme {
+                work: Style::default().add_modifier(Modifier::BOLD),
+                rest: Style::default(),
+                text: Style::default(),
+                highlight: Style::default().add_modifier(Modifier::REVERSED),
+            }
+        } else if high_contrast {
+            // Bright colors on black, bold text and no dimmed or reversed styles
+            let bright = Style::default().bg(Color::Black).add_modifier(Modifier::BOLD);
+            Theme {
+                work: bright.fg(Color::LightYellow),
+                rest: bright.fg(Color::LightCyan),
+                text: bright.fg(Color::White),
+                highlight: Style::default().fg(Color::Black).bg(Color::White).add_modifier(Modifier::BOLD),
+            }
+        } else {
+            Theme {
+                work: Style::default().fg(Color::Red),
+                rest: Style::default().fg(Color::Green),
+                text: Style::default(),
+                highlight: Style::default().add_modifier(Modifier::REVERSED),
+            }
+        }
+    }
+}
+
+/// Figures shown by the `stats --watch` dashboard.
+pub struct Dashboard {
+    pub today_pomodoros: i64,
+    pub daily_goal: i64,
+    pub week_pomodoros: i64,
+    pub weekly_goal: i64,
+    pub streak: u32,
+    /// Pomodoros per day for the last seven days, oldest first
+    pub last_seven_days: Vec<(String, u64)>,
+    /// Pomodoros per day of the current week, Monday first, with days still
+    /// to come at zero
+    pub this_week: Vec<(String, i64)>,
+    /// Position of today in `this_week`
+    pub today_index: usize,
+}
+
+/// Drops repeated presses of the same control key, such as the terminal's
+/// key repeat while `p` is held, so one press acts once.
+pub struct KeyDebounce {
+    window: Duration,
+    last_pressed: HashMap<char, Instant>,
+}
+
+impl KeyDebounce {
+    pub fn new(window: Duration) -> Self {
+        Self { window, last_pressed: HashMap::new() }
+    }
+    
+    /// Whether a press of `key` at `now` should act. Every press restarts
+    /// the key's window, even an ignored one, so a held key acts only once.
+    pub fn accept(&mut self, key: char, now: Instant) -> bool {
+        let repeated = self
+            .last_pressed
+            .insert(key, now)
+            .is_some_and(|last| now.saturating_duration_since(last) < self.window);
+        
+        !repeated
+    }
+}
+
+/// Scroll state for the recent-history panel in the timer view.
+pub struct HistoryPanel {
+    pub items: Vec<String>,
+    pub state: ListState,
+    /// Rows visible in the panel, updated on every draw
+    pub page_size: usize,
+}
+
+impl HistoryPanel {
+    pub fn new(items: Vec<String>) -> Self {
+        let mut panel = Self {
+            items: Vec::new(),
+            state: ListState::default(),
+            page_size: 1,
+        };
+        panel.set_items(items);
+        panel
+    }
+    
+    /// Replaces the entries, keeping the selection within bounds.
+    pub fn set_items(&mut self, items: Vec<String>) {
+        self.items = items;
+        let selected = match self.state.selected() {
+            _ if self.items.is_empty() => None,
+            Some(i) => Some(i.min(self.items.len() - 1)),
+            None => Some(0),
+        };
+        self.state.select(selected);
+    }
+    
+    /// Moves the selection by `delta` rows, stopping at either end.
+    pub fn scroll(&mut self, delta: isize) {
+        if self.items.is_empty() {
+            return;
+        }
+        
+        let current = self.state.selected().unwrap_or(0) as isize;
+        let last = self.items.len() as isize - 1;
+        self.state.select(Some((current + delta).clamp(0, last) as usize));
+    }
+    
+    pub fn page_up(&mut self) {
+        self.scroll(-(self.page_size.max(1) as isize));
+    }
+    
+    pub fn page_down(&mut self) {
+        self.scroll(self.page_size.max(1) as isize);
+    }
+}
+
+/// Draws the read-only live statistics dashboard.
+pub fn draw_dashboard<B: Backend>(f: &mut Frame<B>, data: &Dashboard, theme: &Theme) {
+    let chunks = Layout::default()
+        .direction(Direction::Vertical)
+        .margin(2)
+        .constraints(
+            [
+                Constraint::Length(3),
+                Constraint::Length(3),
+                Constraint::Length(3),
+                Constraint::Min(0),
+                Constraint::Length(3),
+            ]
+            .as_ref(),
+        )
+        .split(f.size());
+    
+    let goal_gauge = |title: &str, current: i64, goal: i64, style: Style| {
+        Gauge::default()
+            .block(Block::default().title(title.to_string()).borders(Borders::ALL))
+            .gauge_style(style)
+            .ratio(goal_ratio(current, goal))
+            .label(format!("{}/{}", current, goal))
+    };
+    
+    f.render_widget(
+        goal_gauge("🍅 Today", data.today_pomodoros, data.daily_goal, theme.work),
+        chunks[0],
+    );
+    f.render_widget(
+        goal_gauge("📅 This Week", data.week_pomodoros, data.weekly_goal, theme.rest),
+        chunks[1],
+    );
+    
+    // The week's shape: each day filled towards the daily goal
+    let days = Layout::default()
+        .direction(Direction::Horizontal)
+        .constraints([Constraint::Ratio(1, 7); 7].as_ref())
+        .split(chunks[2]);
+    for (i, ((day, count), area)) in data.this_week.iter().zip(days.iter()).enumerate() {
+        let today = i == data.today_index;
+        let (title, border) = if today {
+            (format!("▶{}", day), theme.work.add_modifier(Modifier::BOLD))
+        } else {
+            (day.clone(), Style::default())
+        };
+        let gauge = Gauge::default()
+            .block(Block::default().title(title).borders(Borders::ALL).border_style(border))
+            .gauge_style(if today { theme.work } else { theme.rest })
+            .ratio(goal_ratio(*count, data.daily_goal))
+            .label(count.to_string());
+        
+        f.render_widget(gauge, *area);
+    }
+    
+    let bars: Vec<(&str, u64)> = data
+        .last_seven_days
+        .iter()
+        .map(|(day, count)| (day.as_str(), *count))
+        .collect();
+    
+    let chart = BarChart::default()
+        .block(Block::default().title("Last 7 Days").borders(Borders::ALL))
+        .data(&bars)
+        .bar_width(5)
+        .bar_gap(2)
+        .bar_style(theme.work)
+        .value_style(Style::default().add_modifier(Modifier::BOLD));
+    
+    f.render_widget(chart, chunks[3]);
+    
+    let footer = Paragraph::new(Line::from(vec![
+        Span::raw(format!("🔥 Streak: {} days    Press ", data.streak)),
+        Span::styled("q", Style::default().add_modifier(Modifier::BOLD)),
+        Span::raw(" to quit"),
+    ]))
+    .block(Block::default().borders(Borders::ALL));
+    
+    f.render_widget(footer, chunks[4]);
+}
+
+/// Share of `goal` reached, capped at full; a goal of zero is always met.
+fn goal_ratio(current: i64, goal: i64) -> f64 {
+    if goal > 0 {
+        (current.max(0) as f64 / goal as f64).min(1.0)
+    } else {
+        1.0
+    }
+}
+
+/// Which way the timer's gauge moves as the phase goes on.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum GaugeDirection {
+    /// Starts empty and fills up
+    #[default]
+    Fill,
+    /// Starts full and empties
+    Drain,
+}
+
+impl GaugeDirection {
+    /// How full to draw the gauge once `done` of the phase (0 to 1) has passed.
+    pub fn ratio(self, done: f64) -> f64 {
+        match self {
+            GaugeDirection::Fill => done,
+            GaugeDirection::Drain => 1.0 - done,
+        }
+    }
+}
+
+/// Tokens a gauge label template can use
+const LABEL_TOKENS: [&str; 4] = ["remaining", "elapsed", "total", "percent"];
+
+/// The `gauge_label` template, e.g. "{remaining} left", parsed once so
+/// drawing only has to fill it in.
+#[derive(Debug, Clone, PartialEq)]
+pub struct GaugeLabel(Vec<LabelPart>);
+
+#[derive(Debug, Clone, PartialEq)]
+enum LabelPart {
+    Text(String),
+    /// Time left, as the countdown shows it
+    Remaining,
+    Elapsed,
+    /// Length of the whole phase
+    Total,
+    /// Share of the phase done, rounded down
+    Percent,
+}
+
+impl GaugeLabel {
+    /// Parses a template, rejecting unknown tokens and unclosed braces.
+    pub fn parse(template: &str) -> Result<Self, String> {
+        let mut parts = Vec::new();
+        let mut rest = template;
+        while let Some(open) = rest.find('{') {
+            if open > 0 {
+                parts.push(LabelPart::Text(rest[..open].to_string()));
+            }
+            let close = rest[open..]
+                .find('}')
+                .map(|close| open + close)
+                .ok_or_else(|| format!("'{{' without a closing '}}' in '{}'", template))?;
+            let token = &rest[open + 1..close];
+            parts.push(match token {
+                "remaining" => LabelPart::Remaining,
+                "elapsed" => LabelPart::Elapsed,
+                "total" => LabelPart::Total,
+                "percent" => LabelPart::Percent,
+                _ => {
+                    return Err(match suggest(token, &LABEL_TOKENS) {
+                        Some(candidate) => format!("unknown token '{{{}}}', did you mean '{{{}}}'?", token, candidate),
+                        None => format!("unknown token '{{{}}}' (available: {{{}}})", token, LABEL_TOKENS.join("}, {")),
+                    })
+                }
+            });
+            rest = &rest[close + 1..];
+        }
+        if !rest.is_empty() {
+            parts.push(LabelPart::Text(rest.to_string()));
+        }
+        
+        Ok(GaugeLabel(parts))
+    }
+    
+    /// Fills in the template for the timer's current phase.
+    pub fn render(&self, timer: &PomodoroTimer) -> String {
+        let total = timer.total_time();
+        self.0
+            .iter()
+            .map(|part| match part {
+                LabelPart::Text(text) => text.clone(),
+                LabelPart::Remaining => format_remaining(remaining_secs(timer)),
+                LabelPart::Elapsed => format_remaining(timer.elapsed().min(total).as_secs()),
+                LabelPart::Total => format_remaining(total.as_secs()),
+                LabelPart::Percent => {
+                    let done = if total.is_zero() { 1.0 } else { timer.elapsed().as_secs_f64() / total.as_secs_f64() };
+                    ((done.min(1.0) * 100.0) as u32).to_string()
+                }
+            })
+            .collect()
+    }
+}
+
+impl Default for GaugeLabel {
+    /// Just the countdown
+    fn default() -> Self {
+        GaugeLabel(vec![LabelPart::Remaining])
+    }
+}
+
+/// Everything the timer view shows, gathered from the event loop.
+pub struct TimerView<'a> {
+    pub timer: &'a PomodoroTimer,
+    pub work_mins: u64,
+    /// Flowtime work phase, counting up with no set end
+    pub open_ended: bool,
+    /// What the work session is meant to get done, shown in the title
+    pub intention: Option<&'a str>,
+    /// Label on the gauge, unless the phase is open-ended
+    pub gauge_label: &'a GaugeLabel,
+    pub gauge_direction: GaugeDirection,
+    /// Paused because the screen is locked
+    pub locked: bool,
+    /// The work phase was marked as not counting
+    pub invalidated: bool,
+    /// The break can't be skipped, per max_pomodoros_before_forced_rest
+    pub rest_required: bool,
+    /// Short-lived message shown next to the timer state, e.g. a micro-break
+    pub flash: Option<&'a str>,
+    /// Running time of the whole cycle of phases, when shown
+    pub cycle: Option<Duration>,
+    /// Wall-clock time shown in the status block
+    pub clock: NaiveTime,
+    pub today_pomodoros: u64,
+    pub daily_goal: u64,
+    /// Today's pomodoros against a steady pace towards the goal, when there is one
+    pub pace: Option<analytics::Pace>,
+    /// Filled and empty symbols for the goal tally
+    pub tally_symbols: (&'a str, &'a str),
+    /// Accessibility modes in use, named in the help title
+    pub modes: Vec<&'a str>,
+    /// Text of the today overview, drawn over everything when open
+    pub overview: Option<&'a str>,
+    pub theme: &'a Theme,
+}
+
+/// Draws the running timer, updating the history panel's page size to fit.
+pub fn draw_timer<B: Backend>(f: &mut Frame<B>, view: &TimerView, history: &mut HistoryPanel) {
+    let timer = view.timer;
+    let theme = view.theme;
+    
+    let chunks = Layout::default()
+        .direction(Direction::Vertical)
+        .margin(2)
+        .constraints(
+            [
+                Constraint::Length(3),
+                Constraint::Length(3),
+                Constraint::Length(3),
+                Constraint::Length(3),
+                Constraint::Min(0),
+                Constraint::Length(3),
+            ]
+            .as_ref(),
+        )
+        .split(f.size());
+    
+    // Title block
+    let mut title = match timer.timer_type() {
+        TimerType::Work if view.open_ended => "🌊 Flow Session (press s to take a break)".to_string(),
+        TimerType::Work => format!("🍅 Work Session ({}m)", view.work_mins),
+        TimerType::Break => format!("☕ Break ({}m)", timer.total_time().as_secs().div_ceil(60)),
+    };
+    if let (TimerType::Work, Some(intention)) = (timer.timer_type(), view.intention) {
+        title.push_str(&format!(" · {}", intention));
+    }
+    let banked = timer.banked().as_secs();
+    if banked > 0 {
+        title.push_str(&format!(" · +{}:{:02} banked", banked / 60, banked % 60));
+    }
+    
+    let title_block = Block::default()
+        .title(title)
+        .borders(Borders::ALL)
+        .style(theme.text);
+    
+    f.render_widget(title_block, chunks[0]);
+    
+    // Timer gauge
+    let elapsed = timer.elapsed().as_secs_f64();
+    let total = timer.total_time().as_secs_f64();
+    let done = if total > 0.0 { (elapsed / total).clamp(0.0, 1.0) } else { 1.0 };
+    let ratio = view.gauge_direction.ratio(done);
+    
+    let gauge_label = if view.open_ended {
+        // Count up instead, since there's no end to count down to
+        format!("{} ↑", format_remaining(timer.elapsed().as_secs()))
+    } else {
+        view.gauge_label.render(timer)
+    };
+    
+    let gauge = Gauge::default()
+        .block(Block::default().borders(Borders::ALL).style(theme.text))
+        .gauge_style(match timer.timer_type() {
+            TimerType::Work => theme.work,
+            TimerType::Break => theme.rest,
+        })
+        .ratio(ratio)
+        .label(gauge_label);
+    
+    f.render_widget(gauge, chunks[1]);
+    
+    // Status
+    let status = match timer.state() {
+        TimerState::Paused if view.locked => "🔒 Paused (screen locked)",
+        TimerState::Running => "⏱️  Running",
+        TimerState::Paused => "⏸️  Paused",
+        TimerState::Stopped => "⏹️  Stopped",
+    };
+    let mut status = status.to_string();
+    if view.invalidated {
+        status.push_str("   ✗ Invalidated, won't count");
+    }
+    let extension = timer.work_extension().as_secs();
+    if extension > 0 {
+        status.push_str(&format!(
+            "   +{}m, then a {} break",
+            extension.div_ceil(60),
+            format_remaining(timer.projected_break().as_secs()),
+        ));
+    }
+    if let Some(flash) = view.flash {
+        status.push_str(&format!("   {}", flash));
+    }
+    
+    // Wall-clock time shares the status block to keep the layout compact
+    let status_title = format!("Status · {}", view.clock.format("%H:%M"));
+    let status_para = Paragraph::new(status)
+        .style(theme.text)
+        .block(Block::default().title(status_title).borders(Borders::ALL));
+    
+    f.render_widget(status_para, chunks[2]);
+    
+    // Stats
+    let (filled, empty) = view.tally_symbols;
+    let mut stats = format!(
+        "Today: {}  {}/{}",
+        analytics::render_tally(view.today_pomodoros, view.daily_goal, filled, empty),
+        view.today_pomodoros,
+        view.daily_goal,
+    );
+    if let Some(pace) = view.pace {
+        stats.push_str(&format!("    {}", pace));
+    }
+    if let Some(cycle) = view.cycle {
+        stats.push_str(&format!("    Cycle: {}", format_cycle(cycle)));
+    }
+    let stats_para = Paragraph::new(stats)
+        .style(theme.text)
+        .block(Block::default().title("Statistics").borders(Borders::ALL));
+    
+    f.render_widget(stats_para, chunks[3]);
+    
+    // Recent history
+    let items: Vec<ListItem> = history
+        .items
+        .iter()
+        .map(|entry| ListItem::new(entry.as_str()))
+        .collect();
+    
+    let history_list = List::new(items)
+        .style(theme.text)
+        .block(Block::default().title("History").borders(Borders::ALL))
+        .highlight_style(theme.highlight);
+    
+    history.page_size = chunks[4].height.saturating_sub(2) as usize;
+    f.render_stateful_widget(history_list, chunks[4], &mut history.state);
+    
+    // Help
+    let key = |k: &'static str| Span::styled(k, Style::default().add_modifier(Modifier::BOLD));
+    // During a forced rest the skip key does nothing, so say why in its place
+    let (skip_key, skip_text) = if view.rest_required {
+        ("Rest required", ", no skipping this break, ")
+    } else {
+        ("s", " to skip a break, ")
+    };
+    let help = Line::from(vec![
+        Span::raw("Press "),
+        key("p"),
+        Span::raw(" to pause/resume, "),
+        key("r"),
+        Span::raw(" to reset, "),
+        key(skip_key),
+        Span::raw(skip_text),
+        key("x"),
+        Span::raw(" to not count this session, "),
+        key("+/-"),
+        Span::raw(" to adjust work time, "),
+        key("↑/↓"),
+        Span::raw(" to scroll history, "),
+        key("o"),
+        Span::raw(" for today's overview, "),
+        key("q"),
+        Span::raw(" to quit"),
+    ]);
+    
+    // Name the accessibility modes in use, so they're not only implied by color
+    let help_title = if view.modes.is_empty() {
+        "Help".to_string()
+    } else {
+        format!("Help · {}", view.modes.join(", "))
+    };
+    
+    let help_para = Paragraph::new(help)
+        .style(theme.text)
+        .block(Block::default().title(help_title).borders(Borders::ALL));
+    
+    f.render_widget(help_para, chunks[5]);
+    
+    if let Some(text) = view.overview {
+        draw_overlay(f, "📅 Today · press any key to close", text);
+    }
+}
+
+/// Formats the cycle time as "1h12m", or "12m" under an hour.
+fn format_cycle(cycle: Duration) -> String {
+    let minutes = cycle.as_secs() / 60;
+    if minutes >= 60 {
+        format!("{}h{:02}m", minutes / 60, minutes % 60)
+    } else {
+        format!("{}m", minutes)
+    }
+}
+
+/// Draws a read-only view of a timer running in another process, asking
+/// to press x again when `confirm_stop` is set.
+pub fn draw_attached<B: Backend>(f: &mut Frame<B>, status: &Status, confirm_stop: bool, theme: &Theme) {
+    let chunks = Layout::default()
+        .direction(Direction::Vertical)
+        .margin(2)
+        .constraints([Constraint::Length(3), Constraint::Length(3), Constraint::Length(3)].as_ref())
+        .split(f.size());
+    
+    let mut title = format!("{} · {} (pid {})", status.phase, status.state, status.pid);
+    if let Some(tag) = &status.tag {
+        title.push_str(&format!(" · {}", tag));
+    }
+    f.render_widget(Block::default().title(title).borders(Borders::ALL), chunks[0]);
+    
+    // Flow sessions have no end, so count up on a full gauge
+    let (ratio, label) = match status.remaining_secs {
+        Some(remaining) => {
+            let total = status.elapsed_secs + remaining;
+            let ratio = if total > 0 { status.elapsed_secs as f64 / total as f64 } else { 1.0 };
+            (ratio, format_remaining(remaining))
+        }
+        None => (1.0, format!("{} ↑", format_remaining(status.elapsed_secs))),
+    };
+    let gauge = Gauge::default()
+        .block(Block::default().borders(Borders::ALL))
+        .gauge_style(if status.phase == "break" { theme.rest } else { theme.work })
+        .ratio(ratio)
+        .label(label);
+    
+    f.render_widget(gauge, chunks[1]);
+    
+    let (stop_hint, stop_tail) = if confirm_stop {
+        (" to detach, or ", " again to stop the timer")
+    } else {
+        (" to detach, ", " to stop the timer")
+    };
+    let footer = Paragraph::new(Line::from(vec![
+        Span::raw(format!("{} pomodoros    Press ", status.completed_pomodoros)),
+        Span::styled("q", Style::default().add_modifier(Modifier::BOLD)),
+        Span::raw(stop_hint),
+        Span::styled("x", Style::default().add_modifier(Modifier::BOLD)),
+        Span::raw(stop_tail),
+    ]))
+    .block(Block::default().borders(Borders::ALL));
+    
+    f.render_widget(footer, chunks[2]);
+}
+
+/// Draws `text` in a box over the middle of the screen, hiding what's beneath.
+pub fn draw_overlay<B: Backend>(f: &mut Frame<B>, title: &str, text: &str) {
+    let width = text.lines().map(|line| line.chars().count()).max().unwrap_or(0) as u16 + 4;
+    let height = text.lines().count() as u16 + 4;
+    let area = centered_rect(width, height, f.size());
+    
+    let popup = Paragraph::new(text.to_string())
+        .block(
+            Block::default()
+                .title(title.to_string())
+                .title_alignment(tui::layout::Alignment::Center)
+                .borders(Borders::ALL),
+        )
+        .style(Style::default().fg(Color::White));
+    
+    f.render_widget(Clear, area);
+    f.render_widget(popup, area);
+}
+
+/// A `width` x `height` rectangle centered in `area`, shrunk to fit.
+fn centered_rect(width: u16, height: u16, area: Rect) -> Rect {
+    let width = width.min(area.width);
+    let height = height.min(area.height);
+    
+    Rect::new(
+        area.x + (area.width - width) / 2,
+        area.y + (area.height - height) / 2,
+        width,
+        height,
+    )
+}
+
+/// Asks how focused the user felt during the work session that just ended.
+///
+/// Accepts a single digit from 1 to 5. Enter confirms, and pressing Enter
+/// without a value (or Esc) skips the rating.
+pub fn prompt_energy<B: Backend>(terminal: &mut Terminal<B>) -> io::Result<Option<u8>> {
+    let mut rating: Option<u8> = None;
+    
+    loop {
+        let value = rating.map(|r| r.to_string()).unwrap_or_default();
+        draw_prompt(
+            terminal,
+            "How focused did you feel? (1-5)",
+            &value,
+            "Type 1-5 and press Enter, or press Enter to skip",
+        )?;
+        
+        if let Event::Key(key) = event::read()? {
+            match key.code {
+                KeyCode::Char(c @ '1'..='5') => rating = c.to_digit(10).map(|d| d as u8),
+                KeyCode::Backspace => rating = None,
+                KeyCode::Enter => return Ok(rating),
+                KeyCode::Esc => return Ok(None),
+                _ => {}
+            }
+        }
+    }
+}
+
+/// Asks what the coming work session is for, starting from `current` so
+/// the last intention can be kept with Enter. Clearing it, or Esc, means
+/// no intention.
+pub fn prompt_intention<B: Backend>(terminal: &mut Terminal<B>, current: Option<&str>) -> io::Result<Option<String>> {
+    let mut text = current.unwrap_or_default().to_string();
+    
+    loop {
+        draw_prompt(
+            terminal,
+            "What do you intend to get done this session?",
+            &text,
+            "Type one line and press Enter, or press Esc to go without",
+        )?;
+        
+        if let Event::Key(key) = event::read()? {
+            match key.code {
+                KeyCode::Char(c) => text.push(c),
+                KeyCode::Backspace => {
+                    text.pop();
+                }
+                KeyCode::Enter => {
+                    let text = text.trim();
+                    return Ok((!text.is_empty()).then(|| text.to_string()));
+                }
+                KeyCode::Esc => return Ok(None),
+                _ => {}
+            }
+        }
+    }
+}
+
+/// Asks whether the work session that just ended achieved `intention`:
+/// y or n, with Enter or Esc skipping the question.
+pub fn prompt_intention_met<B: Backend>(terminal: &mut Terminal<B>, intention: &str) -> io::Result<Option<bool>> {
+    let mut answer: Option<bool> = None;
+    
+    loop {
+        let value = match answer {
+            Some(true) => "yes",
+            Some(false) => "no",
+            None => "",
+        };
+        draw_prompt(
+            terminal,
+            &format!("Did you manage it? {}", intention),
+            value,
+            "Press y or n, then Enter; Enter alone skips",
+        )?;
+        
+        if let Event::Key(key) = event::read()? {
+            match key.code {
+                KeyCode::Char('y' | 'Y') => answer = Some(true),
+                KeyCode::Char('n' | 'N') => answer = Some(false),
+                KeyCode::Backspace => answer = None,
+                KeyCode::Enter => return Ok(answer),
+                KeyCode::Esc => return Ok(None),
+                _ => {}
+            }
+        }
+    }
+}
+
+/// Draws a one-line input box titled `title` holding `value`, with `help` below.
+fn draw_prompt<B: Backend>(terminal: &mut Terminal<B>, title: &str, value: &str, help: &str) -> io::Result<()> {
+    terminal.draw(|f| {
+        let chunks = Layout::default()
+            .direction(Direction::Vertical)
+            .margin(2)
+            .constraints([Constraint::Length(3), Constraint::Min(0)].as_ref())
+            .split(f.size());
+        
+        let input = Paragraph::new(Line::from(vec![
+            Span::raw("> "),
+            Span::styled(value, Style::default().add_modifier(Modifier::BOLD)),
+        ]))
+        .block(Block::default().title(title).borders(Borders::ALL));
+        
+        f.render_widget(input, chunks[0]);
+        
+        let help = Paragraph::new(help).block(Block::default().title("Help").borders(Borders::ALL));
+        
+        f.render_widget(help, chunks[1]);
+    })?;
+    
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tui::backend::TestBackend;
+    
+    fn entries(n: usize) -> Vec<String> {
+        (0..n).map(|i| format!("session {}", i)).collect()
+    }
+    
+    #[test]
+    fn test_debounce_ignores_repeats_of_the_same_key() {
+        let start = Instant::now();
+        let ms = Duration::from_millis;
+        let mut keys = KeyDebounce::new(ms(150));
+        
+        assert!(keys.accept('p', start));
+        // Key repeat while held: ignored for as long as it keeps coming
+        assert!(!keys.accept('p', start + ms(40)));
+        assert!(!keys.accept('p', start + ms(180)));
+        assert!(keys.accept('p', start + ms(330)));
+        
+        // Other keys have their own window
+        assert!(keys.accept('r', start + ms(340)));
+    }
+    
+    #[test]
+    fn test_history_scroll_stays_in_bounds() {
+        let mut history = HistoryPanel::new(entries(12));
+        history.page_size = 5;
+        
+        history.scroll(-1);
+        assert_eq!(history.state.selected(), Some(0));
+        
+        history.page_down();
+        history.page_down();
+        history.page_down();
+        assert_eq!(history.state.selected(), Some(11));
+        
+        history.page_up();
+        assert_eq!(history.state.selected(), Some(6));
+        
+        // Shrinking the list clamps the selection
+        history.set_items(entries(3));
+        assert_eq!(history.state.selected(), Some(2));
+        
+        history.set_items(Vec::new());
+        assert_eq!(history.state.selected(), None);
+    }
+    
+    #[test]
+    fn test_centered_rect_fits_area() {
+        let area = Rect::new(0, 0, 80, 24);
+        assert_eq!(centered_rect(40, 10, area), Rect::new(20, 7, 40, 10));
+        
+        // Larger than the screen: clamp to it
+        assert_eq!(centered_rect(100, 30, area), area);
+    }
+    
+    #[test]
+    fn test_high_contrast_theme_has_no_dim_styles() {
+        let theme = Theme::new(true, true);
+        
+        for style in [theme.work, theme.rest, theme.text, theme.highlight] {
+            assert!(!style.add_modifier.contains(Modifier::DIM));
+            assert!(style.fg.is_some());
+        }
+    }
+    
+    /// Renders the timer view into an in-memory terminal and returns its rows.
+    fn render_timer(view: &TimerView) -> Vec<String> {
+        let mut terminal = Terminal::new(TestBackend::new(100, 30)).unwrap();
+        let mut history = HistoryPanel::new(entries(3));
+        terminal.draw(|f| draw_timer(f, view, &mut history)).unwrap();
+        screen_rows(&terminal)
+    }
+    
+    fn screen_rows(terminal: &Terminal<TestBackend>) -> Vec<String> {
+        let buffer = terminal.backend().buffer();
+        (0..buffer.area.height)
+            .map(|y| (0..buffer.area.width).map(|x| buffer.get(x, y).symbol.as_str()).collect())
+            .collect()
+    }
+    
+    fn view<'a>(timer: &'a PomodoroTimer, theme: &'a Theme) -> TimerView<'a> {
+        static GAUGE_LABEL: std::sync::OnceLock<GaugeLabel> = std::sync::OnceLock::new();
+        
+        TimerView {
+            timer,
+            work_mins: 25,
+            open_ended: false,
+            intention: None,
+            gauge_label: GAUGE_LABEL.get_or_init(GaugeLabel::default),
+            gauge_direction: GaugeDirection::Fill,
+            locked: false,
+            invalidated: false,
+            rest_required: false,
+            flash: None,
+            cycle: None,
+            clock: NaiveTime::from_hms_opt(9, 30, 0).unwrap(),
+            today_pomodoros: 2,
+            daily_goal: 4,
+            pace: None,
+            tally_symbols: ("#", "."),
+            modes: Vec::new(),
+            overview: None,
+            theme,
+        }
+    }
+    
+    #[test]
+    fn test_timer_view_shows_phase_time_and_status() {
+        let timer = PomodoroTimer::new(Duration::from_secs(25 * 60), Duration::from_secs(5 * 60));
+        let theme = Theme::new(false, true);
+        let screen = render_timer(&TimerView { pace: Some(analytics::Pace::Behind(2)), ..view(&timer, &theme) }).join("\n");
+        
+        assert!(screen.contains("Work Session (25m)"));
+        assert!(screen.contains("25:00"));
+        assert!(screen.contains("Status · 09:30"));
+        assert!(screen.contains("Stopped"));
+        assert!(screen.contains("Today: ##..  2/4    Behind by 2"));
+        assert!(screen.contains("session 0"));
+        assert!(screen.contains("┌Help─"));
+    }
+    
+    #[test]
+    fn test_timer_view_shows_projected_break_when_extended() {
+        let mut timer = PomodoroTimer::new(Duration::from_secs(25 * 60), Duration::from_secs(5 * 60));
+        timer.set_break_scaling(crate::timer::BreakScaling::Proportional);
+        timer.extend_work(Duration::from_secs(2 * 60));
+        let theme = Theme::new(false, true);
+        let screen = render_timer(&view(&timer, &theme)).join("\n");
+        
+        assert!(screen.contains("27:00"));
+        assert!(screen.contains("Stopped   +2m, then a 05:24 break"));
+    }
+    
+    #[test]
+    fn test_timer_view_shows_intention_during_work() {
+        let mut timer = PomodoroTimer::new(Duration::from_secs(25 * 60), Duration::from_secs(5 * 60));
+        let theme = Theme::new(false, true);
+        let with_intention = |timer: &PomodoroTimer| {
+            render_timer(&TimerView { intention: Some("Finish the API docs"), ..view(timer, &theme) }).join("\n")
+        };
+        
+        assert!(with_intention(&timer).contains("Work Session (25m) · Finish the API docs"));
+        timer.switch_to_break();
+        assert!(!with_intention(&timer).contains("Finish the API docs"));
+    }
+    
+    #[test]
+    fn test_timer_view_help_says_when_rest_is_required() {
+        let mut timer = PomodoroTimer::new(Duration::from_secs(25 * 60), Duration::from_secs(5 * 60));
+        timer.switch_to_break();
+        let theme = Theme::new(false, true);
+        
+        assert!(render_timer(&view(&timer, &theme)).join("\n").contains("s to skip a break"));
+        let screen = render_timer(&TimerView { rest_required: true, ..view(&timer, &theme) }).join("\n");
+        assert!(screen.contains("r to reset, Rest required, no skipping this break"));
+    }
+    
+    #[test]
+    fn test_timer_view_flow_lock_and_modes() {
+        let mut timer = PomodoroTimer::new(Duration::from_secs(25 * 60), Duration::from_secs(5 * 60));
+        timer.start();
+        timer.pause();
+        let theme = Theme::new(true, true);
+        let screen = render_timer(&TimerView {
+            open_ended: true,
+            locked: true,
+            invalidated: true,
+            flash: Some("Stretch!"),
+            cycle: Some(Duration::from_secs(72 * 60)),
+            modes: vec!["high contrast"],
+            overview: Some("2/4 pomodoros"),
+            ..view(&timer, &theme)
+        })
+        .join("\n");
+        
+        assert!(screen.contains("Flow Session"));
+        assert!(screen.contains("00:00 ↑"));
+        assert!(screen.contains("Paused (screen locked)   ✗ Invalidated, won't count   Stretch!"));
+        assert!(screen.contains("Help · high contrast"));
+        assert!(screen.contains("Cycle: 1h12m"));
+        assert!(screen.contains("2/4 pomodoros"));
+    }
+    
+    #[test]
+    fn test_attached_view_shows_remaining_time() {
+        let status = Status {
+            pid: 42,
+            phase: "work".to_string(),
+            state: "running".to_string(),
+            elapsed_secs: 60,
+            remaining_secs: Some(24 * 60),
+            completed_pomodoros: 3,
+            tag: Some("docs".to_string()),
+            updated_at: chrono::Local::now(),
+        };
+        
+        let mut terminal = Terminal::new(TestBackend::new(80, 15)).unwrap();
+        terminal.draw(|f| draw_attached(f, &status, false, &Theme::new(false, true))).unwrap();
+        let screen = screen_rows(&terminal).join("\n");
+        
+        assert!(screen.contains("work · running (pid 42) · docs"));
+        assert!(screen.contains("24:00"));
+        assert!(screen.contains("3 pomodoros"));
+        assert!(screen.contains("x to stop the timer"));
+        
+        terminal.draw(|f| draw_attached(f, &status, true, &Theme::new(false, true))).unwrap();
+        assert!(screen_rows(&terminal).join("\n").contains("q to detach, or x again to stop the timer"));
+    }
+    
+    #[test]
+    fn test_monochrome_theme_has_no_colors() {
+        for high_contrast in [false, true] {
+            let theme = Theme::new(high_contrast, false);
+            for style in [theme.work, theme.rest, theme.text, theme.highlight] {
+                assert_eq!((style.fg, style.bg), (None, None));
+            }
+        }
+    }
+    
+    #[test]
+    fn test_format_cycle() {
+        assert_eq!(format_cycle(Duration::from_secs(59)), "0m");
+        assert_eq!(format_cycle(Duration::from_secs(12 * 60 + 30)), "12m");
+        assert_eq!(format_cycle(Duration::from_secs(72 * 60)), "1h12m");
+    }
+    
+    #[test]
+    fn test_dashboard_shows_week_with_today_marked() {
+        let days = ["Mon", "Tue", "Wed", "Thu", "Fri", "Sat", "Sun"];
+        let data = Dashboard {
+            today_pomodoros: 3,
+            daily_goal: 4,
+            week_pomodoros: 9,
+            weekly_goal: 40,
+            streak: 2,
+            last_seven_days: days.iter().map(|d| (d.to_string(), 1)).collect(),
+            this_week: days.iter().zip([4, 2, 3, 0, 0, 0, 0]).map(|(d, n)| (d.to_string(), n)).collect(),
+            today_index: 2,
+        };
+        
+        let mut terminal = Terminal::new(TestBackend::new(100, 30)).unwrap();
+        terminal.draw(|f| draw_dashboard(f, &data, &Theme::new(false, true))).unwrap();
+        let screen = screen_rows(&terminal).join("\n");
+        
+        assert!(screen.contains("┌Mon"));
+        assert!(screen.contains("┌▶Wed"));
+        assert!(screen.contains("┌Sun"));
+        
+        assert_eq!(goal_ratio(2, 4), 0.5);
+        assert_eq!(goal_ratio(6, 4), 1.0);
+        assert_eq!(goal_ratio(0, 0), 1.0);
+    }
+    
+    #[test]
+    fn test_gauge_label_template() {
+        let mut timer = PomodoroTimer::new(Duration::from_secs(25 * 60), Duration::from_secs(5 * 60));
+        timer.switch_to_break();
+        
+        let label = GaugeLabel::parse("{remaining} left of {total} ({percent}%)").unwrap();
+        assert_eq!(label.render(&timer), "05:00 left of 05:00 (0%)");
+        assert_eq!(GaugeLabel::parse("{elapsed}").unwrap().render(&timer), "00:00");
+        assert_eq!(GaugeLabel::default(), GaugeLabel::parse("{remaining}").unwrap());
+        assert_eq!(GaugeLabel::parse("no tokens").unwrap().render(&timer), "no tokens");
+        
+        let zero = PomodoroTimer::new(Duration::ZERO, Duration::ZERO);
+        assert_eq!(GaugeLabel::parse("{percent}%").unwrap().render(&zero), "100%");
+        
+        let theme = Theme::new(false, true);
+        let screen = render_timer(&TimerView { gauge_label: &label, ..view(&timer, &theme) }).join("\n");
+        assert!(screen.contains("05:00 left of 05:00 (0%)"));
+    }
+    
+    #[test]
+    fn test_gauge_label_rejects_bad_templates() {
+        assert_eq!(
+            GaugeLabel::parse("{remainig} left").unwrap_err(),
+            "unknown token '{remainig}', did you mean '{remaining}'?"
+        );
+        assert!(GaugeLabel::parse("{x}").unwrap_err().contains("(available: {remaining}, {elapsed}, {total}, {percent})"));
+        assert!(GaugeLabel::parse("{remaining").unwrap_err().contains("without a closing"));
+    }
+    
+    #[test]
+    fn test_gauge_direction() {
+        assert_eq!(GaugeDirection::Fill.ratio(0.25), 0.25);
+        assert_eq!(GaugeDirection::Drain.ratio(0.25), 0.75);
+        assert_eq!(GaugeDirection::Drain.ratio(1.0), 0.0);
+        
+        let config: crate::config::Config = toml::from_str("gauge_direction = \"drain\"").unwrap();
+        assert_eq!(config.gauge_direction, GaugeDirection::Drain);
+    }
+}
\ No newline at end of file
@@ -0,0 +1,102 @@
+/// Longest tag taken from a window title
+const MAX_TAG_CHARS: usize = 60;
+
+/// Reports what the user is looking at, for automatic session tags.
+pub trait WindowInfo {
+    /// Title or application name of the foreground window, if it can be read.
+    fn active_window(&self) -> Option<String>;
+}
+
+/// Fallback for platforms without window detection.
+#[cfg(not(any(target_os = "linux", target_os = "macos")))]
+pub struct NoWindowInfo;
+
+#[cfg(not(any(target_os = "linux", target_os = "macos")))]
+impl WindowInfo for NoWindowInfo {
+    fn active_window(&self) -> Option<String> {
+        None
+    }
+}
+
+/// Returns the window detector for the current platform.
+pub fn detector() -> Box<dyn WindowInfo> {
+    #[cfg(target_os = "linux")]
+    {
+        Box::new(platform::XdotoolWindowInfo)
+    }
+    
+    #[cfg(target_os = "macos")]
+    {
+        Box::new(platform::FrontmostAppInfo)
+    }
+    
+    #[cfg(not(any(target_os = "linux", target_os = "macos")))]
+    {
+        Box::new(NoWindowInfo)
+    }
+}
+
+/// Turns a raw window title into a tag: trimmed, non-empty and bounded in length.
+pub fn clean_tag(raw: &str) -> Option<String> {
+    let trimmed = raw.trim();
+    if trimmed.is_empty() {
+        return None;
+    }
+    
+    Some(trimmed.chars().take(MAX_TAG_CHARS).collect::<String>().trim_end().to_string())
+}
+
+#[cfg(any(target_os = "linux", target_os = "macos"))]
+fn run(program: &str, args: &[&str]) -> Option<String> {
+    let output = std::process::Command::new(program).args(args).output().ok()?;
+    if !output.status.success() {
+        return None;
+    }
+    
+    clean_tag(&String::from_utf8_lossy(&output.stdout))
+}
+
+#[cfg(target_os = "linux")]
+mod platform {
+    use super::{run, WindowInfo};
+    
+    /// Reads the focused X11 window's title with `xdotool`.
+    pub struct XdotoolWindowInfo;
+    
+    impl WindowInfo for XdotoolWindowInfo {
+        fn active_window(&self) -> Option<String> {
+            run("xdotool", &["getactivewindow", "getwindowname"])
+        }
+    }
+}
+
+#[cfg(target_os = "macos")]
+mod platform {
+    use super::{run, WindowInfo};
+    
+    /// Asks System Events for the name of the frontmost application.
+    pub struct FrontmostAppInfo;
+    
+    impl WindowInfo for FrontmostAppInfo {
+        fn active_window(&self) -> Option<String> {
+            run(
+                "osascript",
+                &["-e", "tell application \"System Events\" to get name of first application process whose frontmost is true"],
+            )
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    
+    #[test]
+    fn test_clean_tag() {
+        assert_eq!(clean_tag("  Firefox\n"), Some("Firefox".to_string()));
+        assert_eq!(clean_tag(" \n"), None);
+        
+        let long = "a".repeat(59) + " trailing words";
+        assert_eq!(clean_tag(&long), Some("a".repeat(59)));
+    }
+}
\ No newline at end of file